@@ -0,0 +1,16 @@
+use bufflib::dependencies;
+use bufflib::manifest;
+use quicli::prelude::*;
+use std::path::Path;
+
+pub fn execute(manifest_path: Option<&Path>, package: &str, registry_url: Option<&str>) {
+  let added = dependencies::add(
+    &manifest::find_manifest(manifest_path),
+    package,
+    registry_url,
+  );
+  info!(
+    "Added {} = \"{}\" (locked to {})",
+    added.name, added.requirement, added.version
+  );
+}
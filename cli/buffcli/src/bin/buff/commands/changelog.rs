@@ -0,0 +1,48 @@
+use bufflib::buff_cli_config::BuffCliConfig;
+use bufflib::changelog::{self, Changelog};
+use bufflib::manifest;
+use std::path::Path;
+
+pub fn execute(
+  manifest_path: Option<&Path>,
+  package: Option<&str>,
+  range: Option<&str>,
+  json: bool,
+) {
+  let changelog = match (package, range) {
+    (Some(package), Some(range)) => {
+      let (from, to) = changelog::parse_range(range).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+      });
+      let registry_url = BuffCliConfig::new().preferred_registry;
+      changelog::between_versions(&registry_url, package, &from, &to)
+    }
+    (Some(package), None) => {
+      eprintln!(
+        "Expected a range of versions after `{}`, e.g. 1.0.0..1.1.0",
+        package
+      );
+      std::process::exit(1);
+    }
+    _ => {
+      let manifest_path = manifest::find_manifest(manifest_path);
+      match changelog::since_published(&manifest_path) {
+        Some(changelog) => changelog,
+        None => {
+          eprintln!("No published version to compare the working tree to");
+          std::process::exit(1);
+        }
+      }
+    }
+  };
+  print(&changelog, json);
+}
+
+fn print(changelog: &Changelog, json: bool) {
+  if json {
+    println!("{}", changelog.to_json());
+  } else {
+    print!("{}", changelog.to_markdown());
+  }
+}
@@ -0,0 +1,64 @@
+use bufflib::compat;
+use bufflib::compat::Baseline;
+use bufflib::manifest;
+use bufflib::workspace;
+use quicli::prelude::*;
+use std::path::Path;
+
+pub fn execute(manifest_path: Option<&Path>, all_members: bool, against: Baseline) {
+  let manifest_path = manifest::find_manifest(manifest_path);
+  let manifests = if all_members {
+    workspace::find_workspace(&manifest_path)
+      .unwrap_or_else(|| panic!("`{}` is not part of a workspace", manifest_path.display()))
+      .members
+      .into_iter()
+      .map(|member| member.manifest_path)
+      .collect()
+  } else {
+    vec![manifest_path]
+  };
+  let mut failed = false;
+  for manifest_path in manifests {
+    let result = compat::check_package(&manifest_path, &against);
+    match result.baseline {
+      None if against == Baseline::Registry => println!(
+        "{} {}: no published version to check against",
+        result.name, result.version
+      ),
+      None => println!(
+        "{} {}: not in {}, nothing to check against",
+        result.name, result.version, against
+      ),
+      Some(ref baseline) if result.major_bump => println!(
+        "{} {}: major bump over {}, breaking changes are allowed",
+        result.name, result.version, baseline
+      ),
+      Some(ref baseline) if result.violations.is_empty() => println!(
+        "{} {}: compatible with {}",
+        result.name, result.version, baseline
+      ),
+      Some(ref baseline) => {
+        failed = true;
+        println!(
+          "{} {}: {} breaking change(s) against {}",
+          result.name,
+          result.version,
+          result.violations.len(),
+          baseline
+        );
+        for violation in &result.violations {
+          println!("  {}", violation);
+        }
+      }
+    }
+    for violation in &result.suppressed {
+      info!("Ignoring {}", violation);
+    }
+    for stale in &result.stale_suppressions {
+      println!("  warning: {}", stale);
+    }
+  }
+  if failed {
+    std::process::exit(1);
+  }
+}
@@ -0,0 +1,34 @@
+use bufflib::artifact::Artifact;
+use bufflib::compat::{self, Rejection};
+use std::path::Path;
+
+/// Exit code for breaking changes, printed as JSON.
+const BREAKING: i32 = 1;
+/// Exit code for artifacts that can't be checked, with the reason printed.
+const INVALID: i32 = 2;
+
+pub fn execute(artifact: &Path, baseline: Option<&Path>) {
+  let artifact = Artifact::open(artifact);
+  let baseline = baseline.map(Artifact::open);
+  let published: Vec<String> = match baseline.as_ref().map(Artifact::read_metadata) {
+    Some(Ok(metadata)) => vec![metadata.package.version],
+    Some(Err(problem)) => {
+      println!("the baseline is invalid: {}", problem);
+      std::process::exit(INVALID);
+    }
+    None => Vec::new(),
+  };
+  match compat::check_publish(&published, &artifact, |_| {
+    baseline.expect("Nothing to check against")
+  }) {
+    Ok(()) => (),
+    Err(Rejection::Breaking(breaking)) => {
+      println!("{}", breaking.to_json());
+      std::process::exit(BREAKING);
+    }
+    Err(Rejection::Invalid(problem)) => {
+      println!("{}", problem);
+      std::process::exit(INVALID);
+    }
+  }
+}
@@ -0,0 +1,61 @@
+use ansi_term::{Colour, Style};
+use bufflib::buff_cli_config::BuffCliConfig;
+use bufflib::diff::{Change, ChangeKind, Diff, Target};
+
+pub fn execute(from: &Target, to: &Target, json: bool) {
+  let registry_url = BuffCliConfig::new().preferred_registry;
+  let diff = Diff::new(from, to, &registry_url);
+  if json {
+    println!("{}", diff.to_json());
+    return;
+  }
+  let color = atty::is(atty::Stream::Stdout);
+  let paint = |style: Style, text: &str| {
+    if color {
+      style.paint(text).to_string()
+    } else {
+      text.to_string()
+    }
+  };
+  println!(
+    "{}",
+    paint(Style::new().bold(), &format!("--- {}", diff.from))
+  );
+  println!(
+    "{}",
+    paint(Style::new().bold(), &format!("+++ {}", diff.to))
+  );
+  if diff.changes.is_empty() {
+    println!("No changes to the schema");
+    return;
+  }
+  for change in &diff.changes {
+    let (sign, colour) = match change.kind {
+      ChangeKind::Added => ('+', Colour::Green),
+      ChangeKind::Removed => ('-', Colour::Red),
+      ChangeKind::Renamed | ChangeKind::Moved => ('>', Colour::Cyan),
+      ChangeKind::Changed => ('~', Colour::Yellow),
+    };
+    let mut line = paint(
+      Style::from(colour),
+      &format!("{} {}", sign, describe(change)),
+    );
+    if change.is_breaking() {
+      line.push_str(&paint(
+        Colour::Red.bold(),
+        &format!(" [breaking: {}]", change.breaking.join(", ")),
+      ));
+    }
+    println!("{}", line);
+    for detail in &change.details {
+      println!("    {}", detail);
+    }
+  }
+}
+
+fn describe(change: &Change) -> String {
+  match change.to {
+    Some(ref to) => format!("{} {} -> {}", change.element, change.path, to),
+    None => format!("{} {}", change.element, change.path),
+  }
+}
@@ -0,0 +1,32 @@
+use bufflib::fix;
+use bufflib::manifest;
+use std::path::Path;
+
+pub fn execute(manifest_path: Option<&Path>, check: bool) {
+  let manifest_path = manifest::find_manifest(manifest_path);
+  let reservations = match fix::fix_package(&manifest_path, check) {
+    Some(reservations) => reservations,
+    None => {
+      println!("No published version to compare to, nothing to fix");
+      return;
+    }
+  };
+  if reservations.is_empty() {
+    println!("Every removed field and enum value is reserved");
+    return;
+  }
+  for reservation in &reservations {
+    println!("  {}", reservation);
+  }
+  if check {
+    println!(
+      "{} message(s) and enum(s) need `reserved` statements, run `buff fix` to add them",
+      reservations.len()
+    );
+    std::process::exit(1);
+  }
+  println!(
+    "Added `reserved` statements to {} message(s) and enum(s)",
+    reservations.len()
+  );
+}
@@ -0,0 +1,37 @@
+use bufflib::fmt;
+use bufflib::manifest;
+use std::path::Path;
+
+pub fn execute(manifest_path: Option<&Path>, check: bool, diff: bool) {
+  let manifest_path = manifest::find_manifest(manifest_path);
+  let unformatted = match fmt::format_package(&manifest_path, check || diff) {
+    Ok(unformatted) => unformatted,
+    Err(err) => {
+      eprintln!("Failed to parse {}", err);
+      std::process::exit(1);
+    }
+  };
+  if unformatted.is_empty() {
+    if !diff {
+      println!("Every .proto file is formatted");
+    }
+    return;
+  }
+  for file in &unformatted {
+    if diff {
+      print!("{}", file.diff());
+    } else {
+      println!("  {}", file.path.display());
+    }
+  }
+  if check {
+    println!(
+      "{} file(s) aren't formatted, run `buff fmt` to format them",
+      unformatted.len()
+    );
+    std::process::exit(1);
+  }
+  if !diff {
+    println!("Formatted {} file(s)", unformatted.len());
+  }
+}
@@ -0,0 +1,91 @@
+use bufflib::artifact::Artifact;
+use bufflib::buff_cli_config::BuffCliConfig;
+use bufflib::provenance::{self, Statement};
+use bufflib::registry;
+use bufflib::signing;
+
+pub fn execute(package: &str, show_provenance: bool) {
+  let (name, version) = match package.find('@') {
+    Some(at) => (&package[..at], &package[at + 1..]),
+    None => {
+      eprintln!("Expected <package>@<version>, got `{}`", package);
+      std::process::exit(1);
+    }
+  };
+  let config = BuffCliConfig::new();
+  let registry_url = &config.preferred_registry;
+  let trusted = config.trust.keys_for(registry_url, name);
+  let downloaded =
+    registry::download(registry_url, name, version).expect("Failed gRPC download call");
+  let artifact = Artifact::from_bytes(downloaded.artifact);
+  let sha256 = artifact.sha256();
+  let metadata = artifact.metadata();
+  println!("{} {}", metadata.package.name, metadata.package.version);
+  if !metadata.package.description.is_empty() {
+    println!("{}", metadata.package.description);
+  }
+  println!("sha256 {}", sha256);
+  match signing::check_signature(trusted.as_ref(), &sha256, downloaded.signature.as_ref()) {
+    Ok(Some(signer)) => println!("Signed by {}", signer),
+    Ok(None) => println!("Not signed"),
+    Err(err) => {
+      eprintln!("{}", err);
+      std::process::exit(1);
+    }
+  }
+  if !show_provenance {
+    return;
+  }
+  println!();
+  match provenance::verify(
+    downloaded.provenance.as_ref(),
+    trusted.as_ref(),
+    name,
+    version,
+    &sha256,
+  ) {
+    Ok((statement, signer)) => print_provenance(&statement, signer),
+    Err(err) => {
+      eprintln!("{}", err);
+      std::process::exit(1);
+    }
+  }
+}
+
+fn print_provenance(statement: &Statement, signer: String) {
+  let provenance = &statement.predicate;
+  println!("Provenance, signed by {}:", signer);
+  match &provenance.git {
+    Some(git) => {
+      println!(
+        "  commit     {}{}{}",
+        git.commit,
+        git
+          .branch
+          .as_ref()
+          .map(|branch| format!(" on {}", branch))
+          .unwrap_or_default(),
+        if git.dirty { " (dirty)" } else { "" }
+      );
+      if let Some(remote_url) = &git.remote_url {
+        println!("  remote     {}", remote_url);
+      }
+    }
+    None => println!("  commit     not published from a git repository"),
+  }
+  println!(
+    "  built on   {} with {}",
+    provenance.build_host, provenance.tool
+  );
+  println!(
+    "  published  {} by {}{}",
+    provenance.timestamp,
+    provenance.publisher.user,
+    provenance
+      .publisher
+      .email
+      .as_ref()
+      .map(|email| format!(" <{}>", email))
+      .unwrap_or_default()
+  );
+}
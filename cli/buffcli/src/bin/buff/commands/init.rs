@@ -0,0 +1,32 @@
+use bufflib::scaffold;
+use quicli::prelude::*;
+use std::path::PathBuf;
+
+pub fn execute(from_dir: Option<PathBuf>) {
+  let summary = match scaffold::init(
+    &std::env::current_dir().unwrap(),
+    from_dir.as_ref().map(|p| p.as_path()),
+  ) {
+    Ok(summary) => summary,
+    Err(err) => {
+      eprintln!("Failed to parse {}", err);
+      std::process::exit(1);
+    }
+  };
+  info!("Initialized package `{}`", summary.name);
+  for proto in &summary.protos {
+    trace!("Found {}", proto.display());
+  }
+  for (dependency, requirement) in &summary.dependencies {
+    println!("Added dependency {} = \"{}\"", dependency, requirement);
+  }
+  if let Some(ref err) = summary.registry_error {
+    warn!("Leaving imports unresolved, {}", err);
+  }
+  for import in &summary.unresolved_imports {
+    println!(
+      "Import \"{}\" does not match any local file or registry package",
+      import
+    );
+  }
+}
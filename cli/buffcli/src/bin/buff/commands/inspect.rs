@@ -0,0 +1,70 @@
+use bufflib::artifact::{Artifact, EntryKind};
+use std::path::Path;
+
+pub fn execute(path: &Path) {
+  let artifact = Artifact::open(path);
+  let metadata = artifact.metadata();
+  println!("{} {}", metadata.package.name, metadata.package.version);
+  if !metadata.package.description.is_empty() {
+    println!("{}", metadata.package.description);
+  }
+  println!();
+  let entries = artifact.entries().unwrap_or_else(|err| {
+    eprintln!("{}", err);
+    std::process::exit(1);
+  });
+  let width = entries
+    .iter()
+    .map(|entry| entry.size.to_string().len())
+    .max()
+    .unwrap_or(0);
+  let mut files = 0;
+  let mut unpacked_size = 0;
+  for entry in &entries {
+    match entry.kind {
+      EntryKind::File => {
+        files += 1;
+        unpacked_size += entry.size;
+        println!(
+          "{:>width$}  {}",
+          entry.size,
+          entry.path.display(),
+          width = width
+        );
+      }
+      EntryKind::Directory => (),
+      EntryKind::Symlink | EntryKind::Hardlink => {
+        println!(
+          "{:>width$}  {} (link)",
+          "",
+          entry.path.display(),
+          width = width
+        )
+      }
+      EntryKind::Other => println!(
+        "{:>width$}  {} (special file)",
+        "",
+        entry.path.display(),
+        width = width
+      ),
+    }
+  }
+  println!();
+  println!(
+    "{} files, {} bytes unpacked, {} bytes compressed",
+    files,
+    unpacked_size,
+    artifact.size()
+  );
+  match artifact.verify_digests() {
+    Ok(Some(manifest)) => println!(
+      "All files match .buff/manifest.json, built by {} with {} level {}",
+      manifest.tool, manifest.compression.format, manifest.compression.level
+    ),
+    Ok(None) => println!("No .buff/manifest.json, files can't be verified"),
+    Err(err) => {
+      eprintln!("{}", err);
+      std::process::exit(1);
+    }
+  }
+}
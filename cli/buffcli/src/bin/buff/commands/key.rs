@@ -0,0 +1,16 @@
+use bufflib::buff_cli_config;
+use bufflib::signing;
+
+pub fn generate(name: &str) {
+  let keys_dir = signing::keys_dir(&buff_cli_config::buff_home());
+  match signing::generate_key(&keys_dir, name) {
+    Ok(public_key) => {
+      println!("Generated key `{}` in {}", name, keys_dir.display());
+      println!("Public key: {}", public_key);
+    }
+    Err(err) => {
+      eprintln!("Failed to generate key: {}", err);
+      std::process::exit(1);
+    }
+  }
+}
@@ -0,0 +1,19 @@
+use bufflib::lint::{self, Format};
+use bufflib::manifest;
+use std::path::Path;
+
+pub fn execute(manifest_path: Option<&Path>, format: Format) {
+  let manifest_path = manifest::find_manifest(manifest_path);
+  let issues = lint::lint_package(&manifest_path);
+  if format == Format::Human && issues.is_empty() {
+    println!("No lint issues");
+    return;
+  }
+  print!("{}", lint::render(&issues, format));
+  if format != Format::Human {
+    println!();
+  }
+  if !issues.is_empty() {
+    std::process::exit(1);
+  }
+}
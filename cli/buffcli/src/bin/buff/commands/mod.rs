@@ -1,2 +1,18 @@
+pub mod add;
+pub mod changelog;
+pub mod check;
+pub mod check_publish;
+pub mod diff;
+pub mod fix;
+pub mod fmt;
+pub mod info;
+pub mod init;
+pub mod inspect;
+pub mod key;
+pub mod lint;
 pub mod login;
-pub mod publish;
\ No newline at end of file
+pub mod new;
+pub mod package;
+pub mod publish;
+pub mod remove;
+pub mod version;
@@ -0,0 +1,7 @@
+use bufflib::scaffold;
+use quicli::prelude::*;
+
+pub fn execute(name: &str) {
+  let path = scaffold::new_package(&std::env::current_dir().unwrap(), name);
+  info!("Created package `{}` at {}", name, path.display());
+}
@@ -0,0 +1,19 @@
+use bufflib::artifact;
+use bufflib::codec::Codec;
+use bufflib::manifest;
+use quicli::prelude::*;
+use std::path::{Path, PathBuf};
+
+pub fn execute(manifest_path: Option<&Path>, output: Option<PathBuf>, codec: Option<Codec>) {
+  let (path, digest) = artifact::package(
+    &manifest::find_manifest(manifest_path),
+    output.as_ref().map(PathBuf::as_path),
+    codec,
+  );
+  info!(
+    "Packaged {} ({} bytes, sha256 {})",
+    path.display(),
+    digest.size,
+    digest.sha256
+  );
+}
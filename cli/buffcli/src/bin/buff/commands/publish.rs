@@ -1,6 +1,110 @@
+use bufflib::manifest;
 use bufflib::registry;
+use bufflib::registry::{PublishOptions, PublishSummary, WorkspacePublishError};
+use bufflib::verify::Check;
+use bufflib::workspace;
 use quicli::prelude::*;
+use std::path::Path;
 
-pub fn execute() {
-  registry::publish()
+pub fn execute(manifest_path: Option<&Path>, all_members: bool, options: PublishOptions) {
+  let manifest_path = manifest::find_manifest(manifest_path);
+  let result = if all_members {
+    let workspace = workspace::find_workspace(&manifest_path)
+      .unwrap_or_else(|| panic!("`{}` is not part of a workspace", manifest_path.display()));
+    registry::publish_workspace(&workspace, &options)
+  } else {
+    registry::publish(&manifest_path, &options)
+      .map(|summary| vec![summary])
+      .map_err(|error| WorkspacePublishError {
+        published: Vec::new(),
+        error,
+      })
+  };
+  match result {
+    Ok(ref published) if published.is_empty() => {
+      info!("All workspace members are already published")
+    }
+    Ok(published) => {
+      for summary in &published {
+        report(summary, options.dry_run);
+      }
+    }
+    Err(WorkspacePublishError { published, error }) => {
+      for summary in &published {
+        report(summary, options.dry_run);
+      }
+      if !published.is_empty() {
+        let names: Vec<&str> = published
+          .iter()
+          .map(|summary| summary.name.as_str())
+          .collect();
+        warn!(
+          "Stopped at {} after publishing {}",
+          error.package,
+          names.join(", ")
+        );
+      }
+      eprintln!("{}", error);
+      match error.check {
+        Check::Clean => eprintln!("Commit your changes or pass --allow-dirty to publish anyway"),
+        Check::Version => eprintln!("Bump the version in buff.toml to publish the changes"),
+        Check::Registry => eprintln!(
+          "The registry refuses breaking changes within a major version, bump the major version or add them to `ignore` in [compatibility]"
+        ),
+        Check::Connection => eprintln!("Publish again once the registry can be reached"),
+        Check::Trust => eprintln!("Check the [trust] section of the CLI config"),
+        _ => eprintln!("Fix the problems above or pass --no-verify to publish anyway"),
+      }
+      std::process::exit(1);
+    }
+  }
+}
+
+fn report(summary: &PublishSummary, dry_run: bool) {
+  for check in &summary.verified {
+    trace!("{} passed the `{}` check", summary.name, check);
+  }
+  if let Some(requested) = summary.unsupported_codec {
+    warn!(
+      "{} doesn't support {} compression, used {} instead",
+      summary.registry_url,
+      requested.name(),
+      summary.codec
+    );
+  }
+  if let Some(git) = summary
+    .provenance
+    .as_ref()
+    .and_then(|statement| statement.predicate.git.as_ref())
+  {
+    trace!(
+      "Recorded provenance for {} at commit {}",
+      summary.name,
+      git.commit
+    );
+  }
+  if let Some(ref public_key) = summary.signed_by {
+    info!("Signed {} with key {}", summary.name, public_key);
+  }
+  if !dry_run {
+    info!("Published {} {}", summary.name, summary.version);
+    match &summary.tag {
+      Some(Ok(tag)) => info!("Tagged the release {}", tag),
+      Some(Err(err)) => warn!("Failed to tag the release: {}", err),
+      None => (),
+    }
+    return;
+  }
+  println!(
+    "Would publish {} {} to {} ({} bytes, {}, sha256 {}):",
+    summary.name,
+    summary.version,
+    summary.registry_url,
+    summary.artifact_size,
+    summary.codec,
+    summary.sha256
+  );
+  for file in &summary.files {
+    println!("  {}", file.display());
+  }
 }
@@ -0,0 +1,12 @@
+use bufflib::dependencies;
+use bufflib::manifest;
+use quicli::prelude::*;
+use std::path::Path;
+
+pub fn execute(manifest_path: Option<&Path>, package: &str) {
+  if dependencies::remove(&manifest::find_manifest(manifest_path), package) {
+    info!("Removed {}", package);
+  } else {
+    println!("{} is not a dependency of this package", package);
+  }
+}
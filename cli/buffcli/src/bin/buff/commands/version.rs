@@ -0,0 +1,60 @@
+use bufflib::manifest;
+use bufflib::package_metadata::PackageMetadata;
+use bufflib::version::{self, Bump, BumpOutcome};
+use std::path::Path;
+
+pub fn suggest(manifest_path: Option<&Path>) {
+  let manifest_path = manifest::find_manifest(manifest_path);
+  let metadata = PackageMetadata::new(manifest_path.to_str().unwrap());
+  match version::suggest_for_package(&manifest_path) {
+    None => println!(
+      "{}: no published version to compare against",
+      metadata.package.name
+    ),
+    Some((published, suggestion)) => {
+      println!(
+        "{}: {} bump over {}, to {}",
+        metadata.package.name,
+        suggestion.bump,
+        published,
+        version::next_version(&published, suggestion.bump)
+      );
+      for reason in &suggestion.reasons {
+        println!("  {}", reason);
+      }
+    }
+  }
+}
+
+pub fn bump(manifest_path: Option<&Path>, level: &str) {
+  let manifest_path = manifest::find_manifest(manifest_path);
+  let bump = if level == "auto" {
+    None
+  } else {
+    match level.parse::<Bump>() {
+      Ok(bump) => Some(bump),
+      Err(_) => {
+        eprintln!(
+          "unknown bump `{}`, expected major, minor, patch or auto",
+          level
+        );
+        std::process::exit(1);
+      }
+    }
+  };
+  let name = PackageMetadata::new(manifest_path.to_str().unwrap())
+    .package
+    .name;
+  match version::bump_package(&manifest_path, bump) {
+    BumpOutcome::Bumped { from, to } => println!("Bumped {} from {} to {}", name, from, to),
+    BumpOutcome::UpToDate {
+      version,
+      bump,
+      published,
+    } => println!(
+      "{} {} already covers a {} bump over {}",
+      name, version, bump, published
+    ),
+    BumpOutcome::NothingPublished => println!("{}: no published version to bump from", name),
+  }
+}
@@ -1,4 +1,10 @@
+use bufflib::codec::Codec;
+use bufflib::compat::Baseline;
+use bufflib::diff::Target;
+use bufflib::lint::Format;
+use bufflib::registry::PublishOptions;
 use quicli::prelude::*;
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 mod commands;
@@ -17,7 +23,216 @@ enum Command {
     name = "publish",
     about = "Publishes the package as configured in buff.toml"
   )]
-  Publish {},
+  Publish {
+    /// Publish every workspace member whose version isn't in the registry
+    /// yet, dependencies first, refusing members whose files changed since
+    /// their version was published
+    #[structopt(long = "workspace")]
+    workspace: bool,
+    /// Build the artifact and have the registry validate it without
+    /// publishing anything
+    #[structopt(long = "dry-run")]
+    dry_run: bool,
+    /// Skip the checks configured in the [publish] section of buff.toml
+    #[structopt(long = "no-verify")]
+    no_verify: bool,
+    /// Publish even if files in the package have uncommitted changes
+    #[structopt(long = "allow-dirty")]
+    allow_dirty: bool,
+    /// Artifact compression, e.g. gzip:9, zstd:19 or none, instead of the one
+    /// set in buff.toml. Falls back to gzip if the registry can't read it
+    #[structopt(long = "compression")]
+    compression: Option<Codec>,
+    /// Sign the artifact so consumers can check who published it, and record
+    /// its provenance
+    #[structopt(long = "sign")]
+    sign: bool,
+    /// Name of the key to sign with, as given to `buff key generate`
+    #[structopt(long = "key", default_value = "default")]
+    key: String,
+  },
+  #[structopt(
+    name = "package",
+    about = "Builds the package artifact without publishing it"
+  )]
+  Package {
+    /// Where to write the artifact, by default <name>-<version>.tar.gz in the
+    /// current directory
+    #[structopt(short = "o", long = "output", parse(from_os_str))]
+    output: Option<PathBuf>,
+    /// Artifact compression, e.g. gzip:9, zstd:19 or none, instead of the one
+    /// set in buff.toml
+    #[structopt(long = "compression")]
+    compression: Option<Codec>,
+  },
+  #[structopt(
+    name = "new",
+    about = "Creates a new package in a directory named <name>"
+  )]
+  New { name: String },
+  #[structopt(
+    name = "init",
+    about = "Creates a package in the current directory, adopting any existing .proto files"
+  )]
+  Init {
+    /// Copy .proto files from this directory into protos/ instead of using
+    /// the ones already in the current directory.
+    #[structopt(long = "from-dir", parse(from_os_str))]
+    from_dir: Option<PathBuf>,
+  },
+  #[structopt(
+    name = "add",
+    about = "Adds a dependency to buff.toml and locks it to the latest matching version"
+  )]
+  Add {
+    /// Package to add, optionally with a version requirement, e.g. yummy_package@1.2
+    package: String,
+    /// Registry to resolve the package from, defaults to the preferred registry
+    #[structopt(long = "registry")]
+    registry: Option<String>,
+  },
+  #[structopt(name = "remove", about = "Removes a dependency from buff.toml")]
+  Remove { package: String },
+  #[structopt(
+    name = "inspect",
+    about = "Shows the metadata and files of a package artifact"
+  )]
+  Inspect {
+    #[structopt(parse(from_os_str))]
+    artifact: PathBuf,
+  },
+  #[structopt(
+    name = "check",
+    about = "Checks the package for breaking changes against its latest published version"
+  )]
+  Check {
+    /// Check every workspace member
+    #[structopt(long = "workspace")]
+    workspace: bool,
+    /// What to check against: `registry` for the latest published version,
+    /// or `git:<revision>` for the package as of a commit, branch or tag
+    #[structopt(long = "against", default_value = "registry")]
+    against: Baseline,
+  },
+  #[structopt(
+    name = "check-publish",
+    about = "Checks an artifact for breaking changes the way a registry does on publish",
+    raw(setting = "structopt::clap::AppSettings::Hidden")
+  )]
+  CheckPublish {
+    #[structopt(parse(from_os_str))]
+    artifact: PathBuf,
+    /// The artifact of the latest version published before it, if any
+    #[structopt(long = "baseline", parse(from_os_str))]
+    baseline: Option<PathBuf>,
+  },
+  #[structopt(
+    name = "changelog",
+    about = "Lists the schema changes between two published versions, or since the latest one"
+  )]
+  Changelog {
+    /// A published package to compare two versions of, by default the
+    /// working tree against the latest published version
+    package: Option<String>,
+    /// The versions to compare, as <from>..<to>
+    range: Option<String>,
+    /// Print the changelog as JSON instead of Markdown
+    #[structopt(long = "json")]
+    json: bool,
+  },
+  #[structopt(
+    name = "diff",
+    about = "Shows the schema changes between two versions of a package, element by element"
+  )]
+  Diff {
+    /// The older version, as <package>@<version>, a package directory or an
+    /// artifact file
+    from: Target,
+    /// The newer version, in the same forms
+    to: Target,
+    /// Print the changes as JSON
+    #[structopt(long = "json")]
+    json: bool,
+  },
+  #[structopt(
+    name = "fix",
+    about = "Reserves the numbers and names of fields and enum values removed since the latest published version"
+  )]
+  Fix {
+    /// Only report missing reservations, failing if there are any
+    #[structopt(long = "check")]
+    check: bool,
+  },
+  #[structopt(
+    name = "lint",
+    about = "Checks the package's .proto files against the rules in the [lint] section of buff.toml"
+  )]
+  Lint {
+    /// human, json, or sarif for code scanning tools
+    #[structopt(long = "format", default_value = "human")]
+    format: Format,
+  },
+  #[structopt(
+    name = "fmt",
+    about = "Lays out the package's .proto files canonically, keeping every comment"
+  )]
+  Fmt {
+    /// Only report unformatted files, failing if there are any
+    #[structopt(long = "check")]
+    check: bool,
+    /// Print the changes formatting would make instead of making them
+    #[structopt(long = "diff")]
+    diff: bool,
+  },
+  #[structopt(
+    name = "info",
+    about = "Shows a published package and checks its signature"
+  )]
+  Info {
+    /// The package, as <name>@<version>
+    package: String,
+    /// Also show and verify where the artifact was published from
+    #[structopt(long = "provenance")]
+    provenance: bool,
+  },
+  #[structopt(name = "version", about = "Works out and applies semver bumps")]
+  Version {
+    #[structopt(subcommand)]
+    cmd: VersionCommand,
+  },
+  #[structopt(name = "key", about = "Manages the keys used to sign packages")]
+  Key {
+    #[structopt(subcommand)]
+    cmd: KeyCommand,
+  },
+}
+
+#[derive(StructOpt, Debug)]
+enum KeyCommand {
+  #[structopt(
+    name = "generate",
+    about = "Generates an ed25519 signing key under BUFF_HOME"
+  )]
+  Generate {
+    #[structopt(long = "name", default_value = "default")]
+    name: String,
+  },
+}
+
+#[derive(StructOpt, Debug)]
+enum VersionCommand {
+  #[structopt(
+    name = "suggest",
+    about = "Suggests a patch, minor or major bump from the changes since the latest published version"
+  )]
+  Suggest,
+  #[structopt(name = "bump", about = "Bumps the version in buff.toml")]
+  Bump {
+    /// major, minor, patch, or auto for the bump `buff version suggest`
+    /// suggests
+    #[structopt(default_value = "auto")]
+    level: String,
+  },
 }
 
 #[derive(StructOpt)]
@@ -26,6 +241,10 @@ struct Cli {
   cmd: Command,
   #[structopt(flatten)]
   verbosity: Verbosity,
+  /// Path to the buff.toml to use, by default the nearest one in the current
+  /// directory or its parents
+  #[structopt(long = "manifest-path", parse(from_os_str), raw(global = "true"))]
+  manifest_path: Option<PathBuf>,
 }
 
 fn main() -> CliResult {
@@ -33,9 +252,74 @@ fn main() -> CliResult {
   args.verbosity.setup_env_logger(&env!("CARGO_PKG_NAME"))?;
   info!("asfasf");
 
+  let manifest_path = args.manifest_path.as_ref().map(PathBuf::as_path);
   match args.cmd {
     Command::Login { email, password } => commands::login::execute(&email, &password),
-    Command::Publish {} => commands::publish::execute(),
+    Command::Publish {
+      workspace,
+      dry_run,
+      no_verify,
+      allow_dirty,
+      compression,
+      sign,
+      key,
+    } => commands::publish::execute(
+      manifest_path,
+      workspace,
+      PublishOptions {
+        dry_run,
+        no_verify,
+        allow_dirty,
+        codec: compression,
+        signing_key: if sign { Some(key) } else { None },
+      },
+    ),
+    Command::Package {
+      output,
+      compression,
+    } => commands::package::execute(manifest_path, output, compression),
+    Command::New { name } => commands::new::execute(&name),
+    Command::Init { from_dir } => commands::init::execute(from_dir),
+    Command::Add { package, registry } => commands::add::execute(
+      manifest_path,
+      &package,
+      registry.as_ref().map(String::as_str),
+    ),
+    Command::Remove { package } => commands::remove::execute(manifest_path, &package),
+    Command::Inspect { artifact } => commands::inspect::execute(&artifact),
+    Command::Check { workspace, against } => {
+      commands::check::execute(manifest_path, workspace, against)
+    }
+    Command::CheckPublish { artifact, baseline } => {
+      commands::check_publish::execute(&artifact, baseline.as_ref().map(PathBuf::as_path))
+    }
+    Command::Changelog {
+      package,
+      range,
+      json,
+    } => commands::changelog::execute(
+      manifest_path,
+      package.as_ref().map(String::as_str),
+      range.as_ref().map(String::as_str),
+      json,
+    ),
+    Command::Diff { from, to, json } => commands::diff::execute(&from, &to, json),
+    Command::Fix { check } => commands::fix::execute(manifest_path, check),
+    Command::Lint { format } => commands::lint::execute(manifest_path, format),
+    Command::Fmt { check, diff } => commands::fmt::execute(manifest_path, check, diff),
+    Command::Info {
+      package,
+      provenance,
+    } => commands::info::execute(&package, provenance),
+    Command::Version {
+      cmd: VersionCommand::Suggest,
+    } => commands::version::suggest(manifest_path),
+    Command::Version {
+      cmd: VersionCommand::Bump { level },
+    } => commands::version::bump(manifest_path, &level),
+    Command::Key {
+      cmd: KeyCommand::Generate { name },
+    } => commands::key::generate(&name),
   }
   Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,85 @@
+//! Compares artifact size and build time across codecs on a generated proto
+//! corpus. Run with `cargo bench -p bufflib`.
+use bufflib::artifact;
+use bufflib::codec::Codec;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const PACKAGES: usize = 40;
+const FILES_PER_PACKAGE: usize = 25;
+const MESSAGES_PER_FILE: usize = 20;
+
+fn generate_corpus(root: &Path) {
+  fs::write(
+    root.join("buff.toml"),
+    "[package]\nname = \"corpus\"\nversion = \"0.1.0\"\ndescription = \"\"\nhomepage = \"\"\nrepository_url = \"\"\nkeywords = []\n",
+  )
+  .unwrap();
+  for package in 0..PACKAGES {
+    let dir = root.join(format!("protos/acme/service{}/v1", package));
+    fs::create_dir_all(&dir).unwrap();
+    for file in 0..FILES_PER_PACKAGE {
+      let mut source = format!(
+        "syntax = \"proto3\";\n\npackage acme.service{}.v1;\n\nimport \"google/protobuf/timestamp.proto\";\n\n",
+        package
+      );
+      for message in 0..MESSAGES_PER_FILE {
+        writeln!(source, "// Message {} of file {}.", message, file).unwrap();
+        writeln!(source, "message Entity{}x{} {{", file, message).unwrap();
+        writeln!(source, "  string id = 1;").unwrap();
+        writeln!(source, "  string display_name = 2;").unwrap();
+        writeln!(source, "  int64 revision = 3;").unwrap();
+        writeln!(source, "  repeated string labels = 4;").unwrap();
+        writeln!(source, "  map<string, string> attributes = 5;").unwrap();
+        writeln!(source, "  google.protobuf.Timestamp created_at = 6;").unwrap();
+        writeln!(source, "}}\n").unwrap();
+      }
+      writeln!(source, "service Entity{}Service {{", file).unwrap();
+      for message in 0..MESSAGES_PER_FILE {
+        writeln!(
+          source,
+          "  rpc Get{0}x{1} (Entity{0}x{1}) returns (Entity{0}x{1});",
+          file, message
+        )
+        .unwrap();
+      }
+      writeln!(source, "}}").unwrap();
+      fs::write(dir.join(format!("entities{}.proto", file)), source).unwrap();
+    }
+  }
+}
+
+fn compression(c: &mut Criterion) {
+  let corpus = tempdir::TempDir::new("buff_bench").unwrap();
+  generate_corpus(corpus.path());
+  let codecs = vec![
+    Codec::None,
+    Codec::Gzip { level: 1 },
+    Codec::default(),
+    Codec::Gzip { level: 9 },
+    Codec::Zstd { level: 1 },
+    Codec::Zstd { level: 3 },
+    Codec::Zstd { level: 19 },
+  ];
+  println!("{} files in the corpus", PACKAGES * FILES_PER_PACKAGE);
+  for codec in &codecs {
+    let digest = artifact::write_artifact(corpus.path(), *codec, io::sink()).unwrap();
+    println!("{:>8}: {} bytes", codec.to_string(), digest.size);
+  }
+  for codec in codecs {
+    let path = corpus.path().to_path_buf();
+    c.bench_function(&format!("write_artifact {}", codec), move |b| {
+      b.iter(|| artifact::write_artifact(&path, codec, io::sink()).unwrap())
+    });
+  }
+}
+
+criterion_group! {
+  name = benches;
+  config = Criterion::default().sample_size(10);
+  targets = compression
+}
+criterion_main!(benches);
@@ -1,66 +1,1199 @@
-use flate2::{bufread::GzEncoder, Compression};
-use ignore::Walk;
+use crate::codec::{self, Codec};
+use crate::manifest;
+use crate::manifest::MANIFEST_FILE_NAME;
+use crate::package_metadata::PackageMetadata;
+use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
 use std::fs::File;
-use std::io::{BufReader, Read, Seek, SeekFrom, Write};
-use std::path::{Path, PathBuf};
-use tar::Builder;
-use tempfile::tempfile;
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tar::{Archive, Builder, EntryType, Header};
 
 pub fn get_artifact_bytes(path: &str) -> Vec<u8> {
+  let mut buffer = Vec::new();
+  write_artifact(Path::new(path), Codec::default(), &mut buffer).expect("Failed to build artifact");
+  buffer
+}
+
+pub fn save_artifact_to_path(path: &str, codec: Codec, output_path: &str) -> ArtifactDigest {
+  let output_file = File::create(output_path).expect("Failed to create artifact file");
+  write_artifact(Path::new(path), codec, BufWriter::new(output_file))
+    .expect("Failed to write artifact")
+}
+
+/// Size and SHA-256 of an artifact as it was written out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtifactDigest {
+  pub size: u64,
+  pub sha256: String,
+}
+
+/// Builds the artifact for the package in `path` straight into `output`,
+/// compressing tar entries with `codec` as they are produced. Files are only
+/// ever read in chunks, so memory use doesn't depend on the size of the
+/// package.
+pub fn write_artifact<W: Write>(
+  path: &Path,
+  codec: Codec,
+  output: W,
+) -> io::Result<ArtifactDigest> {
+  let (root_entry, files) = package_files(path)?;
+  let metadata = PackageMetadata::new(path.join(MANIFEST_FILE_NAME).to_str().unwrap());
+  let mut output = HashingWriter::new(output);
+  {
+    let encoder = codec.encoder(&mut output)?;
+    let mut tar_builder = Builder::new(encoder);
+    if let Some(root_entry) = root_entry {
+      tar_builder.append_path_with_name(root_entry, "./")?;
+    }
+    let format = format!("{}\n", ARTIFACT_FORMAT_VERSION);
+    append_generated(&mut tar_builder, ARTIFACT_FORMAT_PATH, format.as_bytes())?;
+    // Files are hashed as they are written, so the manifest comes
+    // last. Readers look it up before checking any file against it.
+    let mut digests = Vec::new();
+    for (path, stripped_path) in &files {
+      if !path.is_file() {
+        tar_builder.append_path_with_name(path, stripped_path)?;
+        continue;
+      }
+      let file = File::open(path)?;
+      let mut header = Header::new_gnu();
+      header.set_metadata(&file.metadata()?);
+      let mut reader = HashingReader::new(file);
+      tar_builder.append_data(&mut header, stripped_path, &mut reader)?;
+      digests.push(FileDigest {
+        path: manifest_path_string(stripped_path),
+        size: reader.bytes_read(),
+        sha256: reader.hex_digest(),
+      });
+    }
+    let manifest = ArtifactManifest {
+      name: metadata.package.name,
+      version: metadata.package.version,
+      files: digests,
+      links: Vec::new(),
+      tool: format!("buff {}", env!("CARGO_PKG_VERSION")),
+      compression: CompressionParameters {
+        format: codec.name().to_string(),
+        level: codec.level(),
+      },
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest).unwrap();
+    append_generated(&mut tar_builder, ARTIFACT_MANIFEST_PATH, &manifest_json)?;
+    tar_builder.into_inner()?.finish()?;
+  }
+  output.flush()?;
+  Ok(ArtifactDigest {
+    size: output.bytes_written(),
+    sha256: output.hex_digest(),
+  })
+}
+
+fn append_generated<W: Write>(
+  tar_builder: &mut Builder<W>,
+  path: &str,
+  content: &[u8],
+) -> io::Result<()> {
+  let mut header = Header::new_gnu();
+  header.set_size(content.len() as u64);
+  header.set_mode(0o644);
+  tar_builder.append_data(&mut header, path, content)
+}
+
+/// Writes the artifact for the package at `manifest_path` to `output`, or to
+/// `<name>-<version>.<extension>` in the current directory, and returns its
+/// path and digest. `codec` overrides the `compression` set in `[package]`.
+pub fn package(
+  manifest_path: &Path,
+  output: Option<&Path>,
+  codec: Option<Codec>,
+) -> (PathBuf, ArtifactDigest) {
+  let metadata = PackageMetadata::new(manifest_path.to_str().unwrap());
+  let codec = codec.or(metadata.package.compression).unwrap_or_default();
+  let output = match output {
+    Some(output) => output.to_path_buf(),
+    None => PathBuf::from(format!(
+      "{}-{}.{}",
+      metadata.package.name,
+      metadata.package.version,
+      codec.extension()
+    )),
+  };
+  let package_dir = manifest::package_dir(manifest_path);
+  let digest = save_artifact_to_path(
+    package_dir.to_str().unwrap(),
+    codec,
+    output.to_str().unwrap(),
+  );
+  (output, digest)
+}
+
+/// The entries that go into the artifact of the package in `path`, as their
+/// path on disk and in the artifact, and the package directory itself.
+fn package_files(path: &Path) -> io::Result<(Option<PathBuf>, Vec<(PathBuf, PathBuf)>)> {
   // note(itay): The ignore crate uses the .gitignore file and also a .ignore file
   // if specified by default, so basically like walkdir but already baked with the
   // logic we had in mind.
-  let artifact_files = ignore::Walk::new(path);
-  let tar = create_tar(artifact_files, Path::new(path).to_path_buf());
-  get_compressed_tar(tar)
+  let mut root_entry = None;
+  let mut files = Vec::new();
+  for file in ignore::Walk::new(path) {
+    let entry = file.map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+    let entry_path = entry.path().to_path_buf();
+    let stripped_path = entry_path.strip_prefix(path).unwrap().to_path_buf();
+    if stripped_path.as_os_str().is_empty() {
+      root_entry = Some(entry_path);
+    } else {
+      files.push((entry_path, stripped_path));
+    }
+  }
+  Ok((root_entry, files))
 }
 
-pub fn save_artifact_to_path(path: &str, output_path: &str) {
-  let compressed_tar = get_artifact_bytes(path);
-  save_buffer_to_file(compressed_tar, output_path);
+/// Size and SHA-256 of every file that goes into the artifact of the package
+/// in `path`, sorted by path, as `.buff/manifest.json` lists them.
+pub fn file_digests(path: &Path) -> io::Result<Vec<FileDigest>> {
+  let (_, files) = package_files(path)?;
+  let mut digests = digest_files(&files)?;
+  digests.sort_by(|a, b| a.path.cmp(&b.path));
+  Ok(digests)
 }
 
-fn create_tar(files_to_tar: Walk, tar_path_root: PathBuf) -> File {
-  let mut tar_file = tempfile().unwrap();
-  {
-    let mut tar_builder = Builder::new(&tar_file);
-    for file in files_to_tar {
-      let entry = file.unwrap();
-      let path = entry.path();
-      let stripped_path = path.strip_prefix(tar_path_root.as_path()).unwrap();
-      if stripped_path.to_str().unwrap().len() == 0 {
-        tar_builder.append_path_with_name(path, "./").unwrap()
-      } else {
-        tar_builder
-          .append_path_with_name(path, stripped_path)
-          .unwrap()
+pub const ARTIFACT_MANIFEST_PATH: &str = ".buff/manifest.json";
+/// Holds the version of the artifact layout. Artifacts that have it must
+/// have a `.buff/manifest.json` too.
+pub const ARTIFACT_FORMAT_PATH: &str = ".buff/format";
+pub const ARTIFACT_FORMAT_VERSION: u32 = 1;
+
+/// Generated into every artifact as `.buff/manifest.json`, so each file in it
+/// can be verified on its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactManifest {
+  pub name: String,
+  pub version: String,
+  pub files: Vec<FileDigest>,
+  /// Symlinks and hardlinks, which have no content to hash. buff stores
+  /// links as the files they point to, so artifacts it builds have none.
+  #[serde(default)]
+  pub links: Vec<LinkEntry>,
+  /// The tool that built the artifact, e.g. `buff 0.1.0`.
+  pub tool: String,
+  pub compression: CompressionParameters,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileDigest {
+  /// Path relative to the package root, always with `/` separators.
+  pub path: String,
+  pub size: u64,
+  pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkEntry {
+  /// Path relative to the package root, always with `/` separators.
+  pub path: String,
+  pub target: String,
+  pub hardlink: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompressionParameters {
+  pub format: String,
+  pub level: u32,
+}
+
+/// Passes writes through to `inner` while computing their SHA-256.
+pub struct HashingWriter<W: Write> {
+  inner: W,
+  hasher: Sha256,
+  written: u64,
+}
+
+impl<W: Write> HashingWriter<W> {
+  pub fn new(inner: W) -> Self {
+    HashingWriter {
+      inner,
+      hasher: Sha256::new(),
+      written: 0,
+    }
+  }
+
+  pub fn bytes_written(&self) -> u64 {
+    self.written
+  }
+
+  pub fn hex_digest(&self) -> String {
+    hex::encode(self.hasher.clone().result())
+  }
+
+  pub fn into_inner(self) -> W {
+    self.inner
+  }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let written = self.inner.write(buf)?;
+    self.hasher.input(&buf[..written]);
+    self.written += written as u64;
+    Ok(written)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.inner.flush()
+  }
+}
+
+/// Passes reads through from `inner` while computing their SHA-256.
+pub struct HashingReader<R: Read> {
+  inner: R,
+  hasher: Sha256,
+  read: u64,
+}
+
+impl<R: Read> HashingReader<R> {
+  pub fn new(inner: R) -> Self {
+    HashingReader {
+      inner,
+      hasher: Sha256::new(),
+      read: 0,
+    }
+  }
+
+  pub fn bytes_read(&self) -> u64 {
+    self.read
+  }
+
+  pub fn hex_digest(&self) -> String {
+    hex::encode(self.hasher.clone().result())
+  }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let read = self.inner.read(buf)?;
+    self.hasher.input(&buf[..read]);
+    self.read += read as u64;
+    Ok(read)
+  }
+}
+
+/// Tracks which files listed in an artifact's manifest were seen, and
+/// whether they matched.
+struct DigestCheck {
+  expected: HashMap<PathBuf, FileDigest>,
+  expected_links: HashMap<PathBuf, LinkEntry>,
+}
+
+impl DigestCheck {
+  fn new(manifest: ArtifactManifest) -> Self {
+    DigestCheck {
+      expected: manifest
+        .files
+        .into_iter()
+        .map(|digest| (PathBuf::from(&digest.path), digest))
+        .collect(),
+      expected_links: manifest
+        .links
+        .into_iter()
+        .map(|link| (PathBuf::from(&link.path), link))
+        .collect(),
+    }
+  }
+
+  fn check(&mut self, path: &Path, size: u64, sha256: String) -> Result<(), ExtractError> {
+    match self.expected.remove(path) {
+      None => Err(ExtractError::UnlistedFile(path.to_path_buf())),
+      Some(ref digest) if digest.size != size || digest.sha256 != sha256 => {
+        Err(ExtractError::DigestMismatch {
+          path: path.to_path_buf(),
+          expected: digest.sha256.clone(),
+          actual: sha256,
+        })
+      }
+      Some(_) => Ok(()),
+    }
+  }
+
+  fn check_link(&mut self, path: &Path, target: &Path, hardlink: bool) -> Result<(), ExtractError> {
+    match self.expected_links.remove(path) {
+      None => Err(ExtractError::UnlistedFile(path.to_path_buf())),
+      Some(ref link)
+        if link.target != manifest_path_string(target) || link.hardlink != hardlink =>
+      {
+        Err(ExtractError::LinkMismatch {
+          path: path.to_path_buf(),
+          expected: PathBuf::from(&link.target),
+          actual: target.to_path_buf(),
+        })
       }
+      Some(_) => Ok(()),
+    }
+  }
+
+  fn finish(self) -> Result<(), ExtractError> {
+    let mut missing: Vec<PathBuf> = self
+      .expected
+      .into_iter()
+      .map(|(path, _)| path)
+      .chain(self.expected_links.into_iter().map(|(path, _)| path))
+      .collect();
+    missing.sort();
+    match missing.into_iter().next() {
+      Some(path) => Err(ExtractError::MissingFile(path)),
+      None => Ok(()),
     }
-    tar_builder.into_inner().unwrap();
   }
-  &mut tar_file.seek(SeekFrom::Start(0)).unwrap();
-  return tar_file;
 }
 
-fn get_compressed_tar(tar: File) -> Vec<u8> {
-  let buf_reader = BufReader::new(tar);
-  let mut encoder = GzEncoder::new(buf_reader, Compression::default());
-  let mut buffer = Vec::new();
-  encoder.read_to_end(&mut buffer).unwrap();
-  return buffer;
+/// Upper bound on the unpacked size of an artifact, so a small compressed
+/// artifact can't fill the disk when extracted.
+pub const MAX_UNPACKED_SIZE: u64 = 256 * 1024 * 1024;
+pub const MAX_ENTRIES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EntryKind {
+  File,
+  Directory,
+  Symlink,
+  Hardlink,
+  Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtifactEntry {
+  pub path: PathBuf,
+  pub size: u64,
+  pub kind: EntryKind,
 }
 
-fn save_buffer_to_file(buffer: Vec<u8>, path: &str) {
-  let mut output_file = File::create(path).unwrap();
-  output_file.write_all(&buffer).unwrap();
+#[derive(Debug)]
+pub enum ExtractError {
+  AbsolutePath(PathBuf),
+  ParentTraversal(PathBuf),
+  LinkEscapesRoot {
+    path: PathBuf,
+    target: PathBuf,
+  },
+  UnsupportedEntry(PathBuf),
+  TooLarge(u64),
+  TooManyEntries,
+  InvalidManifest(String),
+  MissingManifest,
+  UnsupportedFormat(String),
+  UnlistedFile(PathBuf),
+  MissingFile(PathBuf),
+  DigestMismatch {
+    path: PathBuf,
+    expected: String,
+    actual: String,
+  },
+  LinkMismatch {
+    path: PathBuf,
+    expected: PathBuf,
+    actual: PathBuf,
+  },
+  Io(io::Error),
+}
+
+impl fmt::Display for ExtractError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ExtractError::AbsolutePath(path) => {
+        write!(f, "entry `{}` has an absolute path", path.display())
+      }
+      ExtractError::ParentTraversal(path) => {
+        write!(f, "entry `{}` points outside the artifact", path.display())
+      }
+      ExtractError::LinkEscapesRoot { path, target } => write!(
+        f,
+        "link `{}` -> `{}` points outside the artifact",
+        path.display(),
+        target.display()
+      ),
+      ExtractError::UnsupportedEntry(path) => {
+        write!(
+          f,
+          "entry `{}` is not a file, directory or link",
+          path.display()
+        )
+      }
+      ExtractError::TooLarge(limit) => write!(f, "artifact unpacks to more than {} bytes", limit),
+      ExtractError::TooManyEntries => write!(f, "artifact has more than {} entries", MAX_ENTRIES),
+      ExtractError::InvalidManifest(err) => {
+        write!(f, "invalid {}: {}", ARTIFACT_MANIFEST_PATH, err)
+      }
+      ExtractError::MissingManifest => write!(f, "artifact has no {}", ARTIFACT_MANIFEST_PATH),
+      ExtractError::UnsupportedFormat(format) => {
+        write!(f, "unsupported artifact format `{}`", format)
+      }
+      ExtractError::UnlistedFile(path) => write!(
+        f,
+        "`{}` is not listed in {}",
+        path.display(),
+        ARTIFACT_MANIFEST_PATH
+      ),
+      ExtractError::MissingFile(path) => write!(
+        f,
+        "`{}` is listed in {} but missing from the artifact",
+        path.display(),
+        ARTIFACT_MANIFEST_PATH
+      ),
+      ExtractError::DigestMismatch {
+        path,
+        expected,
+        actual,
+      } => write!(
+        f,
+        "`{}` has SHA-256 {} but {} lists {}",
+        path.display(),
+        actual,
+        ARTIFACT_MANIFEST_PATH,
+        expected
+      ),
+      ExtractError::LinkMismatch {
+        path,
+        expected,
+        actual,
+      } => write!(
+        f,
+        "link `{}` points to `{}` but {} lists `{}`",
+        path.display(),
+        actual.display(),
+        ARTIFACT_MANIFEST_PATH,
+        expected.display()
+      ),
+      ExtractError::Io(err) => write!(f, "{}", err),
+    }
+  }
+}
+
+impl From<io::Error> for ExtractError {
+  fn from(err: io::Error) -> Self {
+    ExtractError::Io(err)
+  }
+}
+
+/// A package artifact, a tarball of the package directory. Artifacts opened
+/// from a file are read from it as needed rather than loaded into memory.
+pub struct Artifact {
+  source: ArtifactSource,
+  require_manifest: bool,
+}
+
+enum ArtifactSource {
+  Bytes(Vec<u8>),
+  File(PathBuf),
+}
+
+impl Artifact {
+  pub fn open(path: &Path) -> Self {
+    if let Err(err) = File::open(path) {
+      panic!("Failed to read artifact `{}`: {}", path.display(), err);
+    }
+    Artifact {
+      source: ArtifactSource::File(path.to_path_buf()),
+      require_manifest: false,
+    }
+  }
+
+  pub fn from_bytes(bytes: Vec<u8>) -> Self {
+    Artifact {
+      source: ArtifactSource::Bytes(bytes),
+      require_manifest: false,
+    }
+  }
+
+  /// Refuses to verify or extract the artifact without a
+  /// `.buff/manifest.json`, instead of treating it as built before manifests
+  /// were added and leaving its files unverified.
+  pub fn require_manifest(mut self) -> Self {
+    self.require_manifest = true;
+    self
+  }
+
+  /// The compressed size of the artifact.
+  pub fn size(&self) -> u64 {
+    match &self.source {
+      ArtifactSource::Bytes(bytes) => bytes.len() as u64,
+      ArtifactSource::File(path) => fs::metadata(path)
+        .unwrap_or_else(|err| panic!("Failed to read artifact `{}`: {}", path.display(), err))
+        .len(),
+    }
+  }
+
+  /// Hex-encoded SHA-256 of the artifact, the digest signatures are made
+  /// over.
+  pub fn sha256(&self) -> String {
+    let mut writer = HashingWriter::new(io::sink());
+    self
+      .reader()
+      .and_then(|mut reader| io::copy(&mut reader, &mut writer))
+      .unwrap_or_else(|err| panic!("Failed to read artifact: {}", err));
+    writer.hex_digest()
+  }
+
+  /// The artifact as it was written, compressed.
+  pub fn reader(&self) -> io::Result<Box<dyn BufRead + '_>> {
+    Ok(match &self.source {
+      ArtifactSource::Bytes(bytes) => Box::new(&bytes[..]),
+      ArtifactSource::File(path) => Box::new(BufReader::new(File::open(path)?)),
+    })
+  }
+
+  fn archive(&self) -> io::Result<Archive<Box<dyn Read + '_>>> {
+    Ok(Archive::new(codec::decoder(self.reader()?)?))
+  }
+
+  /// Lists the entries of the artifact in archive order, skipping the root
+  /// directory entry.
+  pub fn entries(&self) -> Result<Vec<ArtifactEntry>, ExtractError> {
+    let mut archive = self.archive()?;
+    let mut entries = Vec::new();
+    for entry in archive.entries()? {
+      let entry = entry?;
+      let path = normalize_path(&entry.path()?);
+      if path.as_os_str().is_empty() {
+        continue;
+      }
+      entries.push(ArtifactEntry {
+        path,
+        size: entry.header().size().unwrap_or(0),
+        kind: entry_kind(entry.header().entry_type()),
+      });
+    }
+    Ok(entries)
+  }
+
+  /// Reads a single file out of the artifact.
+  pub fn read_file(&self, path: &Path) -> Result<Option<Vec<u8>>, ExtractError> {
+    let mut archive = self.archive()?;
+    for entry in archive.entries()? {
+      let mut entry = entry?;
+      let entry_path = normalize_path(&entry.path()?);
+      if entry_path == path && entry.header().entry_type().is_file() {
+        let mut content = Vec::new();
+        entry
+          .by_ref()
+          .take(MAX_UNPACKED_SIZE)
+          .read_to_end(&mut content)?;
+        return Ok(Some(content));
+      }
+    }
+    Ok(None)
+  }
+
+  /// The package's buff.toml as it was published.
+  pub fn metadata(&self) -> PackageMetadata {
+    self
+      .read_metadata()
+      .unwrap_or_else(|err| panic!("Failed to read artifact: {}", err))
+  }
+
+  /// Like `metadata`, but for artifacts that might not have a valid
+  /// buff.toml, e.g. ones sent to a registry.
+  pub fn read_metadata(&self) -> Result<PackageMetadata, String> {
+    let content = self
+      .read_file(Path::new(MANIFEST_FILE_NAME))
+      .map_err(|err| err.to_string())?
+      .ok_or_else(|| "the artifact has no buff.toml".to_string())?;
+    toml::from_str(&String::from_utf8_lossy(&content))
+      .map_err(|err| format!("invalid buff.toml: {}", err))
+  }
+
+  /// The embedded `.buff/manifest.json`, `None` for artifacts built before
+  /// it was added, which have no `.buff/format` either, unless the manifest
+  /// is required.
+  pub fn manifest(&self) -> Result<Option<ArtifactManifest>, ExtractError> {
+    let format = self.read_file(Path::new(ARTIFACT_FORMAT_PATH))?;
+    let content = match self.read_file(Path::new(ARTIFACT_MANIFEST_PATH))? {
+      Some(content) => content,
+      None if format.is_none() && !self.require_manifest => return Ok(None),
+      None => return Err(ExtractError::MissingManifest),
+    };
+    if let Some(format) = format {
+      let format = String::from_utf8_lossy(&format).trim().to_string();
+      if format != ARTIFACT_FORMAT_VERSION.to_string() {
+        return Err(ExtractError::UnsupportedFormat(format));
+      }
+    }
+    serde_json::from_slice(&content)
+      .map(Some)
+      .map_err(|err| ExtractError::InvalidManifest(err.to_string()))
+  }
+
+  /// Checks every file against `.buff/manifest.json` without extracting
+  /// anything, returning the manifest if there is one.
+  pub fn verify_digests(&self) -> Result<Option<ArtifactManifest>, ExtractError> {
+    let manifest = match self.manifest()? {
+      Some(manifest) => manifest,
+      None => return Ok(None),
+    };
+    let mut digests = DigestCheck::new(manifest.clone());
+    let mut archive = self.archive()?;
+    for entry in archive.entries()? {
+      let mut entry = entry?;
+      let path = safe_relative_path(&entry.path()?)?;
+      let kind = entry_kind(entry.header().entry_type());
+      if kind == EntryKind::Symlink || kind == EntryKind::Hardlink {
+        let target = entry
+          .link_name()?
+          .ok_or_else(|| ExtractError::UnsupportedEntry(path.clone()))?
+          .into_owned();
+        digests.check_link(&path, &target, kind == EntryKind::Hardlink)?;
+        continue;
+      }
+      if kind != EntryKind::File || is_generated(&path) {
+        continue;
+      }
+      let mut writer = HashingWriter::new(io::sink());
+      let size = io::copy(&mut entry.by_ref().take(MAX_UNPACKED_SIZE), &mut writer)?;
+      digests.check(&path, size, writer.hex_digest())?;
+    }
+    digests.finish()?;
+    Ok(Some(manifest))
+  }
+
+  /// Size and SHA-256 of every file in the artifact but the generated
+  /// manifest, sorted by path, so it can be compared with `file_digests` of
+  /// a package directory.
+  pub fn file_digests(&self) -> Result<Vec<FileDigest>, ExtractError> {
+    let mut digests = Vec::new();
+    let mut archive = self.archive()?;
+    for entry in archive.entries()? {
+      let mut entry = entry?;
+      let path = safe_relative_path(&entry.path()?)?;
+      if !entry.header().entry_type().is_file() || is_generated(&path) {
+        continue;
+      }
+      let mut writer = HashingWriter::new(io::sink());
+      let size = io::copy(&mut entry.by_ref().take(MAX_UNPACKED_SIZE), &mut writer)?;
+      digests.push(FileDigest {
+        path: manifest_path_string(&path),
+        size,
+        sha256: writer.hex_digest(),
+      });
+    }
+    digests.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(digests)
+  }
+
+  /// The .proto files in the artifact, with their paths relative to the
+  /// package root.
+  pub fn proto_sources(&self) -> Result<Vec<(PathBuf, String)>, ExtractError> {
+    let mut archive = self.archive()?;
+    let mut sources = Vec::new();
+    for entry in archive.entries()? {
+      let mut entry = entry?;
+      let path = normalize_path(&entry.path()?);
+      if !entry.header().entry_type().is_file()
+        || path.extension().map_or(true, |ext| ext != "proto")
+      {
+        continue;
+      }
+      let mut source = String::new();
+      entry
+        .by_ref()
+        .take(MAX_UNPACKED_SIZE)
+        .read_to_string(&mut source)?;
+      sources.push((path, source));
+    }
+    Ok(sources)
+  }
+
+  /// Extracts the artifact into `destination`. Entries with absolute paths or
+  /// `..` components, links that could lead outside `destination`, device
+  /// files and artifacts that unpack to more than `MAX_UNPACKED_SIZE` are
+  /// rejected, as are files that don't match `.buff/manifest.json`. Files of
+  /// artifacts without a manifest are extracted unverified, check `manifest`
+  /// first or use `require_manifest` to refuse them.
+  pub fn extract(&self, destination: &Path) -> Result<Vec<PathBuf>, ExtractError> {
+    self.extract_with_limit(destination, MAX_UNPACKED_SIZE)
+  }
+
+  fn extract_with_limit(
+    &self,
+    destination: &Path,
+    max_unpacked_size: u64,
+  ) -> Result<Vec<PathBuf>, ExtractError> {
+    let mut digests = self.manifest()?.map(DigestCheck::new);
+    fs::create_dir_all(destination)?;
+    let mut archive = self.archive()?;
+    let mut extracted = Vec::new();
+    let mut unpacked_size = 0;
+    for (index, entry) in archive.entries()?.enumerate() {
+      if index >= MAX_ENTRIES {
+        return Err(ExtractError::TooManyEntries);
+      }
+      let mut entry = entry?;
+      let raw_path = entry.path()?.into_owned();
+      let path = safe_relative_path(&raw_path)?;
+      if path.as_os_str().is_empty() {
+        continue;
+      }
+      let target = destination.join(&path);
+      let entry_type = entry.header().entry_type();
+      match entry_kind(entry_type) {
+        EntryKind::Directory => fs::create_dir_all(&target)?,
+        EntryKind::File => {
+          if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+          }
+          let remaining = max_unpacked_size - unpacked_size;
+          let mut writer = HashingWriter::new(File::create(&target)?);
+          let written = io::copy(&mut entry.by_ref().take(remaining + 1), &mut writer)?;
+          if written > remaining {
+            return Err(ExtractError::TooLarge(max_unpacked_size));
+          }
+          unpacked_size += written;
+          if let Some(digests) = digests.as_mut() {
+            if !is_generated(&path) {
+              if let Err(err) = digests.check(&path, written, writer.hex_digest()) {
+                drop(writer);
+                fs::remove_file(&target)?;
+                return Err(err);
+              }
+            }
+          }
+        }
+        kind @ EntryKind::Symlink | kind @ EntryKind::Hardlink => {
+          let link_name = entry
+            .link_name()?
+            .ok_or_else(|| ExtractError::UnsupportedEntry(raw_path.clone()))?
+            .into_owned();
+          // Link targets may only descend, otherwise a link to
+          // `.` next to one to `that_link/..` would still escape.
+          let link_target =
+            safe_relative_path(&link_name).map_err(|_| ExtractError::LinkEscapesRoot {
+              path: raw_path.clone(),
+              target: link_name.clone(),
+            })?;
+          if let Some(digests) = digests.as_mut() {
+            digests.check_link(&path, &link_target, kind == EntryKind::Hardlink)?;
+          }
+          if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+          }
+          if kind == EntryKind::Hardlink {
+            fs::hard_link(destination.join(&link_target), &target)?;
+          } else {
+            create_symlink(&link_target, &target)?;
+          }
+        }
+        EntryKind::Other => return Err(ExtractError::UnsupportedEntry(raw_path)),
+      }
+      extracted.push(path);
+    }
+    if let Some(digests) = digests {
+      digests.finish()?;
+    }
+    Ok(extracted)
+  }
+}
+
+fn entry_kind(entry_type: EntryType) -> EntryKind {
+  match entry_type {
+    EntryType::Regular | EntryType::Continuous => EntryKind::File,
+    EntryType::Directory => EntryKind::Directory,
+    EntryType::Symlink => EntryKind::Symlink,
+    EntryType::Link => EntryKind::Hardlink,
+    _ => EntryKind::Other,
+  }
+}
+
+// The entries buff writes about the artifact itself, which the manifest
+// doesn't list.
+fn is_generated(path: &Path) -> bool {
+  path == Path::new(ARTIFACT_MANIFEST_PATH) || path == Path::new(ARTIFACT_FORMAT_PATH)
+}
+
+fn normalize_path(path: &Path) -> PathBuf {
+  path
+    .components()
+    .filter(|component| *component != Component::CurDir)
+    .collect()
+}
+
+fn safe_relative_path(path: &Path) -> Result<PathBuf, ExtractError> {
+  let mut safe = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::Normal(part) => safe.push(part),
+      Component::CurDir => (),
+      Component::ParentDir => return Err(ExtractError::ParentTraversal(path.to_path_buf())),
+      Component::RootDir | Component::Prefix(_) => {
+        return Err(ExtractError::AbsolutePath(path.to_path_buf()))
+      }
+    }
+  }
+  Ok(safe)
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+  std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &Path, _link: &Path) -> io::Result<()> {
+  Err(io::Error::new(
+    io::ErrorKind::Other,
+    "symlinks in artifacts are only supported on unix",
+  ))
+}
+
+fn digest_files(files: &[(PathBuf, PathBuf)]) -> io::Result<Vec<FileDigest>> {
+  let mut digests = Vec::new();
+  for (path, stripped_path) in files {
+    if !path.is_file() {
+      continue;
+    }
+    let mut writer = HashingWriter::new(io::sink());
+    let size = io::copy(&mut File::open(path)?, &mut writer)?;
+    digests.push(FileDigest {
+      path: manifest_path_string(stripped_path),
+      size,
+      sha256: writer.hex_digest(),
+    });
+  }
+  Ok(digests)
+}
+
+fn manifest_path_string(path: &Path) -> String {
+  path
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/")
 }
 
 #[test]
 fn should_save_artifact_to_path() {
   let output_path = "/tmp/test_artifact.tar.gz";
-  save_artifact_to_path("tests/fixtures/test_artifact", output_path);
+  save_artifact_to_path(
+    "tests/fixtures/test_artifact",
+    Codec::default(),
+    output_path,
+  );
   assert!(std::path::Path::new(output_path).exists());
   let file = File::open(output_path).unwrap();
   assert_ne!(file.metadata().unwrap().len(), 0);
   std::fs::remove_file(output_path).unwrap();
 }
+
+#[cfg(test)]
+fn artifact_with_entry(
+  path: &str,
+  entry_type: EntryType,
+  link_name: Option<&str>,
+  content: &[u8],
+) -> Artifact {
+  // Header::set_path refuses unsafe paths, so the raw name fields
+  // are written directly.
+  let mut header = tar::Header::new_old();
+  header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+  if let Some(link_name) = link_name {
+    header.as_old_mut().linkname[..link_name.len()].copy_from_slice(link_name.as_bytes());
+  }
+  header.set_entry_type(entry_type);
+  header.set_size(content.len() as u64);
+  header.set_mode(0o644);
+  header.set_cksum();
+  let mut builder = Builder::new(Vec::new());
+  builder.append(&header, content).unwrap();
+  let mut encoder = Codec::default().encoder(Vec::new()).unwrap();
+  encoder.write_all(&builder.into_inner().unwrap()).unwrap();
+  Artifact::from_bytes(encoder.finish().unwrap())
+}
+
+#[test]
+fn should_read_artifact() {
+  let artifact = Artifact::from_bytes(get_artifact_bytes("../tests/fixtures/test_artifact"));
+  let sources = artifact.proto_sources().unwrap();
+  assert_eq!(sources.len(), 1);
+  assert_eq!(sources[0].0, PathBuf::from("protobuffers/buff.proto"));
+  assert!(sources[0].1.contains("package buff_server_grpc;"));
+  let metadata = artifact.metadata();
+  assert_eq!(metadata.package.name, "test_package");
+  assert_eq!(metadata.package.version, "0.1.0");
+  assert!(artifact
+    .read_file(Path::new("moshe.txt"))
+    .unwrap()
+    .is_none());
+  let bytes = get_artifact_bytes("../tests/fixtures/test_artifact");
+  let truncated = Artifact::from_bytes(bytes[..bytes.len() / 2].to_vec());
+  assert!(truncated.entries().is_err());
+  assert!(truncated.proto_sources().is_err());
+  assert!(truncated.read_file(Path::new("moshe.txt")).is_err());
+}
+
+#[test]
+fn should_extract() {
+  let artifact = Artifact::from_bytes(get_artifact_bytes("../tests/fixtures/test_artifact"));
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  artifact.extract(tmp_dir.path()).unwrap();
+  assert_eq!(
+    fs::read_to_string(tmp_dir.path().join("protobuffers/buff.proto")).unwrap(),
+    fs::read_to_string("../tests/fixtures/test_artifact/protobuffers/buff.proto").unwrap()
+  );
+  let link = artifact_with_entry("protos", EntryType::Symlink, Some("nested/protos"), b"");
+  link.extract(tmp_dir.path()).unwrap();
+}
+
+#[test]
+fn should_reject_unsafe_entries() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let extract = |artifact: Artifact| artifact.extract(tmp_dir.path()).unwrap_err();
+  match extract(artifact_with_entry(
+    "/etc/passwd",
+    EntryType::Regular,
+    None,
+    b"x",
+  )) {
+    ExtractError::AbsolutePath(_) => (),
+    err => panic!("unexpected error {}", err),
+  }
+  match extract(artifact_with_entry(
+    "protos/../../evil",
+    EntryType::Regular,
+    None,
+    b"x",
+  )) {
+    ExtractError::ParentTraversal(_) => (),
+    err => panic!("unexpected error {}", err),
+  }
+  for target in &["../../etc", "/etc", "a/../.."] {
+    match extract(artifact_with_entry(
+      "link",
+      EntryType::Symlink,
+      Some(target),
+      b"",
+    )) {
+      ExtractError::LinkEscapesRoot { .. } => (),
+      err => panic!("unexpected error {}", err),
+    }
+  }
+  match extract(artifact_with_entry(
+    "hard",
+    EntryType::Link,
+    Some("/etc/passwd"),
+    b"",
+  )) {
+    ExtractError::LinkEscapesRoot { .. } => (),
+    err => panic!("unexpected error {}", err),
+  }
+  match extract(artifact_with_entry("tty", EntryType::Char, None, b"")) {
+    ExtractError::UnsupportedEntry(_) => (),
+    err => panic!("unexpected error {}", err),
+  }
+  let bomb = artifact_with_entry("bomb", EntryType::Regular, None, &[0; 4096]);
+  match bomb.extract_with_limit(tmp_dir.path(), 1024).unwrap_err() {
+    ExtractError::TooLarge(1024) => (),
+    err => panic!("unexpected error {}", err),
+  }
+}
+
+#[test]
+fn should_package() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let output = tmp_dir.path().join("out.tar.gz");
+  let (path, digest) = package(
+    Path::new("../tests/fixtures/test_artifact/buff.toml"),
+    Some(&output),
+    None,
+  );
+  assert_eq!(path, output);
+  let content = fs::read(&output).unwrap();
+  assert_eq!(digest.size, content.len() as u64);
+  assert_eq!(digest.sha256, hex::encode(Sha256::digest(&content)));
+  let mut entries: Vec<PathBuf> = Artifact::open(&output)
+    .entries()
+    .unwrap()
+    .into_iter()
+    .filter(|entry| entry.kind == EntryKind::File)
+    .map(|entry| entry.path)
+    .collect();
+  entries.sort();
+  assert_eq!(
+    entries,
+    vec![
+      PathBuf::from(".buff/format"),
+      PathBuf::from(".buff/manifest.json"),
+      PathBuf::from("buff.toml"),
+      PathBuf::from("protobuffers/buff.proto")
+    ]
+  );
+}
+
+#[cfg(test)]
+fn artifact_with_files(files: &[(&str, &[u8])]) -> Artifact {
+  let mut builder = Builder::new(Vec::new());
+  for (path, content) in files {
+    let mut header = Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    builder.append_data(&mut header, path, *content).unwrap();
+  }
+  let mut encoder = Codec::default().encoder(Vec::new()).unwrap();
+  encoder.write_all(&builder.into_inner().unwrap()).unwrap();
+  Artifact::from_bytes(encoder.finish().unwrap())
+}
+
+#[test]
+fn should_embed_manifest() {
+  let artifact = Artifact::from_bytes(get_artifact_bytes("../tests/fixtures/test_artifact"));
+  let manifest = artifact.verify_digests().unwrap().unwrap();
+  assert_eq!(manifest.name, "test_package");
+  assert_eq!(manifest.version, "0.1.0");
+  assert_eq!(manifest.compression.format, "gzip");
+  let mut paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+  paths.sort();
+  assert_eq!(paths, vec!["buff.toml", "protobuffers/buff.proto"]);
+  let proto = manifest
+    .files
+    .iter()
+    .find(|f| f.path == "protobuffers/buff.proto")
+    .unwrap();
+  let content = fs::read("../tests/fixtures/test_artifact/protobuffers/buff.proto").unwrap();
+  assert_eq!(proto.size, content.len() as u64);
+  assert_eq!(proto.sha256, hex::encode(Sha256::digest(&content)));
+  assert_eq!(
+    artifact.file_digests().unwrap(),
+    file_digests(Path::new("../tests/fixtures/test_artifact")).unwrap()
+  );
+}
+
+#[test]
+fn should_reject_files_not_matching_manifest() {
+  let manifest = |sha256: &str| {
+    format!(
+      "{{\"name\":\"a\",\"version\":\"0.1.0\",\"tool\":\"buff\",\"compression\":{{\"format\":\"gzip\",\"level\":6}},\"files\":[{{\"path\":\"a.proto\",\"size\":5,\"sha256\":\"{}\"}}]}}",
+      sha256
+    )
+  };
+  let good = hex::encode(Sha256::digest(b"hello"));
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let artifact = artifact_with_files(&[
+    (ARTIFACT_MANIFEST_PATH, manifest(&good).as_bytes()),
+    ("a.proto", b"hello"),
+  ]);
+  artifact.extract(tmp_dir.path()).unwrap();
+  let tampered = artifact_with_files(&[
+    (ARTIFACT_MANIFEST_PATH, manifest(&good).as_bytes()),
+    ("a.proto", b"HELLO"),
+  ]);
+  match tampered
+    .extract(&tmp_dir.path().join("tampered"))
+    .unwrap_err()
+  {
+    ExtractError::DigestMismatch { path, .. } => assert_eq!(path, PathBuf::from("a.proto")),
+    err => panic!("unexpected error {}", err),
+  }
+  assert!(!tmp_dir.path().join("tampered/a.proto").exists());
+  let extra = artifact_with_files(&[
+    (ARTIFACT_MANIFEST_PATH, manifest(&good).as_bytes()),
+    ("a.proto", b"hello"),
+    ("b.proto", b"extra"),
+  ]);
+  match extra.verify_digests().unwrap_err() {
+    ExtractError::UnlistedFile(path) => assert_eq!(path, PathBuf::from("b.proto")),
+    err => panic!("unexpected error {}", err),
+  }
+  let missing = artifact_with_files(&[(ARTIFACT_MANIFEST_PATH, manifest(&good).as_bytes())]);
+  match missing.verify_digests().unwrap_err() {
+    ExtractError::MissingFile(path) => assert_eq!(path, PathBuf::from("a.proto")),
+    err => panic!("unexpected error {}", err),
+  }
+}
+
+#[test]
+fn should_require_manifest_and_listed_links() {
+  let manifest = |links: &str| {
+    format!(
+      "{{\"name\":\"a\",\"version\":\"0.1.0\",\"tool\":\"buff\",\"compression\":{{\"format\":\"gzip\",\"level\":6}},\"files\":[],\"links\":[{}]}}",
+      links
+    )
+  };
+  let stripped = artifact_with_files(&[(ARTIFACT_FORMAT_PATH, b"1\n"), ("a.proto", b"hello")]);
+  match stripped.verify_digests().unwrap_err() {
+    ExtractError::MissingManifest => (),
+    err => panic!("unexpected error {}", err),
+  }
+  let legacy = artifact_with_files(&[("a.proto", b"hello")]);
+  assert!(legacy.verify_digests().unwrap().is_none());
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  match legacy
+    .require_manifest()
+    .extract(tmp_dir.path())
+    .unwrap_err()
+  {
+    ExtractError::MissingManifest => (),
+    err => panic!("unexpected error {}", err),
+  }
+  assert!(!tmp_dir.path().join("a.proto").exists());
+  let with_link = |manifest: &str| {
+    let mut builder = Builder::new(Vec::new());
+    for (path, content) in &[
+      (ARTIFACT_FORMAT_PATH, "1\n"),
+      (ARTIFACT_MANIFEST_PATH, manifest),
+    ] {
+      let mut header = Header::new_gnu();
+      header.set_size(content.len() as u64);
+      header.set_mode(0o644);
+      builder
+        .append_data(&mut header, path, content.as_bytes())
+        .unwrap();
+    }
+    let mut header = Header::new_gnu();
+    header.set_entry_type(EntryType::Symlink);
+    header.set_size(0);
+    header.set_mode(0o644);
+    builder.append_link(&mut header, "latest", "v1").unwrap();
+    let mut encoder = Codec::default().encoder(Vec::new()).unwrap();
+    encoder.write_all(&builder.into_inner().unwrap()).unwrap();
+    Artifact::from_bytes(encoder.finish().unwrap())
+  };
+  let listed = with_link(&manifest(
+    "{\"path\":\"latest\",\"target\":\"v1\",\"hardlink\":false}",
+  ));
+  assert_eq!(listed.verify_digests().unwrap().unwrap().links.len(), 1);
+  match with_link(&manifest("")).verify_digests().unwrap_err() {
+    ExtractError::UnlistedFile(path) => assert_eq!(path, PathBuf::from("latest")),
+    err => panic!("unexpected error {}", err),
+  }
+  let retargeted = with_link(&manifest(
+    "{\"path\":\"latest\",\"target\":\"v2\",\"hardlink\":false}",
+  ));
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  match retargeted.extract(tmp_dir.path()).unwrap_err() {
+    ExtractError::LinkMismatch { path, .. } => assert_eq!(path, PathBuf::from("latest")),
+    err => panic!("unexpected error {}", err),
+  }
+}
+
+#[test]
+fn should_detect_codec() {
+  for codec in &[
+    Codec::Gzip { level: 1 },
+    Codec::Zstd { level: 19 },
+    Codec::None,
+  ] {
+    let mut bytes = Vec::new();
+    write_artifact(
+      Path::new("../tests/fixtures/test_artifact"),
+      *codec,
+      &mut bytes,
+    )
+    .unwrap();
+    assert_eq!(Codec::detect(&bytes).name(), codec.name());
+    let artifact = Artifact::from_bytes(bytes);
+    let manifest = artifact.verify_digests().unwrap().unwrap();
+    assert_eq!(manifest.compression.format, codec.name());
+    assert_eq!(manifest.compression.level, codec.level());
+    assert_eq!(artifact.proto_sources().unwrap().len(), 1);
+  }
+}
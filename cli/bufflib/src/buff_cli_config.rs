@@ -19,10 +19,59 @@ impl PartialEq for RegistryConfig {
   }
 }
 
+/// Keys trusted to sign packages, by hex-encoded ed25519 public key.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub struct TrustedKeys {
+  #[serde(default)]
+  pub keys: Vec<String>,
+  /// Refuse unsigned packages instead of only checking the ones that are
+  /// signed.
+  #[serde(default)]
+  pub require_signature: bool,
+}
+
+/// The `[trust]` section, deciding which signatures are accepted on fetch.
+/// Entries for a package and for its registry both apply.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct TrustPolicy {
+  #[serde(default)]
+  pub registries: HashMap<String, TrustedKeys>,
+  #[serde(default)]
+  pub packages: HashMap<String, TrustedKeys>,
+}
+
+impl TrustPolicy {
+  /// The keys of the registry's and the package's entries together,
+  /// requiring a signature if either of them does.
+  pub fn keys_for(&self, registry_url: &str, package: &str) -> Option<TrustedKeys> {
+    let entries: Vec<&TrustedKeys> = self
+      .registries
+      .get(registry_url)
+      .into_iter()
+      .chain(self.packages.get(package))
+      .collect();
+    if entries.is_empty() {
+      return None;
+    }
+    let mut trusted = TrustedKeys::default();
+    for entry in entries {
+      for key in &entry.keys {
+        if !trusted.keys.contains(key) {
+          trusted.keys.push(key.clone());
+        }
+      }
+      trusted.require_signature |= entry.require_signature;
+    }
+    Some(trusted)
+  }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct BuffCliConfig {
   pub preferred_registry: String,
   registries: HashMap<String, RegistryConfig>,
+  #[serde(default)]
+  pub trust: TrustPolicy,
 }
 
 pub fn get_default_registry_url() -> String {
@@ -38,6 +87,7 @@ impl BuffCliConfig {
     let mut config = BuffCliConfig {
       preferred_registry: get_default_registry_url(),
       registries: HashMap::new(),
+      trust: TrustPolicy::default(),
     };
     if path.exists() {
       let toml_content = fs::read_to_string(path).unwrap();
@@ -68,16 +118,18 @@ impl BuffCliConfig {
   }
 }
 
-fn get_config_path() -> PathBuf {
+/// Where buff keeps its config and keys, `$BUFF_HOME` if set.
+pub fn buff_home() -> PathBuf {
   match env::var("BUFF_HOME") {
-    Ok(s) => std::env::current_dir()
-      .unwrap()
-      .join(&s)
-      .join("config.toml"),
-    _ => config_dir().unwrap().join("buff/config.toml"),
+    Ok(s) => std::env::current_dir().unwrap().join(&s),
+    _ => config_dir().unwrap().join("buff"),
   }
 }
 
+fn get_config_path() -> PathBuf {
+  buff_home().join("config.toml")
+}
+
 #[test]
 fn should_new() {
   env::set_var("BUFF_HOME", "../tests/fixtures/buff_home");
@@ -96,6 +148,22 @@ fn should_new() {
       token: "token2".to_string()
     }
   );
+  let trusted = config
+    .trust
+    .keys_for("localhost:50051", "yummy_package")
+    .unwrap();
+  assert!(trusted.require_signature);
+  assert_eq!(trusted.keys.len(), 2);
+  let trusted = config
+    .trust
+    .keys_for("localhost:50052", "other_package")
+    .unwrap();
+  assert!(trusted.require_signature);
+  assert_eq!(trusted.keys.len(), 1);
+  assert!(config
+    .trust
+    .keys_for("localhost:50053", "unknown_package")
+    .is_none());
 }
 
 #[test]
@@ -0,0 +1,242 @@
+use crate::buff_cli_config::BuffCliConfig;
+use crate::compat::Compatibility;
+use crate::dependencies;
+use crate::diff::{self, Change, ChangeKind};
+use crate::manifest;
+use crate::package_metadata::PackageMetadata;
+use crate::proto;
+use crate::proto::SourceFile;
+use crate::registry;
+use serde_derive::Serialize;
+use std::fmt::Write;
+use std::path::Path;
+
+#[cfg(test)]
+use crate::proto::sources;
+
+/// What changed in a package's schema between two versions, split by
+/// whether the changes break consumers of the older one.
+#[derive(Debug, Serialize)]
+pub struct Changelog {
+  pub package: String,
+  pub from: String,
+  pub to: String,
+  pub breaking: Vec<Change>,
+  pub non_breaking: Vec<Change>,
+}
+
+impl Changelog {
+  /// Compares `previous` to `current` with the guarantees `current` is held
+  /// to.
+  pub fn new(
+    package: &str,
+    from: &str,
+    to: &str,
+    previous: &[SourceFile],
+    current: &[SourceFile],
+    compatibility: &Compatibility,
+  ) -> Self {
+    let (breaking, non_breaking) = diff::diff_with(previous, current, compatibility)
+      .into_iter()
+      .partition(Change::is_breaking);
+    Changelog {
+      package: package.to_string(),
+      from: from.to_string(),
+      to: to.to_string(),
+      breaking,
+      non_breaking,
+    }
+  }
+
+  pub fn to_markdown(&self) -> String {
+    let mut markdown = format!("# {} {}..{}\n", self.package, self.from, self.to);
+    if self.breaking.is_empty() && self.non_breaking.is_empty() {
+      markdown.push_str("\nNo changes to the schema.\n");
+      return markdown;
+    }
+    let sections = [
+      ("Breaking changes", &self.breaking),
+      ("Non-breaking changes", &self.non_breaking),
+    ];
+    for (title, changes) in sections.iter() {
+      if changes.is_empty() {
+        continue;
+      }
+      write!(markdown, "\n## {}\n", title).unwrap();
+      let kinds = [
+        ("Added", ChangeKind::Added),
+        ("Removed", ChangeKind::Removed),
+        ("Renamed", ChangeKind::Renamed),
+        ("Moved", ChangeKind::Moved),
+        ("Changed", ChangeKind::Changed),
+      ];
+      for (heading, kind) in kinds.iter() {
+        let of_kind: Vec<&Change> = changes
+          .iter()
+          .filter(|change| change.kind == *kind)
+          .collect();
+        if of_kind.is_empty() {
+          continue;
+        }
+        write!(markdown, "\n### {}\n\n", heading).unwrap();
+        for change in of_kind {
+          write!(markdown, "- {} `{}`", change.element, change.path).unwrap();
+          if let Some(ref to) = change.to {
+            write!(markdown, " to `{}`", to).unwrap();
+          }
+          if !change.details.is_empty() {
+            write!(markdown, ": {}", change.details.join(", ")).unwrap();
+          }
+          if change.is_breaking() {
+            write!(markdown, " ({})", change.breaking.join(", ")).unwrap();
+          }
+          markdown.push('\n');
+        }
+      }
+    }
+    markdown
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).expect("Failed to serialize changelog")
+  }
+}
+
+/// Splits a `<from>..<to>` range of versions.
+pub fn parse_range(range: &str) -> Result<(String, String), String> {
+  let mut parts = range.splitn(2, "..");
+  match (parts.next(), parts.next()) {
+    (Some(from), Some(to)) if !from.is_empty() && !to.is_empty() => {
+      Ok((from.to_string(), to.to_string()))
+    }
+    _ => Err(format!(
+      "invalid range `{}`, expected <from>..<to>, e.g. 1.0.0..1.1.0",
+      range
+    )),
+  }
+}
+
+/// The changelog between two published versions of `name`, with the
+/// guarantees set in the `[compatibility]` section of the newer one.
+pub fn between_versions(registry_url: &str, name: &str, from: &str, to: &str) -> Changelog {
+  let previous = registry::fetch(registry_url, name, from)
+    .unwrap_or_else(|err| panic!("Failed to fetch {} {}: {}", name, from, err));
+  let current = registry::fetch(registry_url, name, to)
+    .unwrap_or_else(|err| panic!("Failed to fetch {} {}: {}", name, to, err));
+  Changelog::new(
+    name,
+    from,
+    to,
+    &proto::parse_sources(
+      previous
+        .proto_sources()
+        .unwrap_or_else(|err| panic!("Failed to read {} {}: {}", name, from, err)),
+    )
+    .unwrap_or_else(|err| panic!("Failed to parse {} {}: {}", name, from, err)),
+    &proto::parse_sources(
+      current
+        .proto_sources()
+        .unwrap_or_else(|err| panic!("Failed to read {} {}: {}", name, to, err)),
+    )
+    .unwrap_or_else(|err| panic!("Failed to parse {} {}: {}", name, to, err)),
+    &current.metadata().compatibility,
+  )
+}
+
+/// The changelog of the package at `manifest_path` since the latest version
+/// of it published to the preferred registry, `None` if it hasn't been
+/// published.
+pub fn since_published(manifest_path: &Path) -> Option<Changelog> {
+  let metadata = PackageMetadata::new(manifest_path.to_str().unwrap());
+  let name = &metadata.package.name;
+  let registry_url = BuffCliConfig::new().preferred_registry;
+  let versions = registry::get_package_versions(&registry_url, name)
+    .unwrap_or_else(|err| panic!("Failed to fetch versions of `{}`: {}", name, err));
+  let latest = dependencies::select_version(&versions, None)?.to_string();
+  let previous = registry::fetch(&registry_url, name, &latest)
+    .unwrap_or_else(|err| panic!("Failed to fetch {} {}: {}", name, latest, err));
+  let current = proto::parse_dir(&manifest::package_dir(manifest_path))
+    .unwrap_or_else(|err| panic!("Failed to parse {}", err));
+  Some(Changelog::new(
+    name,
+    &latest,
+    &format!("{} (working tree)", metadata.package.version),
+    &proto::parse_sources(
+      previous
+        .proto_sources()
+        .unwrap_or_else(|err| panic!("Failed to read {} {}: {}", name, latest, err)),
+    )
+    .unwrap_or_else(|err| panic!("Failed to parse {} {}: {}", name, latest, err)),
+    &current,
+    &metadata.compatibility,
+  ))
+}
+
+#[test]
+fn should_render_changelogs_as_markdown() {
+  let previous = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { string id = 1; int32 count = 2; }
+     service Orders { rpc Get (Order) returns (Order); rpc Cancel (Order) returns (Order); }",
+  );
+  let current = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { string id = 1; int64 count = 2; string note = 3; }
+     service Orders { rpc Get (Order) returns (Order); }",
+  );
+  let changelog = Changelog::new(
+    "acme.orders",
+    "1.0.0",
+    "2.0.0",
+    &previous,
+    &current,
+    &Compatibility::default(),
+  );
+  assert_eq!(
+    changelog.to_markdown(),
+    "# acme.orders 1.0.0..2.0.0
+
+## Breaking changes
+
+### Removed
+
+- rpc `acme.v1.Orders.Cancel` (RPC_REMOVED)
+
+### Changed
+
+- field `acme.v1.Order.count`: type changed from `int32` to `int64` (FIELD_TYPE_CHANGED)
+
+## Non-breaking changes
+
+### Added
+
+- field `acme.v1.Order.note`: number 3
+"
+  );
+  let json = serde_json::to_value(&changelog).unwrap();
+  assert_eq!(json["breaking"][0]["kind"], "removed");
+  assert_eq!(json["breaking"][0]["breaking"][0], "RPC_REMOVED");
+  assert_eq!(json["non_breaking"][0]["element"], "field");
+  let unchanged = Changelog::new(
+    "acme.orders",
+    "1.0.0",
+    "1.0.1",
+    &previous,
+    &previous,
+    &Compatibility::default(),
+  );
+  assert_eq!(
+    unchanged.to_markdown(),
+    "# acme.orders 1.0.0..1.0.1\n\nNo changes to the schema.\n"
+  );
+}
+
+#[test]
+fn should_parse_version_ranges() {
+  assert_eq!(
+    parse_range("1.0.0..1.2.0"),
+    Ok(("1.0.0".to_string(), "1.2.0".to_string()))
+  );
+  assert!(parse_range("1.0.0").is_err());
+  assert!(parse_range("..1.2.0").is_err());
+}
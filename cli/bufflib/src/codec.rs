@@ -0,0 +1,195 @@
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::de::{self, Deserialize, Deserializer};
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+use std::str::FromStr;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// How an artifact's tar stream is compressed. Written as `gzip`, `zstd` or
+/// `none`, optionally followed by a level, e.g. `zstd:19`.
+///
+/// Readers don't need to be told the codec: each one leaves its own magic
+/// bytes at the start of the artifact, and a plain tar has none.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Codec {
+  Gzip { level: u32 },
+  Zstd { level: u32 },
+  None,
+}
+
+impl Default for Codec {
+  fn default() -> Self {
+    Codec::Gzip {
+      level: Compression::default().level(),
+    }
+  }
+}
+
+impl Codec {
+  pub fn name(&self) -> &'static str {
+    match self {
+      Codec::Gzip { .. } => "gzip",
+      Codec::Zstd { .. } => "zstd",
+      Codec::None => "none",
+    }
+  }
+
+  pub fn level(&self) -> u32 {
+    match self {
+      Codec::Gzip { level } | Codec::Zstd { level } => *level,
+      Codec::None => 0,
+    }
+  }
+
+  /// File extension for artifacts written with this codec.
+  pub fn extension(&self) -> &'static str {
+    match self {
+      Codec::Gzip { .. } => "tar.gz",
+      Codec::Zstd { .. } => "tar.zst",
+      Codec::None => "tar",
+    }
+  }
+
+  /// The codec an artifact was written with, from its leading bytes. Levels
+  /// can't be recovered this way and are reported as 0.
+  pub fn detect(bytes: &[u8]) -> Self {
+    if bytes.starts_with(GZIP_MAGIC) {
+      Codec::Gzip { level: 0 }
+    } else if bytes.starts_with(ZSTD_MAGIC) {
+      Codec::Zstd { level: 0 }
+    } else {
+      Codec::None
+    }
+  }
+
+  pub fn encoder<W: Write>(&self, output: W) -> io::Result<Encoder<W>> {
+    Ok(match self {
+      Codec::Gzip { level } => Encoder::Gzip(GzEncoder::new(output, Compression::new(*level))),
+      Codec::Zstd { level } => {
+        Encoder::Zstd(zstd::stream::write::Encoder::new(output, *level as i32)?)
+      }
+      Codec::None => Encoder::None(output),
+    })
+  }
+}
+
+/// Decompresses `input` with whichever codec wrote it.
+pub fn decoder<'a, R: BufRead + 'a>(mut input: R) -> io::Result<Box<dyn Read + 'a>> {
+  Ok(match Codec::detect(input.fill_buf()?) {
+    Codec::Gzip { .. } => Box::new(GzDecoder::new(input)),
+    Codec::Zstd { .. } => Box::new(zstd::stream::read::Decoder::new(input)?),
+    Codec::None => Box::new(input),
+  })
+}
+
+impl fmt::Display for Codec {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Codec::None => write!(f, "none"),
+      codec => write!(f, "{}:{}", codec.name(), codec.level()),
+    }
+  }
+}
+
+impl FromStr for Codec {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut parts = s.splitn(2, ':');
+    let name = parts.next().unwrap_or("");
+    let level = match parts.next() {
+      Some(level) => Some(
+        level
+          .parse::<u32>()
+          .map_err(|_| format!("invalid compression level `{}`", level))?,
+      ),
+      None => None,
+    };
+    let codec = match name {
+      "gzip" => Codec::Gzip {
+        level: level.unwrap_or_else(|| Codec::default().level()),
+      },
+      "zstd" => Codec::Zstd {
+        level: level.unwrap_or(zstd::DEFAULT_COMPRESSION_LEVEL as u32),
+      },
+      "none" if level.is_none() => Codec::None,
+      "none" => return Err("`none` doesn't take a level".to_string()),
+      _ => {
+        return Err(format!(
+          "unknown compression `{}`, expected gzip, zstd or none",
+          name
+        ))
+      }
+    };
+    let (min_level, max_level) = match codec {
+      Codec::Gzip { .. } => (0, 9),
+      Codec::Zstd { .. } => (1, 21),
+      Codec::None => (0, 0),
+    };
+    if codec.level() < min_level || codec.level() > max_level {
+      return Err(format!(
+        "{} levels go from {} to {}",
+        name, min_level, max_level
+      ));
+    }
+    Ok(codec)
+  }
+}
+
+impl<'de> Deserialize<'de> for Codec {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(de::Error::custom)
+  }
+}
+
+/// A compressing writer for one of the codecs.
+pub enum Encoder<W: Write> {
+  Gzip(GzEncoder<W>),
+  Zstd(zstd::stream::write::Encoder<W>),
+  None(W),
+}
+
+impl<W: Write> Encoder<W> {
+  /// Writes out whatever the codec still buffers and returns the writer.
+  pub fn finish(self) -> io::Result<W> {
+    match self {
+      Encoder::Gzip(encoder) => encoder.finish(),
+      Encoder::Zstd(encoder) => encoder.finish(),
+      Encoder::None(output) => Ok(output),
+    }
+  }
+}
+
+impl<W: Write> Write for Encoder<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    match self {
+      Encoder::Gzip(encoder) => encoder.write(buf),
+      Encoder::Zstd(encoder) => encoder.write(buf),
+      Encoder::None(output) => output.write(buf),
+    }
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    match self {
+      Encoder::Gzip(encoder) => encoder.flush(),
+      Encoder::Zstd(encoder) => encoder.flush(),
+      Encoder::None(output) => output.flush(),
+    }
+  }
+}
+
+#[test]
+fn should_parse_codecs() {
+  assert_eq!("gzip".parse(), Ok(Codec::Gzip { level: 6 }));
+  assert_eq!("gzip:9".parse(), Ok(Codec::Gzip { level: 9 }));
+  assert_eq!("zstd:19".parse(), Ok(Codec::Zstd { level: 19 }));
+  assert_eq!("none".parse(), Ok(Codec::None));
+  assert!("gzip:10".parse::<Codec>().is_err());
+  assert!("zstd:0".parse::<Codec>().is_err());
+  assert!("none:1".parse::<Codec>().is_err());
+  assert!("brotli".parse::<Codec>().is_err());
+  assert_eq!(Codec::Zstd { level: 3 }.to_string(), "zstd:3");
+}
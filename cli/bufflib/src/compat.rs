@@ -0,0 +1,1512 @@
+use crate::artifact::Artifact;
+use crate::buff_cli_config::BuffCliConfig;
+use crate::dependencies;
+use crate::git;
+use crate::manifest;
+use crate::package_metadata::PackageMetadata;
+use crate::proto;
+use crate::proto::{qualify, Enum, EnumValue, Field, Label, Message, Rpc, Service, SourceFile};
+use crate::protobuffers::buff::{CompatibilityRejection, CompatibilityViolation};
+use crate::registry;
+use chrono::{Local, NaiveDate};
+use semver::Version;
+use serde::de::{self, Deserialize, Deserializer};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+#[cfg(test)]
+use crate::proto::sources;
+
+const SCALAR_TYPES: &[&str] = &[
+  "double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64", "fixed32",
+  "fixed64", "sfixed32", "sfixed64", "bool", "string", "bytes",
+];
+
+/// Types whose values are encoded the same way on the wire, so a field can
+/// change between them. Enums are encoded as varints.
+const WIRE_COMPATIBLE_TYPES: &[&[&str]] = &[
+  &["int32", "uint32", "int64", "uint64", "bool", "enum"],
+  &["sint32", "sint64"],
+  &["fixed32", "sfixed32"],
+  &["fixed64", "sfixed64"],
+  &["string", "bytes"],
+];
+
+/// The subset of `WIRE_COMPATIBLE_TYPES` that also share a JSON encoding,
+/// e.g. 64-bit integers are JSON strings while 32-bit ones are numbers.
+const JSON_COMPATIBLE_TYPES: &[&[&str]] = &[
+  &["int32", "uint32"],
+  &["int64", "uint64"],
+  &["fixed32", "sfixed32"],
+  &["fixed64", "sfixed64"],
+];
+
+/// Rules that, checked in reverse for forward compatibility, only flag
+/// additions, which readers of the previous version skip as unknown fields
+/// and values.
+const ADDITION_RULES: &[&str] = &["FIELD_REMOVED", "ENUM_VALUE_REMOVED"];
+
+/// A change that breaks consumers of the previous version of a schema.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+  pub rule: &'static str,
+  /// Fully-qualified name of the element, e.g. `acme.v1.User.legacy_id`.
+  pub path: String,
+  pub message: String,
+}
+
+impl fmt::Display for Violation {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} {}: {}", self.rule, self.path, self.message)
+  }
+}
+
+/// Which guarantees a new version of a schema has to keep, each one including
+/// the ones before it.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+  /// Data encoded with one version decodes with the other.
+  Wire,
+  /// Also the JSON encoding, so field and enum value names have to stay.
+  WireJson,
+  /// Also code generated from the schema keeps compiling, so nothing that
+  /// is used by name may be removed, renamed or change type.
+  Source,
+}
+
+/// Which side of a change has to keep working.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+  /// Consumers of the previous version work with the new one.
+  Backward,
+  /// Consumers of the new version work with the previous one.
+  Forward,
+  Full,
+}
+
+/// The `[compatibility]` section of buff.toml, e.g.
+///
+/// ```toml
+/// [compatibility]
+/// mode = "wire_json"
+/// direction = "backward_transitive"
+/// ```
+///
+/// `direction` is one of `backward`, `forward` or `full`, optionally with a
+/// `_transitive` suffix to check against every earlier release instead of
+/// only the latest one, across major lines. The default is `source` and
+/// `backward`.
+///
+/// Intentional breaking changes are let through with `ignore` entries, e.g.
+/// `ignore = [{ rule = "RPC_REMOVED", path = "acme.v1.Orders.Cancel" }]`,
+/// or `// buff:ignore RULE` comments in the .proto files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Compatibility {
+  pub mode: Mode,
+  pub direction: Direction,
+  pub transitive: bool,
+  pub ignore: Vec<Suppression>,
+}
+
+impl Default for Compatibility {
+  fn default() -> Self {
+    Compatibility {
+      mode: Mode::Source,
+      direction: Direction::Backward,
+      transitive: false,
+      ignore: Vec::new(),
+    }
+  }
+}
+
+/// An `ignore` entry of `[compatibility]`. It covers the element at `path`
+/// and everything inside it, until the end of the `expires` day if set.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Suppression {
+  pub rule: String,
+  pub path: String,
+  /// A `YYYY-MM-DD` date.
+  pub expires: Option<String>,
+}
+
+const EXPIRY_FORMAT: &str = "%Y-%m-%d";
+
+impl FromStr for Direction {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "backward" => Ok(Direction::Backward),
+      "forward" => Ok(Direction::Forward),
+      "full" => Ok(Direction::Full),
+      _ => Err(format!(
+        "unknown compatibility direction `{}`, expected backward, forward or full",
+        s
+      )),
+    }
+  }
+}
+
+impl<'de> Deserialize<'de> for Compatibility {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    #[derive(Deserialize)]
+    struct Section {
+      mode: Option<Mode>,
+      direction: Option<String>,
+      #[serde(default)]
+      ignore: Vec<Suppression>,
+    }
+    let section = Section::deserialize(deserializer)?;
+    for suppression in &section.ignore {
+      if let Some(expires) = &suppression.expires {
+        NaiveDate::parse_from_str(expires, EXPIRY_FORMAT).map_err(|_| {
+          de::Error::custom(format!(
+            "invalid expiry `{}` for {} {}, expected YYYY-MM-DD",
+            expires, suppression.rule, suppression.path
+          ))
+        })?;
+      }
+    }
+    let mut compatibility = Compatibility {
+      ignore: section.ignore,
+      ..Compatibility::default()
+    };
+    if let Some(mode) = section.mode {
+      compatibility.mode = mode;
+    }
+    if let Some(direction) = section.direction {
+      let (direction, transitive) = if direction.ends_with("_transitive") {
+        (&direction[..direction.len() - "_transitive".len()], true)
+      } else {
+        (direction.as_str(), false)
+      };
+      compatibility.direction = direction.parse().map_err(de::Error::custom)?;
+      compatibility.transitive = transitive;
+    }
+    Ok(compatibility)
+  }
+}
+
+/// What a package's schema is checked against: its latest published version,
+/// written `registry`, or the package as of a git revision, e.g. `git:main`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Baseline {
+  Registry,
+  Git(String),
+}
+
+impl Default for Baseline {
+  fn default() -> Self {
+    Baseline::Registry
+  }
+}
+
+impl fmt::Display for Baseline {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Baseline::Registry => write!(f, "registry"),
+      Baseline::Git(revision) => write!(f, "git:{}", revision),
+    }
+  }
+}
+
+impl FromStr for Baseline {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s == "registry" {
+      Ok(Baseline::Registry)
+    } else if s.starts_with("git:") && s.len() > 4 {
+      Ok(Baseline::Git(s[4..].to_string()))
+    } else {
+      Err(format!(
+        "unknown baseline `{}`, expected registry or git:<revision>",
+        s
+      ))
+    }
+  }
+}
+
+/// The outcome of checking a package against a baseline.
+pub struct PackageCheck {
+  pub name: String,
+  pub version: String,
+  /// What the package was compared to, e.g. the published version, `None`
+  /// if there was nothing to compare to.
+  pub baseline: Option<String>,
+  /// The version is a major bump over the latest published one, so breaking
+  /// changes are allowed and nothing was compared.
+  pub major_bump: bool,
+  pub violations: Vec<Violation>,
+  /// Breaking changes let through by `ignore` entries or comments.
+  pub suppressed: Vec<Violation>,
+  /// Suppressions that expired or didn't match anything.
+  pub stale_suppressions: Vec<String>,
+}
+
+struct Suppressor {
+  rule: String,
+  path: String,
+  /// Where the suppression is written, e.g. `buff.toml`.
+  origin: String,
+  expired_on: Option<NaiveDate>,
+  used: bool,
+}
+
+/// The `ignore` entries and `buff:ignore` comments that apply to a check,
+/// keeping track of which ones were used.
+pub struct Suppressions {
+  suppressors: Vec<Suppressor>,
+  /// The rules of `buff:ignore` comments not attached to any element.
+  detached: Vec<(String, String)>,
+}
+
+impl Suppressions {
+  /// Collects the configured suppressions and the comments in `current`,
+  /// the version being checked. Entries that expired before `today` don't
+  /// suppress anything.
+  pub fn new(configured: &[Suppression], current: &[SourceFile], today: NaiveDate) -> Self {
+    let mut suppressors: Vec<Suppressor> = configured
+      .iter()
+      .map(|suppression| Suppressor {
+        rule: suppression.rule.clone(),
+        path: suppression.path.clone(),
+        origin: "buff.toml".to_string(),
+        expired_on: suppression
+          .expires
+          .as_ref()
+          .and_then(|expires| NaiveDate::parse_from_str(expires, EXPIRY_FORMAT).ok())
+          .filter(|expires| *expires < today),
+        used: false,
+      })
+      .collect();
+    let mut detached: Vec<(String, String)> = Vec::new();
+    for source in current {
+      for ignore in &source.file.ignores {
+        let origin = format!("{}:{}", source.path.display(), ignore.line);
+        for rule in &ignore.rules {
+          match &ignore.path {
+            Some(path) => suppressors.push(Suppressor {
+              rule: rule.clone(),
+              path: path.clone(),
+              origin: origin.clone(),
+              expired_on: None,
+              used: false,
+            }),
+            None => detached.push((origin.clone(), rule.clone())),
+          }
+        }
+      }
+    }
+    Suppressions {
+      suppressors,
+      detached,
+    }
+  }
+
+  /// Whether `violation` is suppressed, marking what suppressed it as used.
+  pub fn suppresses(&mut self, violation: &Violation) -> bool {
+    let mut suppressed = false;
+    for suppressor in &mut self.suppressors {
+      let covers = suppressor.path.is_empty()
+        || violation.path == suppressor.path
+        || violation.path.starts_with(&format!("{}.", suppressor.path));
+      if suppressor.rule == violation.rule && covers && suppressor.expired_on.is_none() {
+        suppressor.used = true;
+        suppressed = true;
+      }
+    }
+    suppressed
+  }
+
+  /// Suppressions that expired or weren't used, so they can be cleaned up.
+  pub fn stale(&self) -> Vec<String> {
+    self
+      .suppressors
+      .iter()
+      .filter_map(|suppressor| match suppressor.expired_on {
+        Some(expired_on) => Some(format!(
+          "{}: ignore {} {} expired on {}",
+          suppressor.origin, suppressor.rule, suppressor.path, expired_on
+        )),
+        None if !suppressor.used => Some(format!(
+          "{}: ignore {} {} doesn't match any breaking change",
+          suppressor.origin, suppressor.rule, suppressor.path
+        )),
+        None => None,
+      })
+      .chain(self.detached.iter().map(|(origin, rule)| {
+        format!("{}: ignore {} doesn't precede any definition", origin, rule)
+      }))
+      .collect()
+  }
+}
+
+/// The definitions of a set of files by fully-qualified name.
+#[derive(Default)]
+pub(crate) struct Schema<'a> {
+  pub(crate) messages: BTreeMap<String, &'a Message>,
+  pub(crate) enums: BTreeMap<String, &'a Enum>,
+  pub(crate) services: BTreeMap<String, &'a Service>,
+  /// The file each top-level definition is in.
+  pub(crate) files: BTreeMap<String, &'a Path>,
+}
+
+impl<'a> Schema<'a> {
+  pub(crate) fn new(files: &'a [SourceFile]) -> Self {
+    let mut schema = Schema::default();
+    for source in files {
+      let scope = source.file.package.clone().unwrap_or_default();
+      let path = source.path.as_path();
+      for message in &source.file.messages {
+        schema.add_message(&scope, message);
+        schema.files.insert(qualify(&scope, &message.name), path);
+      }
+      for enumeration in &source.file.enums {
+        let name = qualify(&scope, &enumeration.name);
+        schema.enums.insert(name.clone(), enumeration);
+        schema.files.insert(name, path);
+      }
+      for service in &source.file.services {
+        let name = qualify(&scope, &service.name);
+        schema.services.insert(name.clone(), service);
+        schema.files.insert(name, path);
+      }
+    }
+    schema
+  }
+
+  fn add_message(&mut self, scope: &str, message: &'a Message) {
+    let name = qualify(scope, &message.name);
+    for nested in &message.messages {
+      self.add_message(&name, nested);
+    }
+    for enumeration in &message.enums {
+      self
+        .enums
+        .insert(qualify(&name, &enumeration.name), enumeration);
+    }
+    self.messages.insert(name, message);
+  }
+
+  /// Resolves a type reference the way protoc does, from the innermost scope
+  /// outwards, so that `Item` and `.acme.v1.Order.Item` compare equal. Types
+  /// that aren't defined in the schema, e.g. ones from dependencies, are
+  /// compared as written.
+  pub(crate) fn resolve(&self, scope: &str, type_name: &str) -> String {
+    if type_name.starts_with("map<") && type_name.ends_with('>') {
+      let mut parts = type_name[4..type_name.len() - 1].splitn(2, ',');
+      let key = parts.next().unwrap_or("").trim();
+      let value = parts.next().unwrap_or("").trim();
+      return format!("map<{}, {}>", key, self.resolve(scope, value));
+    }
+    if type_name.starts_with('.') {
+      return type_name[1..].to_string();
+    }
+    if SCALAR_TYPES.contains(&type_name) {
+      return type_name.to_string();
+    }
+    let mut scope = scope;
+    loop {
+      let candidate = qualify(scope, type_name);
+      if self.messages.contains_key(&candidate) || self.enums.contains_key(&candidate) {
+        return candidate;
+      }
+      if scope.is_empty() {
+        return type_name.to_string();
+      }
+      scope = scope.rfind('.').map_or("", |i| &scope[..i]);
+    }
+  }
+
+  /// A resolved type as far as the wire is concerned, `enum` for enums.
+  fn wire_type<'b>(&self, resolved: &'b str) -> &'b str {
+    if self.enums.contains_key(resolved) {
+      "enum"
+    } else {
+      resolved
+    }
+  }
+}
+
+pub(crate) fn parent_scope(name: &str) -> &str {
+  name.rfind('.').map_or("", |i| &name[..i])
+}
+
+/// Compares two versions of a package's schema and returns the changes in
+/// `current` that break source compatibility for consumers of `previous`.
+/// Elements are matched by fully-qualified name, so moving definitions
+/// between files is fine.
+pub fn check(previous: &[SourceFile], current: &[SourceFile]) -> Vec<Violation> {
+  check_mode(previous, current, Mode::Source)
+}
+
+/// Like `check`, for the guarantees of `compatibility`. Forward checks
+/// compare the versions the other way around, and their violations are
+/// marked `(forward)`.
+pub fn check_compatibility(
+  previous: &[SourceFile],
+  current: &[SourceFile],
+  compatibility: &Compatibility,
+) -> Vec<Violation> {
+  let mut violations = Vec::new();
+  if compatibility.direction != Direction::Forward {
+    violations = check_mode(previous, current, compatibility.mode);
+  }
+  if compatibility.direction != Direction::Backward {
+    for mut violation in check_mode(current, previous, compatibility.mode) {
+      let reported = violations
+        .iter()
+        .any(|other| other.rule == violation.rule && other.path == violation.path);
+      if breaks_forward(compatibility.mode, violation.rule) && !reported {
+        violation.message = format!("{} (forward)", violation.message);
+        violations.push(violation);
+      }
+    }
+  }
+  violations
+}
+
+/// Whether a violation found comparing the versions the other way around
+/// breaks forward compatibility in `mode`.
+fn breaks_forward(mode: Mode, rule: &str) -> bool {
+  mode == Mode::Source || !ADDITION_RULES.contains(&rule)
+}
+
+/// The rules one change to an element breaks under `compatibility`, from
+/// the violations comparing the element's versions `backward`, previous to
+/// current, and `forward`, the other way around.
+pub(crate) fn breaking_rules(
+  compatibility: &Compatibility,
+  backward: Vec<Violation>,
+  forward: Vec<Violation>,
+) -> Vec<&'static str> {
+  let mut rules: Vec<&'static str> = Vec::new();
+  if compatibility.direction != Direction::Forward {
+    rules.extend(backward.iter().map(|violation| violation.rule));
+  }
+  if compatibility.direction != Direction::Backward {
+    rules.extend(
+      forward
+        .iter()
+        .map(|violation| violation.rule)
+        .filter(|rule| breaks_forward(compatibility.mode, rule)),
+    );
+  }
+  let mut unique: Vec<&'static str> = Vec::new();
+  for rule in rules {
+    if !unique.contains(&rule) {
+      unique.push(rule);
+    }
+  }
+  unique
+}
+
+fn check_mode(previous: &[SourceFile], current: &[SourceFile], mode: Mode) -> Vec<Violation> {
+  let old = Schema::new(previous);
+  let new = Schema::new(current);
+  let mut violations = Vec::new();
+  for (name, old_message) in &old.messages {
+    match new.messages.get(name) {
+      Some(new_message) => {
+        for old_field in &old_message.fields {
+          match new_message
+            .fields
+            .iter()
+            .find(|field| field.number == old_field.number)
+          {
+            Some(new_field) => {
+              violations.extend(check_field(&old, &new, mode, name, old_field, new_field))
+            }
+            None => violations.extend(field_removed(name, old_field, new_message)),
+          }
+        }
+      }
+      None => violations.extend(message_removed(mode, name)),
+    }
+  }
+  for (name, old_enum) in &old.enums {
+    match new.enums.get(name) {
+      Some(new_enum) => {
+        for old_value in &old_enum.values {
+          violations.extend(check_enum_value(mode, name, old_value, new_enum));
+        }
+      }
+      None => violations.extend(enum_removed(mode, name)),
+    }
+  }
+  for (name, old_service) in &old.services {
+    match new.services.get(name) {
+      Some(new_service) => {
+        for old_rpc in &old_service.rpcs {
+          match new_service.rpcs.iter().find(|rpc| rpc.name == old_rpc.name) {
+            Some(new_rpc) => violations.extend(check_rpc(&old, &new, name, old_rpc, new_rpc)),
+            None => violations.push(rpc_removed(name, old_rpc)),
+          }
+        }
+      }
+      None => violations.push(service_removed(name)),
+    }
+  }
+  violations
+}
+
+// Messages and enums only matter on the wire through the fields
+// and rpcs that use them, which are checked on their own.
+pub(crate) fn message_removed(mode: Mode, name: &str) -> Option<Violation> {
+  if mode < Mode::Source {
+    return None;
+  }
+  Some(Violation {
+    rule: "MESSAGE_REMOVED",
+    path: name.to_string(),
+    message: "message was removed".to_string(),
+  })
+}
+
+pub(crate) fn enum_removed(mode: Mode, name: &str) -> Option<Violation> {
+  if mode < Mode::Source {
+    return None;
+  }
+  Some(Violation {
+    rule: "ENUM_REMOVED",
+    path: name.to_string(),
+    message: "enum was removed".to_string(),
+  })
+}
+
+pub(crate) fn service_removed(name: &str) -> Violation {
+  Violation {
+    rule: "SERVICE_REMOVED",
+    path: name.to_string(),
+    message: "service was removed".to_string(),
+  }
+}
+
+/// `old_field` of the message `name` going missing from `new_message`,
+/// which only breaks consumers if its number isn't reserved.
+pub(crate) fn field_removed(
+  name: &str,
+  old_field: &Field,
+  new_message: &Message,
+) -> Option<Violation> {
+  if new_message.reserved.contains_number(old_field.number) {
+    return None;
+  }
+  Some(Violation {
+    rule: "FIELD_REMOVED",
+    path: format!("{}.{}", name, old_field.name),
+    message: format!(
+      "field {} was removed without reserving its number",
+      old_field.number
+    ),
+  })
+}
+
+/// The changes from `old_field` of the message `name` to `new_field`, the
+/// field with the same number, that break consumers in `mode`.
+pub(crate) fn check_field(
+  old: &Schema,
+  new: &Schema,
+  mode: Mode,
+  name: &str,
+  old_field: &Field,
+  new_field: &Field,
+) -> Vec<Violation> {
+  let mut violations = Vec::new();
+  let path = format!("{}.{}", name, old_field.name);
+  if mode == Mode::Source && new_field.name != old_field.name {
+    violations.push(Violation {
+      rule: "FIELD_NAME_CHANGED",
+      path: path.clone(),
+      message: format!(
+        "field {} was renamed to `{}`",
+        old_field.number, new_field.name
+      ),
+    });
+  } else if mode >= Mode::WireJson && json_name(old_field) != json_name(new_field) {
+    violations.push(Violation {
+      rule: "FIELD_JSON_NAME_CHANGED",
+      path: path.clone(),
+      message: format!(
+        "JSON name changed from `{}` to `{}`",
+        json_name(old_field),
+        json_name(new_field)
+      ),
+    });
+  }
+  let old_type = old.resolve(name, &old_field.type_name);
+  let new_type = new.resolve(name, &new_field.type_name);
+  let compatible = old_type == new_type
+    || (mode < Mode::Source
+      && types_compatible(mode, old.wire_type(&old_type), new.wire_type(&new_type)));
+  if !compatible {
+    violations.push(Violation {
+      rule: "FIELD_TYPE_CHANGED",
+      path: path.clone(),
+      message: format!("type changed from `{}` to `{}`", old_type, new_type),
+    });
+  }
+  if old_field.label != new_field.label
+    && [old_field.label, new_field.label]
+      .iter()
+      .any(|label| *label == Label::Repeated || *label == Label::Required)
+  {
+    violations.push(Violation {
+      rule: "FIELD_LABEL_CHANGED",
+      path: path.clone(),
+      message: format!(
+        "label changed from {} to {}",
+        label_name(old_field.label),
+        label_name(new_field.label)
+      ),
+    });
+  }
+  if old_field.oneof != new_field.oneof {
+    violations.push(Violation {
+      rule: "FIELD_ONEOF_CHANGED",
+      path,
+      message: format!(
+        "moved from {} to {}",
+        oneof_name(&old_field.oneof),
+        oneof_name(&new_field.oneof)
+      ),
+    });
+  }
+  violations
+}
+
+/// The name a field has in the JSON encoding, its `json_name` option or else
+/// its name in lowerCamelCase.
+pub(crate) fn json_name(field: &Field) -> String {
+  if let Some(option) = field
+    .options
+    .iter()
+    .find(|option| option.name == "json_name")
+  {
+    return option.value.trim_matches('"').to_string();
+  }
+  let mut name = String::new();
+  let mut upper = false;
+  for c in field.name.chars() {
+    if c == '_' {
+      upper = true;
+    } else if upper {
+      name.extend(c.to_uppercase());
+      upper = false;
+    } else {
+      name.push(c);
+    }
+  }
+  name
+}
+
+/// Whether a field can change between two different types in a mode below
+/// `Source`, which doesn't allow any change.
+fn types_compatible(mode: Mode, old_type: &str, new_type: &str) -> bool {
+  let groups = if mode == Mode::Wire {
+    WIRE_COMPATIBLE_TYPES
+  } else {
+    JSON_COMPATIBLE_TYPES
+  };
+  groups
+    .iter()
+    .any(|group| group.contains(&old_type) && group.contains(&new_type))
+}
+
+pub(crate) fn label_name(label: Label) -> &'static str {
+  match label {
+    Label::None => "singular",
+    Label::Optional => "optional",
+    Label::Required => "required",
+    Label::Repeated => "repeated",
+  }
+}
+
+pub(crate) fn oneof_name(oneof: &Option<String>) -> String {
+  match oneof {
+    Some(name) => format!("oneof `{}`", name),
+    None => "no oneof".to_string(),
+  }
+}
+
+/// Whether `old_value` of the enum `name` breaks consumers in `new_enum`:
+/// removed without reserving its number, or renamed in `mode`s that care.
+pub(crate) fn check_enum_value(
+  mode: Mode,
+  name: &str,
+  old_value: &EnumValue,
+  new_enum: &Enum,
+) -> Option<Violation> {
+  let path = format!("{}.{}", name, old_value.name);
+  let same_number: Vec<&str> = new_enum
+    .values
+    .iter()
+    .filter(|value| value.number == old_value.number)
+    .map(|value| value.name.as_str())
+    .collect();
+  if same_number.is_empty() {
+    if new_enum.reserved.contains_number(old_value.number) {
+      return None;
+    }
+    Some(Violation {
+      rule: "ENUM_VALUE_REMOVED",
+      path,
+      message: format!(
+        "value {} was removed without reserving its number",
+        old_value.number
+      ),
+    })
+  } else if mode >= Mode::WireJson && !same_number.contains(&old_value.name.as_str()) {
+    Some(Violation {
+      rule: "ENUM_VALUE_NAME_CHANGED",
+      path,
+      message: format!(
+        "value {} was renamed to `{}`",
+        old_value.number, same_number[0]
+      ),
+    })
+  } else {
+    None
+  }
+}
+
+pub(crate) fn rpc_removed(name: &str, old_rpc: &Rpc) -> Violation {
+  Violation {
+    rule: "RPC_REMOVED",
+    path: format!("{}.{}", name, old_rpc.name),
+    message: "rpc was removed".to_string(),
+  }
+}
+
+/// The changes from `old_rpc` of the service `name` to `new_rpc` that break
+/// consumers.
+pub(crate) fn check_rpc(
+  old: &Schema,
+  new: &Schema,
+  name: &str,
+  old_rpc: &Rpc,
+  new_rpc: &Rpc,
+) -> Vec<Violation> {
+  let mut violations = Vec::new();
+  let scope = parent_scope(name);
+  let path = format!("{}.{}", name, old_rpc.name);
+  let types = [
+    (
+      "RPC_REQUEST_TYPE_CHANGED",
+      "request",
+      &old_rpc.input_type,
+      &new_rpc.input_type,
+    ),
+    (
+      "RPC_RESPONSE_TYPE_CHANGED",
+      "response",
+      &old_rpc.output_type,
+      &new_rpc.output_type,
+    ),
+  ];
+  for (rule, kind, old_type, new_type) in types.iter() {
+    let old_type = old.resolve(scope, old_type);
+    let new_type = new.resolve(scope, new_type);
+    if old_type != new_type {
+      violations.push(Violation {
+        rule,
+        path: path.clone(),
+        message: format!(
+          "{} type changed from `{}` to `{}`",
+          kind, old_type, new_type
+        ),
+      });
+    }
+  }
+  if old_rpc.client_streaming != new_rpc.client_streaming
+    || old_rpc.server_streaming != new_rpc.server_streaming
+  {
+    violations.push(Violation {
+      rule: "RPC_STREAMING_CHANGED",
+      path,
+      message: "streaming changed".to_string(),
+    });
+  }
+  violations
+}
+
+/// Whether going from `previous` to `next` is a semver-major bump, which is
+/// allowed to break consumers. Below 1.0 a minor bump counts as major.
+pub fn allows_breaking_changes(previous: &Version, next: &Version) -> bool {
+  if previous.major == 0 {
+    next.major > 0 || next.minor > previous.minor
+  } else {
+    next.major > previous.major
+  }
+}
+
+/// Whether `version` starts a new major line over `published`, oldest first,
+/// and so doesn't have to be compatible with any of them.
+pub fn starts_major_line<T>(published: &[(Version, T)], version: &Version) -> bool {
+  published.last().map_or(false, |(latest, _)| {
+    allows_breaking_changes(latest, version)
+  })
+}
+
+/// Downloads and parses the published versions of `name` a new version is
+/// checked against, oldest first: the latest one, or with `transitive` every
+/// release. Nothing is returned only when
+/// the registry has no version of the package, failing to ask it, or a
+/// version that can't be read or parsed, is an error.
+pub fn published_baselines(
+  registry_url: &str,
+  name: &str,
+  transitive: bool,
+) -> Result<Vec<(Version, Vec<SourceFile>)>, String> {
+  let versions = registry::get_package_versions(registry_url, name)
+    .map_err(|err| format!("failed to fetch the published versions: {}", err))?;
+  let mut parsed = Vec::new();
+  for version in baseline_versions(&versions, transitive) {
+    let artifact = registry::fetch(registry_url, name, &version.to_string())
+      .map_err(|err| format!("failed to fetch {}: {}", version, err))?;
+    let sources = artifact
+      .proto_sources()
+      .map_err(|err| format!("failed to read {}: {}", version, err))?;
+    let files = proto::parse_sources(sources)
+      .map_err(|err| format!("failed to parse {}: {}", version, err))?;
+    parsed.push((version, files));
+  }
+  Ok(parsed)
+}
+
+/// The versions among `published` a new version is checked against, oldest
+/// first: the latest one, or with `transitive` every release.
+fn baseline_versions(published: &[String], transitive: bool) -> Vec<Version> {
+  let latest = match dependencies::select_version(published, None) {
+    Some(latest) => latest,
+    None => return Vec::new(),
+  };
+  if !transitive {
+    return vec![latest];
+  }
+  let mut baselines: Vec<Version> = published
+    .iter()
+    .filter_map(|version| Version::parse(version).ok())
+    .filter(|version| !version.is_prerelease())
+    .collect();
+  baselines.sort();
+  baselines
+}
+
+/// Checks `current` against each of the labelled `baselines`, reporting each
+/// violation once, with the oldest baseline it was found against.
+pub fn check_baselines(
+  baselines: &[(String, Vec<SourceFile>)],
+  current: &[SourceFile],
+  compatibility: &Compatibility,
+) -> Vec<(String, Violation)> {
+  let mut violations: Vec<(String, Violation)> = Vec::new();
+  for (label, previous) in baselines {
+    for violation in check_compatibility(previous, current, compatibility) {
+      if !violations.iter().any(|(_, found)| *found == violation) {
+        violations.push((label.clone(), violation));
+      }
+    }
+  }
+  violations
+}
+
+/// Why a registry refused to publish a package.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejection {
+  /// The artifact can't be checked, e.g. it has no buff.toml.
+  Invalid(String),
+  Breaking(BreakingChanges),
+}
+
+/// The changes that break consumers of `baseline`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BreakingChanges {
+  pub baseline: String,
+  pub violations: Vec<Violation>,
+}
+
+impl BreakingChanges {
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).expect("Failed to serialize breaking changes")
+  }
+
+  pub fn to_proto(&self) -> CompatibilityRejection {
+    let mut rejection = CompatibilityRejection::new();
+    rejection.set_baseline(self.baseline.clone());
+    for violation in &self.violations {
+      let mut proto = CompatibilityViolation::new();
+      proto.set_rule(violation.rule.to_string());
+      proto.set_path(violation.path.clone());
+      proto.set_message(violation.message.clone());
+      rejection.mut_violations().push(proto);
+    }
+    rejection
+  }
+}
+
+/// The published version a registry holds a new `version` to: the latest
+/// one before it that it isn't a major bump over.
+pub fn enforced_baseline(published: &[String], version: &Version) -> Option<Version> {
+  published
+    .iter()
+    .filter_map(|published| Version::parse(published).ok())
+    .filter(|published| {
+      !published.is_prerelease()
+        && published < version
+        && !allows_breaking_changes(published, version)
+    })
+    .max()
+}
+
+/// The check a registry runs on publish, the same one `buff check` runs:
+/// `artifact` against the latest of the `published` versions in its major
+/// line, fetched with `fetch`, using the `[compatibility]` settings and
+/// suppressions of the new version. Artifacts, new or published, that can't
+/// be read or parsed are invalid.
+pub fn check_publish<F>(
+  published: &[String],
+  artifact: &Artifact,
+  fetch: F,
+) -> Result<(), Rejection>
+where
+  F: FnOnce(&Version) -> Artifact,
+{
+  let metadata = artifact.read_metadata().map_err(Rejection::Invalid)?;
+  // Invalid versions are refused by the manifest checks, which
+  // aren't about compatibility.
+  let version = match Version::parse(&metadata.package.version) {
+    Ok(version) => version,
+    Err(_) => return Ok(()),
+  };
+  let baseline = match enforced_baseline(published, &version) {
+    Some(baseline) => baseline,
+    None => return Ok(()),
+  };
+  let previous = fetch(&baseline)
+    .proto_sources()
+    .map_err(|err| Rejection::Invalid(format!("failed to read {}: {}", baseline, err)))?;
+  let previous = proto::parse_sources(previous)
+    .map_err(|err| Rejection::Invalid(format!("failed to parse {}: {}", baseline, err)))?;
+  let current = artifact
+    .proto_sources()
+    .map_err(|err| Rejection::Invalid(err.to_string()))?;
+  let current = proto::parse_sources(current)
+    .map_err(|err| Rejection::Invalid(format!("failed to parse {}", err)))?;
+  let compatibility = &metadata.compatibility;
+  let mut suppressions = Suppressions::new(
+    &compatibility.ignore,
+    &current,
+    Local::now().naive_local().date(),
+  );
+  let violations: Vec<Violation> = check_compatibility(&previous, &current, compatibility)
+    .into_iter()
+    .filter(|violation| !suppressions.suppresses(violation))
+    .collect();
+  if violations.is_empty() {
+    Ok(())
+  } else {
+    Err(Rejection::Breaking(BreakingChanges {
+      baseline: baseline.to_string(),
+      violations,
+    }))
+  }
+}
+
+/// The package in `package_dir` as of `revision`, if it existed then.
+pub fn git_baseline(package_dir: &Path, revision: &str) -> Result<Option<Vec<SourceFile>>, String> {
+  let sources = git::proto_sources_at(package_dir, revision)
+    .map_err(|err| format!("failed to read the protos at {}: {}", revision, err))?;
+  match sources {
+    Some(sources) => proto::parse_sources(sources)
+      .map(Some)
+      .map_err(|err| format!("failed to parse {} at {}", err, revision)),
+    None => Ok(None),
+  }
+}
+
+/// Checks the package at `manifest_path` against `against`, by default the
+/// latest version of it published to the preferred registry, with the
+/// guarantees set in its `[compatibility]` section. A git revision is a
+/// single baseline even when the direction is transitive. Like publishing,
+/// nothing is compared when the version is a major bump over the latest
+/// published one.
+pub fn check_package(manifest_path: &Path, against: &Baseline) -> PackageCheck {
+  let metadata = PackageMetadata::new(manifest_path.to_str().unwrap());
+  let name = metadata.package.name;
+  let package_dir = manifest::package_dir(manifest_path);
+  let compatibility = &metadata.compatibility;
+  let baselines: Vec<(String, Vec<SourceFile>)> = match against {
+    Baseline::Registry => {
+      let registry_url = BuffCliConfig::new().preferred_registry;
+      let published = published_baselines(&registry_url, &name, compatibility.transitive)
+        .unwrap_or_else(|err| panic!("Failed to check `{}`: {}", name, err));
+      let major_bump = Version::parse(&metadata.package.version)
+        .map_or(false, |version| starts_major_line(&published, &version));
+      if major_bump {
+        return PackageCheck {
+          name,
+          version: metadata.package.version,
+          baseline: published.last().map(|(latest, _)| latest.to_string()),
+          major_bump,
+          violations: Vec::new(),
+          suppressed: Vec::new(),
+          stale_suppressions: Vec::new(),
+        };
+      }
+      published
+        .into_iter()
+        .map(|(version, files)| (version.to_string(), files))
+        .collect()
+    }
+    Baseline::Git(revision) => git_baseline(&package_dir, revision)
+      .unwrap_or_else(|err| panic!("Failed to check `{}`: {}", name, err))
+      .map(|files| (against.to_string(), files))
+      .into_iter()
+      .collect(),
+  };
+  if baselines.is_empty() {
+    return PackageCheck {
+      name,
+      version: metadata.package.version,
+      baseline: None,
+      major_bump: false,
+      violations: Vec::new(),
+      suppressed: Vec::new(),
+      stale_suppressions: Vec::new(),
+    };
+  }
+  let current =
+    proto::parse_dir(&package_dir).unwrap_or_else(|err| panic!("Failed to parse {}", err));
+  let several = baselines.len() > 1;
+  let mut suppressions = Suppressions::new(
+    &compatibility.ignore,
+    &current,
+    Local::now().naive_local().date(),
+  );
+  let (suppressed, violations): (Vec<Violation>, Vec<Violation>) =
+    check_baselines(&baselines, &current, compatibility)
+      .into_iter()
+      .map(|(label, mut violation)| {
+        if several {
+          violation.message = format!("{} (against {})", violation.message, label);
+        }
+        violation
+      })
+      .partition(|violation| suppressions.suppresses(violation));
+  let baseline = if several {
+    format!(
+      "every version from {} to {}",
+      baselines[0].0,
+      baselines[baselines.len() - 1].0
+    )
+  } else {
+    baselines[0].0.clone()
+  };
+  PackageCheck {
+    violations,
+    suppressed,
+    stale_suppressions: suppressions.stale(),
+    name,
+    version: metadata.package.version,
+    baseline: Some(baseline),
+    major_bump: false,
+  }
+}
+
+#[test]
+fn should_accept_compatible_changes() {
+  let previous = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { string id = 1; Item item = 2; message Item { string sku = 1; } }
+     service Orders { rpc Get (Order) returns (Order); }",
+  );
+  let current = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { reserved 1; .acme.v1.Order.Item item = 2; string note = 3; message Item { string sku = 1; } }
+     message Extra {}
+     service Orders { rpc Get (acme.v1.Order) returns (Order); rpc List (Order) returns (Order); }",
+  );
+  assert_eq!(check(&previous, &current), vec![]);
+}
+
+#[test]
+fn should_report_breaking_changes() {
+  let previous = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { string id = 1; repeated string tags = 2; int64 total = 3; string legacy_id = 4; }
+     message Gone {}
+     enum Status { PENDING = 0; DONE = 1; FAILED = 2; }
+     service Orders { rpc Get (Order) returns (Order); rpc Watch (Order) returns (stream Order); }",
+  );
+  let current = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { string order_id = 1; string tags = 2; string total = 3; }
+     enum Status { PENDING = 0; COMPLETED = 1; }
+     service Orders { rpc Get (Order) returns (Status); }",
+  );
+  let found: Vec<String> = check(&previous, &current)
+    .iter()
+    .map(|violation| format!("{} {}", violation.rule, violation.path))
+    .collect();
+  assert_eq!(
+    found,
+    vec![
+      "MESSAGE_REMOVED acme.v1.Gone",
+      "FIELD_NAME_CHANGED acme.v1.Order.id",
+      "FIELD_LABEL_CHANGED acme.v1.Order.tags",
+      "FIELD_TYPE_CHANGED acme.v1.Order.total",
+      "FIELD_REMOVED acme.v1.Order.legacy_id",
+      "ENUM_VALUE_NAME_CHANGED acme.v1.Status.DONE",
+      "ENUM_VALUE_REMOVED acme.v1.Status.FAILED",
+      "RPC_RESPONSE_TYPE_CHANGED acme.v1.Orders.Get",
+      "RPC_REMOVED acme.v1.Orders.Watch",
+    ]
+  );
+}
+
+#[test]
+fn should_allow_breaking_changes_on_major_bumps() {
+  let version = |v: &str| Version::parse(v).unwrap();
+  assert!(allows_breaking_changes(
+    &version("1.4.2"),
+    &version("2.0.0")
+  ));
+  assert!(!allows_breaking_changes(
+    &version("1.4.2"),
+    &version("1.5.0")
+  ));
+  assert!(allows_breaking_changes(
+    &version("0.3.1"),
+    &version("0.4.0")
+  ));
+  assert!(!allows_breaking_changes(
+    &version("0.3.1"),
+    &version("0.3.2")
+  ));
+  // Only the latest release decides, even when older major lines are checked
+  // transitively.
+  let published = vec![(version("1.4.2"), ()), (version("2.1.0"), ())];
+  assert!(!starts_major_line(&published, &version("2.2.0")));
+  assert!(starts_major_line(&published, &version("3.0.0")));
+  assert!(!starts_major_line(
+    &Vec::<(Version, ())>::new(),
+    &version("3.0.0")
+  ));
+}
+
+#[test]
+fn should_check_against_git_revisions() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let root = tmp_dir.path();
+  let repo = git2::Repository::init(root).unwrap();
+  std::fs::copy(
+    "../tests/fixtures/test_artifact/buff.toml",
+    root.join("buff.toml"),
+  )
+  .unwrap();
+  std::fs::write(
+    root.join("orders.proto"),
+    "syntax = \"proto3\"; package acme.v1; message Order { string id = 1; }",
+  )
+  .unwrap();
+  let mut index = repo.index().unwrap();
+  index
+    .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+    .unwrap();
+  let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+  let signature = git2::Signature::now("buff", "buff@example.com").unwrap();
+  repo
+    .commit(
+      Some("HEAD"),
+      &signature,
+      &signature,
+      "Initial commit",
+      &tree,
+      &[],
+    )
+    .unwrap();
+  std::fs::write(
+    root.join("orders.proto"),
+    "syntax = \"proto3\"; package acme.v1; message Order { int64 id = 1; }",
+  )
+  .unwrap();
+  let against: Baseline = "git:HEAD".parse().unwrap();
+  let result = check_package(&root.join("buff.toml"), &against);
+  assert_eq!(result.baseline, Some("git:HEAD".to_string()));
+  assert_eq!(result.violations.len(), 1);
+  assert_eq!(result.violations[0].rule, "FIELD_TYPE_CHANGED");
+  assert!("git:".parse::<Baseline>().is_err());
+}
+
+#[test]
+fn should_check_compatibility_modes() {
+  let previous = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { int32 count = 1; string legacy_id = 2; string customer_id = 3; Status status = 4; }
+     message Gone {}
+     enum Status { PENDING = 0; DONE = 1; }",
+  );
+  let current = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { reserved 2; int64 count = 1; string customer = 3 [json_name = \"customerId\"]; int32 status = 4; string note = 5; }
+     enum Status { PENDING = 0; COMPLETED = 1; }",
+  );
+  let rules = |mode: Mode, direction: Direction| -> Vec<String> {
+    let compatibility = Compatibility {
+      mode,
+      direction,
+      ..Compatibility::default()
+    };
+    check_compatibility(&previous, &current, &compatibility)
+      .iter()
+      .map(|violation| format!("{} {}", violation.rule, violation.path))
+      .collect()
+  };
+  assert_eq!(rules(Mode::Wire, Direction::Full), Vec::<String>::new());
+  assert_eq!(
+    rules(Mode::WireJson, Direction::Backward),
+    vec![
+      "FIELD_TYPE_CHANGED acme.v1.Order.count",
+      "FIELD_TYPE_CHANGED acme.v1.Order.status",
+      "ENUM_VALUE_NAME_CHANGED acme.v1.Status.DONE",
+    ]
+  );
+  assert_eq!(
+    rules(Mode::Source, Direction::Backward),
+    vec![
+      "MESSAGE_REMOVED acme.v1.Gone",
+      "FIELD_TYPE_CHANGED acme.v1.Order.count",
+      "FIELD_NAME_CHANGED acme.v1.Order.customer_id",
+      "FIELD_TYPE_CHANGED acme.v1.Order.status",
+      "ENUM_VALUE_NAME_CHANGED acme.v1.Status.DONE",
+    ]
+  );
+  assert_eq!(
+    rules(Mode::Source, Direction::Forward),
+    vec![
+      "FIELD_TYPE_CHANGED acme.v1.Order.count",
+      "FIELD_NAME_CHANGED acme.v1.Order.customer",
+      "FIELD_TYPE_CHANGED acme.v1.Order.status",
+      "FIELD_REMOVED acme.v1.Order.note",
+      "ENUM_VALUE_NAME_CHANGED acme.v1.Status.COMPLETED",
+    ]
+  );
+}
+
+#[test]
+fn should_parse_compatibility_section() {
+  let parse = |section: &str| toml::from_str::<Compatibility>(section);
+  assert_eq!(parse("").unwrap(), Compatibility::default());
+  assert_eq!(
+    parse("mode = \"wire_json\"\ndirection = \"full_transitive\"").unwrap(),
+    Compatibility {
+      mode: Mode::WireJson,
+      direction: Direction::Full,
+      transitive: true,
+      ignore: Vec::new(),
+    }
+  );
+  assert!(parse("direction = \"sideways\"").is_err());
+  assert!(
+    parse("ignore = [{ rule = \"RPC_REMOVED\", path = \"a.B\", expires = \"soon\" }]").is_err()
+  );
+  assert!(parse("mode = \"binary\"").is_err());
+}
+
+#[test]
+fn should_check_transitively_against_every_release() {
+  let published: Vec<String> = vec!["1.0.0", "2.0.0", "1.1.0", "2.1.0-rc.1"]
+    .into_iter()
+    .map(String::from)
+    .collect();
+  let versions = |transitive| -> Vec<String> {
+    baseline_versions(&published, transitive)
+      .iter()
+      .map(ToString::to_string)
+      .collect()
+  };
+  assert_eq!(versions(false), vec!["2.0.0"]);
+  assert_eq!(versions(true), vec!["1.0.0", "1.1.0", "2.0.0"]);
+  // 2.0.0 was allowed to change the type of `id`, transitive checks still
+  // hold the package to it for consumers of 1.x.
+  let baselines = vec![
+    (
+      "1.0.0".to_string(),
+      sources("syntax = \"proto3\"; package acme.v1; message Order { string id = 1; }"),
+    ),
+    (
+      "2.0.0".to_string(),
+      sources("syntax = \"proto3\"; package acme.v1; message Order { int64 id = 1; }"),
+    ),
+  ];
+  let current = sources(
+    "syntax = \"proto3\"; package acme.v1; message Order { int64 id = 1; string note = 2; }",
+  );
+  let compatibility =
+    toml::from_str::<Compatibility>("direction = \"backward_transitive\"").unwrap();
+  let found: Vec<String> = check_baselines(&baselines, &current, &compatibility)
+    .iter()
+    .map(|(baseline, violation)| format!("{} {} {}", baseline, violation.rule, violation.path))
+    .collect();
+  assert_eq!(found, vec!["1.0.0 FIELD_TYPE_CHANGED acme.v1.Order.id"]);
+}
+
+#[test]
+fn should_suppress_intentional_breaking_changes() {
+  let previous = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message User { string id = 1; string legacy_id = 2; int32 age = 3; }
+     service Users { rpc Get (User) returns (User); rpc Purge (User) returns (User); }",
+  );
+  let current = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message User {
+       string id = 1;
+       int64 age = 3; // buff:ignore FIELD_TYPE_CHANGED
+     }
+     // buff:ignore RPC_REMOVED SERVICE_REMOVED
+     service Users { rpc Get (User) returns (User); }
+     // buff:ignore MESSAGE_REMOVED",
+  );
+  let suppression = |rule: &str, path: &str, expires: Option<&str>| Suppression {
+    rule: rule.to_string(),
+    path: path.to_string(),
+    expires: expires.map(String::from),
+  };
+  let configured = vec![
+    suppression("FIELD_REMOVED", "acme.v1.User.legacy_id", None),
+    suppression("MESSAGE_REMOVED", "acme.v1.Gone", None),
+    suppression("FIELD_REMOVED", "acme.v1.User", Some("2019-01-01")),
+  ];
+  let today = NaiveDate::from_ymd_opt(2019, 6, 1).unwrap();
+  let mut suppressions = Suppressions::new(&configured, &current, today);
+  let remaining: Vec<Violation> = check(&previous, &current)
+    .into_iter()
+    .filter(|violation| !suppressions.suppresses(violation))
+    .collect();
+  assert_eq!(remaining, vec![]);
+  assert_eq!(
+    suppressions.stale(),
+    vec![
+      "buff.toml: ignore MESSAGE_REMOVED acme.v1.Gone doesn't match any breaking change",
+      "buff.toml: ignore FIELD_REMOVED acme.v1.User expired on 2019-01-01",
+      "orders.proto:6: ignore SERVICE_REMOVED acme.v1.Users doesn't match any breaking change",
+      "orders.proto:8: ignore MESSAGE_REMOVED doesn't precede any definition",
+    ]
+  );
+}
+
+#[test]
+fn should_suppress_removed_fields_with_comments_where_they_were() {
+  let previous = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { string id = 1; string note = 2; }
+     message Gone {}",
+  );
+  let current = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order {
+       string id = 1;
+       // buff:ignore FIELD_REMOVED MESSAGE_REMOVED
+     }",
+  );
+  let today = NaiveDate::from_ymd_opt(2019, 6, 1).unwrap();
+  let mut suppressions = Suppressions::new(&[], &current, today);
+  let remaining: Vec<String> = check(&previous, &current)
+    .into_iter()
+    .filter(|violation| !suppressions.suppresses(violation))
+    .map(|violation| format!("{} {}", violation.rule, violation.path))
+    .collect();
+  assert_eq!(remaining, vec!["MESSAGE_REMOVED acme.v1.Gone"]);
+  assert_eq!(
+    suppressions.stale(),
+    vec!["orders.proto:4: ignore MESSAGE_REMOVED acme.v1.Order doesn't match any breaking change"]
+  );
+}
+
+#[cfg(test)]
+fn artifact_with_protos(version: &str, proto: &str) -> Artifact {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let manifest = std::fs::read_to_string("../tests/fixtures/test_artifact/buff.toml")
+    .unwrap()
+    .replace("0.1.0", version);
+  std::fs::write(tmp_dir.path().join("buff.toml"), manifest).unwrap();
+  std::fs::write(tmp_dir.path().join("orders.proto"), proto).unwrap();
+  let mut bytes = Vec::new();
+  crate::artifact::write_artifact(tmp_dir.path(), Default::default(), &mut bytes).unwrap();
+  Artifact::from_bytes(bytes)
+}
+
+#[test]
+fn should_enforce_compatibility_on_publish() {
+  let published = vec![
+    "1.0.0".to_string(),
+    "1.1.0".to_string(),
+    "2.0.0-rc.1".to_string(),
+  ];
+  let previous = "syntax = \"proto3\"; package acme.v1; message Order { string id = 1; }";
+  let breaking = "syntax = \"proto3\"; package acme.v1; message Order { int64 id = 1; }";
+  let fetch = |version: &Version| {
+    assert_eq!(version.to_string(), "1.1.0");
+    artifact_with_protos("1.1.0", previous)
+  };
+  let rejection = match check_publish(&published, &artifact_with_protos("1.2.0", breaking), fetch) {
+    Err(Rejection::Breaking(rejection)) => rejection,
+    result => panic!("expected breaking changes, got {:?}", result),
+  };
+  assert_eq!(rejection.baseline, "1.1.0");
+  assert_eq!(rejection.violations[0].rule, "FIELD_TYPE_CHANGED");
+  let proto = rejection.to_proto();
+  assert_eq!(proto.get_violations()[0].get_path(), "acme.v1.Order.id");
+  assert_eq!(
+    check_publish(&published, &artifact_with_protos("2.0.0", breaking), |_| {
+      panic!("major bumps aren't checked")
+    }),
+    Ok(())
+  );
+  assert_eq!(
+    check_publish(&published, &artifact_with_protos("1.2.0", previous), fetch),
+    Ok(())
+  );
+  let mut builder = tar::Builder::new(Vec::new());
+  let mut header = tar::Header::new_gnu();
+  header.set_size(previous.len() as u64);
+  header.set_cksum();
+  builder
+    .append_data(&mut header, "orders.proto", previous.as_bytes())
+    .unwrap();
+  let without_manifest = Artifact::from_bytes(builder.into_inner().unwrap());
+  assert_eq!(
+    check_publish(&published, &without_manifest, |_| panic!(
+      "nothing to check"
+    )),
+    Err(Rejection::Invalid(
+      "the artifact has no buff.toml".to_string()
+    ))
+  );
+  let unparsable = "syntax = \"proto3\"; package acme.v1; message Order {";
+  assert_eq!(
+    check_publish(
+      &published,
+      &artifact_with_protos("1.2.0", unparsable),
+      fetch
+    ),
+    Err(Rejection::Invalid(
+      "failed to parse orders.proto:1:51: expected a field type".to_string()
+    ))
+  );
+  assert_eq!(
+    check_publish(&published, &artifact_with_protos("1.2.0", previous), |_| {
+      artifact_with_protos("1.1.0", unparsable)
+    }),
+    Err(Rejection::Invalid(
+      "failed to parse 1.1.0: orders.proto:1:51: expected a field type".to_string()
+    ))
+  );
+}
@@ -0,0 +1,122 @@
+use crate::buff_cli_config::BuffCliConfig;
+use crate::lockfile;
+use crate::lockfile::{LockedPackage, Lockfile};
+use crate::package_metadata::ManifestDocument;
+use crate::registry;
+use semver::{Version, VersionReq};
+use std::path::Path;
+
+pub struct AddedDependency {
+  pub name: String,
+  pub requirement: String,
+  pub version: Version,
+}
+
+/// Adds `spec`, a package name optionally followed by `@<requirement>`, to the
+/// dependencies in the manifest and locks it to the latest matching version
+/// published on the registry.
+pub fn add(manifest_path: &Path, spec: &str, registry: Option<&str>) -> AddedDependency {
+  let (name, requirement) = parse_spec(spec);
+  let version_req = requirement.map(|requirement| {
+    VersionReq::parse(requirement)
+      .unwrap_or_else(|err| panic!("Invalid version requirement `{}`: {}", requirement, err))
+  });
+  let registry_url = registry
+    .map(str::to_owned)
+    .unwrap_or_else(|| BuffCliConfig::new().preferred_registry);
+  let versions = registry::get_package_versions(&registry_url, name)
+    .unwrap_or_else(|err| panic!("Failed to fetch versions of `{}`: {}", name, err));
+  let version = select_version(&versions, version_req.as_ref()).unwrap_or_else(|| {
+    panic!(
+      "No published version of `{}` matches `{}`",
+      name,
+      requirement.unwrap_or("*")
+    )
+  });
+  let requirement = requirement
+    .map(str::to_owned)
+    .unwrap_or_else(|| format!("{}.{}", version.major, version.minor));
+
+  let mut manifest = ManifestDocument::open(manifest_path);
+  manifest.set_dependency(name, &requirement, registry);
+  manifest.save();
+
+  let lockfile_path = lockfile::lockfile_path(manifest_path);
+  let mut lockfile = Lockfile::load(&lockfile_path);
+  lockfile.lock(LockedPackage {
+    name: name.to_owned(),
+    version: version.to_string(),
+    registry: registry_url,
+  });
+  lockfile.save(&lockfile_path);
+
+  AddedDependency {
+    name: name.to_owned(),
+    requirement,
+    version,
+  }
+}
+
+/// Removes the dependency from the manifest and its lockfile, returning
+/// whether it was declared at all.
+pub fn remove(manifest_path: &Path, name: &str) -> bool {
+  let mut manifest = ManifestDocument::open(manifest_path);
+  let removed = manifest.remove_dependency(name);
+  if removed {
+    manifest.save();
+  }
+  let lockfile_path = lockfile::lockfile_path(manifest_path);
+  let mut lockfile = Lockfile::load(&lockfile_path);
+  if lockfile.unlock(name) {
+    lockfile.save(&lockfile_path);
+  }
+  removed
+}
+
+/// Picks the highest version matching `requirement`. Without a requirement
+/// pre-releases are skipped, like cargo does.
+pub fn select_version(versions: &[String], requirement: Option<&VersionReq>) -> Option<Version> {
+  versions
+    .iter()
+    .filter_map(|version| Version::parse(version).ok())
+    .filter(|version| match requirement {
+      Some(requirement) => requirement.matches(version),
+      None => !version.is_prerelease(),
+    })
+    .max()
+}
+
+fn parse_spec(spec: &str) -> (&str, Option<&str>) {
+  match spec.find('@') {
+    Some(index) => (&spec[..index], Some(&spec[index + 1..])),
+    None => (spec, None),
+  }
+}
+
+#[test]
+fn should_parse_spec() {
+  assert_eq!(parse_spec("yummy_package"), ("yummy_package", None));
+  assert_eq!(
+    parse_spec("yummy_package@1.2"),
+    ("yummy_package", Some("1.2"))
+  );
+}
+
+#[test]
+fn should_select_version() {
+  let versions: Vec<String> = vec!["1.0.0", "1.2.3", "2.0.0-beta.1", "1.10.0", "garbage"]
+    .into_iter()
+    .map(String::from)
+    .collect();
+  assert_eq!(
+    select_version(&versions, None),
+    Some(Version::parse("1.10.0").unwrap())
+  );
+  let requirement = VersionReq::parse("~1.2").unwrap();
+  assert_eq!(
+    select_version(&versions, Some(&requirement)),
+    Some(Version::parse("1.2.3").unwrap())
+  );
+  let requirement = VersionReq::parse("3").unwrap();
+  assert_eq!(select_version(&versions, Some(&requirement)), None);
+}
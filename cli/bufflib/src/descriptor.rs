@@ -0,0 +1,1074 @@
+use crate::compat;
+use crate::proto::{
+  qualify, Enum, Field, ImportKind, Label, Location, Message, ParseError, Position, ProtoOption,
+  Reserved, Service, SourceFile,
+};
+use protobuf::descriptor::{
+  DescriptorProto, DescriptorProto_ExtensionRange, DescriptorProto_ReservedRange,
+  EnumDescriptorProto, EnumDescriptorProto_EnumReservedRange, EnumOptions,
+  EnumValueDescriptorProto, EnumValueOptions, FieldDescriptorProto, FieldDescriptorProto_Label,
+  FieldDescriptorProto_Type, FieldOptions, FieldOptions_CType, FieldOptions_JSType,
+  FileDescriptorProto, FileOptions, FileOptions_OptimizeMode, MessageOptions,
+  MethodDescriptorProto, MethodOptions, MethodOptions_IdempotencyLevel, OneofDescriptorProto,
+  OneofOptions, ServiceDescriptorProto, ServiceOptions, SourceCodeInfo, SourceCodeInfo_Location,
+  UninterpretedOption, UninterpretedOption_NamePart,
+};
+use protobuf::ProtobufEnum;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::Path;
+
+// Field numbers go up to 2^29 - 1, and descriptors store the end
+// of message ranges exclusively.
+const MAX_FIELD_NUMBER: i32 = 536_870_911;
+
+// Field numbers of the repeated fields in descriptor.proto that source code
+// info paths go through.
+const FILE_MESSAGES: i32 = 4;
+const FILE_ENUMS: i32 = 5;
+const FILE_SERVICES: i32 = 6;
+const FILE_EXTENSIONS: i32 = 7;
+const MESSAGE_FIELDS: i32 = 2;
+const MESSAGE_MESSAGES: i32 = 3;
+const MESSAGE_ENUMS: i32 = 4;
+const MESSAGE_EXTENSIONS: i32 = 6;
+const MESSAGE_ONEOFS: i32 = 8;
+const ENUM_VALUES: i32 = 2;
+const SERVICE_METHODS: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+  Package,
+  Message,
+  Enum,
+}
+
+struct Symbol<'a> {
+  kind: Kind,
+  file: &'a Path,
+}
+
+/// Links parsed files into the descriptors protoc would hand to plugins:
+/// type names resolved to fully-qualified ones, map fields with their entry
+/// messages, proto3 `optional` fields in synthetic oneofs, and spans and
+/// comments in the source code info. Options protoc knows are set, custom
+/// ones are left uninterpreted. Every imported file has to be among
+/// `files`, with its path as imported.
+pub fn link(files: &[SourceFile]) -> Result<Vec<FileDescriptorProto>, ParseError> {
+  let symbols = symbols(files)?;
+  files
+    .iter()
+    .map(|source| {
+      let linker = Linker {
+        source,
+        symbols: &symbols,
+        visible: visible(files, source)?,
+        proto3: source.file.syntax.as_ref().map(String::as_str) == Some("proto3"),
+        locations: Vec::new(),
+      };
+      linker.file()
+    })
+    .collect()
+}
+
+fn error(path: &Path, location: &Location, message: String) -> ParseError {
+  ParseError {
+    file: Some(path.to_path_buf()),
+    line: location.span.start.line,
+    column: location.span.start.column,
+    message,
+  }
+}
+
+/// Every package, message and enum defined in `files`, by fully-qualified
+/// name.
+fn symbols<'a>(files: &'a [SourceFile]) -> Result<BTreeMap<String, Symbol<'a>>, ParseError> {
+  let mut symbols = BTreeMap::new();
+  for source in files {
+    let package = source.file.package.clone().unwrap_or_default();
+    let mut scope = String::new();
+    for part in package.split('.').filter(|part| !part.is_empty()) {
+      scope = qualify(&scope, part);
+      let location = Location::default();
+      define(
+        &mut symbols,
+        source,
+        scope.clone(),
+        Kind::Package,
+        &location,
+      )?;
+    }
+    for message in &source.file.messages {
+      define_message(&mut symbols, source, &package, message)?;
+    }
+    for enumeration in &source.file.enums {
+      let name = qualify(&package, &enumeration.name);
+      define(
+        &mut symbols,
+        source,
+        name,
+        Kind::Enum,
+        &enumeration.location,
+      )?;
+    }
+  }
+  Ok(symbols)
+}
+
+fn define_message<'a>(
+  symbols: &mut BTreeMap<String, Symbol<'a>>,
+  source: &'a SourceFile,
+  scope: &str,
+  message: &Message,
+) -> Result<(), ParseError> {
+  let name = qualify(scope, &message.name);
+  define(
+    symbols,
+    source,
+    name.clone(),
+    Kind::Message,
+    &message.location,
+  )?;
+  for field in &message.fields {
+    if map_types(&field.type_name).is_some() {
+      let entry = qualify(&name, &entry_name(&field.name));
+      define(symbols, source, entry, Kind::Message, &field.location)?;
+    }
+  }
+  for nested in &message.messages {
+    define_message(symbols, source, &name, nested)?;
+  }
+  for enumeration in &message.enums {
+    let enum_name = qualify(&name, &enumeration.name);
+    define(
+      symbols,
+      source,
+      enum_name,
+      Kind::Enum,
+      &enumeration.location,
+    )?;
+  }
+  Ok(())
+}
+
+fn define<'a>(
+  symbols: &mut BTreeMap<String, Symbol<'a>>,
+  source: &'a SourceFile,
+  name: String,
+  kind: Kind,
+  location: &Location,
+) -> Result<(), ParseError> {
+  if let Some(existing) = symbols.get(&name) {
+    if kind == Kind::Package && existing.kind == Kind::Package {
+      return Ok(());
+    }
+    return Err(error(
+      &source.path,
+      location,
+      format!(
+        "`{}` is already defined in {}",
+        name,
+        existing.file.display()
+      ),
+    ));
+  }
+  symbols.insert(
+    name,
+    Symbol {
+      kind,
+      file: &source.path,
+    },
+  );
+  Ok(())
+}
+
+/// The files `source` can refer to definitions in: itself, the files it
+/// imports and, transitively, the files those import publicly.
+fn visible<'a>(
+  files: &'a [SourceFile],
+  source: &'a SourceFile,
+) -> Result<BTreeSet<&'a Path>, ParseError> {
+  let find = |path: &str| files.iter().find(|file| file.path == Path::new(path));
+  let mut visible = BTreeSet::new();
+  visible.insert(source.path.as_path());
+  let mut pending = Vec::new();
+  for import in &source.file.imports {
+    match find(&import.path) {
+      Some(imported) => pending.push(imported),
+      None => {
+        return Err(error(
+          &source.path,
+          &import.location,
+          format!("import `{}` not found", import.path),
+        ));
+      }
+    }
+  }
+  while let Some(imported) = pending.pop() {
+    if !visible.insert(imported.path.as_path()) {
+      continue;
+    }
+    for import in &imported.file.imports {
+      if import.kind == ImportKind::Public {
+        pending.extend(find(&import.path));
+      }
+    }
+  }
+  Ok(visible)
+}
+
+/// The key and value types of a `map<K, V>` type.
+fn map_types(type_name: &str) -> Option<(&str, &str)> {
+  if !type_name.starts_with("map<") || !type_name.ends_with('>') {
+    return None;
+  }
+  let mut types = type_name[4..type_name.len() - 1].splitn(2, ',');
+  match (types.next(), types.next()) {
+    (Some(key), Some(value)) => Some((key.trim(), value.trim())),
+    _ => None,
+  }
+}
+
+/// The name protoc gives the entry message of a map field, e.g.
+/// `LabelsByIdEntry` for `labels_by_id`.
+fn entry_name(field_name: &str) -> String {
+  let mut name = String::new();
+  let mut upper = true;
+  for c in field_name.chars() {
+    if c == '_' {
+      upper = true;
+    } else if upper {
+      name.extend(c.to_uppercase());
+      upper = false;
+    } else {
+      name.push(c);
+    }
+  }
+  name.push_str("Entry");
+  name
+}
+
+fn scalar_type(type_name: &str) -> Option<FieldDescriptorProto_Type> {
+  use protobuf::descriptor::FieldDescriptorProto_Type::*;
+  let scalar = match type_name {
+    "double" => TYPE_DOUBLE,
+    "float" => TYPE_FLOAT,
+    "int64" => TYPE_INT64,
+    "uint64" => TYPE_UINT64,
+    "int32" => TYPE_INT32,
+    "fixed64" => TYPE_FIXED64,
+    "fixed32" => TYPE_FIXED32,
+    "bool" => TYPE_BOOL,
+    "string" => TYPE_STRING,
+    "bytes" => TYPE_BYTES,
+    "uint32" => TYPE_UINT32,
+    "sfixed32" => TYPE_SFIXED32,
+    "sfixed64" => TYPE_SFIXED64,
+    "sint32" => TYPE_SINT32,
+    "sint64" => TYPE_SINT64,
+    _ => return None,
+  };
+  Some(scalar)
+}
+
+/// The exclusive end descriptors use for an inclusive message range.
+fn exclusive_end(end: i32) -> i32 {
+  if end == i32::max_value() {
+    MAX_FIELD_NUMBER + 1
+  } else {
+    end + 1
+  }
+}
+
+/// A message nested in another one, either declared or the entry message of
+/// a map field.
+enum Nested<'a> {
+  Message(&'a Message),
+  MapEntry(&'a Field),
+}
+
+impl<'a> Nested<'a> {
+  fn start(&self) -> Position {
+    match self {
+      Nested::Message(message) => message.location.span.start,
+      Nested::MapEntry(field) => field.location.span.start,
+    }
+  }
+}
+
+struct Linker<'a> {
+  source: &'a SourceFile,
+  symbols: &'a BTreeMap<String, Symbol<'a>>,
+  visible: BTreeSet<&'a Path>,
+  proto3: bool,
+  locations: Vec<SourceCodeInfo_Location>,
+}
+
+impl<'a> Linker<'a> {
+  fn error(&self, location: &Location, message: String) -> ParseError {
+    error(&self.source.path, location, message)
+  }
+
+  /// Resolves a type name used in `scope` the way protoc does: a relative
+  /// name is looked up by its first part from the innermost scope outwards,
+  /// and the rest of it has to be found where that first part is.
+  fn resolve(
+    &self,
+    type_name: &str,
+    scope: &str,
+    location: &Location,
+  ) -> Result<(String, Kind), ParseError> {
+    let full = if type_name.starts_with('.') {
+      Some(type_name[1..].to_string())
+    } else {
+      let first = type_name.split('.').next().unwrap_or_default();
+      let mut scope = scope;
+      loop {
+        if self.symbols.contains_key(&qualify(scope, first)) {
+          break Some(qualify(scope, type_name));
+        }
+        if scope.is_empty() {
+          break None;
+        }
+        scope = compat::parent_scope(scope);
+      }
+    };
+    match full.and_then(|full| self.symbols.get(&full).map(|symbol| (full, symbol))) {
+      Some((full, symbol)) if symbol.kind != Kind::Package => {
+        if !self.visible.contains(symbol.file) {
+          return Err(self.error(
+            location,
+            format!(
+              "`{}` is defined in {}, which isn't imported",
+              type_name,
+              symbol.file.display()
+            ),
+          ));
+        }
+        Ok((format!(".{}", full), symbol.kind))
+      }
+      _ => Err(self.error(location, format!("unknown type `{}`", type_name))),
+    }
+  }
+
+  fn resolve_message(
+    &self,
+    type_name: &str,
+    scope: &str,
+    location: &Location,
+  ) -> Result<String, ParseError> {
+    match self.resolve(type_name, scope, location)? {
+      (full, Kind::Message) => Ok(full),
+      _ => Err(self.error(location, format!("`{}` is not a message", type_name))),
+    }
+  }
+
+  fn record(&mut self, path: &[i32], location: &Location) {
+    let span = location.span;
+    let mut numbers = vec![span.start.line as i32 - 1, span.start.column as i32 - 1];
+    if span.end.line != span.start.line {
+      numbers.push(span.end.line as i32 - 1);
+    }
+    numbers.push(span.end.column as i32 - 1);
+    let mut recorded = SourceCodeInfo_Location::new();
+    recorded.set_path(path.to_vec());
+    recorded.set_span(numbers);
+    if let Some(ref comments) = location.leading_comments {
+      recorded.set_leading_comments(comments.clone());
+    }
+    if let Some(ref comments) = location.trailing_comments {
+      recorded.set_trailing_comments(comments.clone());
+    }
+    self.locations.push(recorded);
+  }
+
+  fn file(mut self) -> Result<FileDescriptorProto, ParseError> {
+    let file = &self.source.file;
+    let mut descriptor = FileDescriptorProto::new();
+    let name: Vec<String> = self
+      .source
+      .path
+      .components()
+      .map(|component| component.as_os_str().to_string_lossy().into_owned())
+      .collect();
+    descriptor.set_name(name.join("/"));
+    if let Some(ref package) = file.package {
+      descriptor.set_package(package.clone());
+    }
+    for (i, import) in file.imports.iter().enumerate() {
+      descriptor.mut_dependency().push(import.path.clone());
+      match import.kind {
+        ImportKind::Public => descriptor.mut_public_dependency().push(i as i32),
+        ImportKind::Weak => descriptor.mut_weak_dependency().push(i as i32),
+        ImportKind::Default => (),
+      }
+    }
+    if self.proto3 {
+      descriptor.set_syntax("proto3".to_string());
+    }
+    let package = file.package.clone().unwrap_or_default();
+    for (i, message) in file.messages.iter().enumerate() {
+      let linked = self.message(message, &package, &[FILE_MESSAGES, i as i32])?;
+      descriptor.mut_message_type().push(linked);
+    }
+    for (i, enumeration) in file.enums.iter().enumerate() {
+      let linked = self.enumeration(enumeration, &[FILE_ENUMS, i as i32])?;
+      descriptor.mut_enum_type().push(linked);
+    }
+    for (i, service) in file.services.iter().enumerate() {
+      let linked = self.service(service, &package, &[FILE_SERVICES, i as i32])?;
+      descriptor.mut_service().push(linked);
+    }
+    for extend in &file.extends {
+      let extendee = self.resolve_message(&extend.extendee, &package, &extend.location)?;
+      for field in &extend.fields {
+        let path = [FILE_EXTENSIONS, descriptor.get_extension().len() as i32];
+        let mut linked = self.field(field, &package, &path)?;
+        linked.set_extendee(extendee.clone());
+        descriptor.mut_extension().push(linked);
+      }
+    }
+    if !file.options.is_empty() {
+      descriptor.set_options(self.file_options(&file.options)?);
+    }
+    let mut info = SourceCodeInfo::new();
+    info.set_location(self.locations.into());
+    descriptor.set_source_code_info(info);
+    Ok(descriptor)
+  }
+
+  fn message(
+    &mut self,
+    message: &Message,
+    scope: &str,
+    path: &[i32],
+  ) -> Result<DescriptorProto, ParseError> {
+    self.record(path, &message.location);
+    let name = qualify(scope, &message.name);
+    let mut descriptor = DescriptorProto::new();
+    descriptor.set_name(message.name.clone());
+    let child = |kind: i32, i: usize| {
+      let mut child = path.to_vec();
+      child.extend(&[kind, i as i32]);
+      child
+    };
+    let mut synthetic = Vec::new();
+    for (i, field) in message.fields.iter().enumerate() {
+      let mut linked = self.field(field, &name, &child(MESSAGE_FIELDS, i))?;
+      if let Some(ref oneof) = field.oneof {
+        let index = message.oneofs.iter().position(|o| o.name == *oneof);
+        linked.set_oneof_index(index.unwrap_or_default() as i32);
+      } else if self.proto3 && field.label == Label::Optional {
+        linked.set_proto3_optional(true);
+        linked.set_oneof_index((message.oneofs.len() + synthetic.len()) as i32);
+        synthetic.push(format!("_{}", field.name));
+      }
+      descriptor.mut_field().push(linked);
+    }
+    // protoc adds the entry messages of map fields where the
+    // fields are declared, among the nested messages.
+    let mut nested: Vec<Nested> = message.messages.iter().map(Nested::Message).collect();
+    for field in &message.fields {
+      if map_types(&field.type_name).is_some() {
+        nested.push(Nested::MapEntry(field));
+      }
+    }
+    nested.sort_by_key(Nested::start);
+    for (i, nested) in nested.iter().enumerate() {
+      let linked = match nested {
+        Nested::Message(nested) => self.message(nested, &name, &child(MESSAGE_MESSAGES, i))?,
+        Nested::MapEntry(field) => self.map_entry(field, &name)?,
+      };
+      descriptor.mut_nested_type().push(linked);
+    }
+    for (i, enumeration) in message.enums.iter().enumerate() {
+      let linked = self.enumeration(enumeration, &child(MESSAGE_ENUMS, i))?;
+      descriptor.mut_enum_type().push(linked);
+    }
+    for extend in &message.extends {
+      let extendee = self.resolve_message(&extend.extendee, &name, &extend.location)?;
+      for field in &extend.fields {
+        let i = descriptor.get_extension().len();
+        let mut linked = self.field(field, &name, &child(MESSAGE_EXTENSIONS, i))?;
+        linked.set_extendee(extendee.clone());
+        descriptor.mut_extension().push(linked);
+      }
+    }
+    for &(start, end) in &message.extensions {
+      let mut range = DescriptorProto_ExtensionRange::new();
+      range.set_start(start);
+      range.set_end(exclusive_end(end));
+      descriptor.mut_extension_range().push(range);
+    }
+    for (i, oneof) in message.oneofs.iter().enumerate() {
+      self.record(&child(MESSAGE_ONEOFS, i), &oneof.location);
+      let mut linked = OneofDescriptorProto::new();
+      linked.set_name(oneof.name.clone());
+      if !oneof.options.is_empty() {
+        let mut options = OneofOptions::new();
+        for option in &oneof.options {
+          let uninterpreted = self.uninterpreted(option)?;
+          options.mut_uninterpreted_option().push(uninterpreted);
+        }
+        linked.set_options(options);
+      }
+      descriptor.mut_oneof_decl().push(linked);
+    }
+    for name in synthetic {
+      let mut linked = OneofDescriptorProto::new();
+      linked.set_name(name);
+      descriptor.mut_oneof_decl().push(linked);
+    }
+    for &(start, end) in &message.reserved.ranges {
+      let mut range = DescriptorProto_ReservedRange::new();
+      range.set_start(start);
+      range.set_end(exclusive_end(end));
+      descriptor.mut_reserved_range().push(range);
+    }
+    descriptor.set_reserved_name(message.reserved.names.clone().into());
+    if !message.options.is_empty() {
+      descriptor.set_options(self.message_options(&message.options)?);
+    }
+    Ok(descriptor)
+  }
+
+  fn map_entry(&mut self, field: &Field, scope: &str) -> Result<DescriptorProto, ParseError> {
+    let (key, value) = map_types(&field.type_name).unwrap();
+    let mut entry = DescriptorProto::new();
+    entry.set_name(entry_name(&field.name));
+    for (number, (name, type_name)) in [("key", key), ("value", value)].iter().enumerate() {
+      let mut linked = FieldDescriptorProto::new();
+      linked.set_name(name.to_string());
+      linked.set_number(number as i32 + 1);
+      linked.set_label(FieldDescriptorProto_Label::LABEL_OPTIONAL);
+      linked.set_json_name(name.to_string());
+      self.set_type(&mut linked, type_name, false, scope, &field.location)?;
+      entry.mut_field().push(linked);
+    }
+    let mut options = MessageOptions::new();
+    options.set_map_entry(true);
+    entry.set_options(options);
+    Ok(entry)
+  }
+
+  fn set_type(
+    &self,
+    descriptor: &mut FieldDescriptorProto,
+    type_name: &str,
+    group: bool,
+    scope: &str,
+    location: &Location,
+  ) -> Result<(), ParseError> {
+    if let Some(scalar) = scalar_type(type_name) {
+      descriptor.set_field_type(scalar);
+      return Ok(());
+    }
+    let (full, kind) = self.resolve(type_name, scope, location)?;
+    descriptor.set_field_type(match kind {
+      Kind::Enum => FieldDescriptorProto_Type::TYPE_ENUM,
+      _ if group => FieldDescriptorProto_Type::TYPE_GROUP,
+      _ => FieldDescriptorProto_Type::TYPE_MESSAGE,
+    });
+    descriptor.set_type_name(full);
+    Ok(())
+  }
+
+  fn field(
+    &mut self,
+    field: &Field,
+    scope: &str,
+    path: &[i32],
+  ) -> Result<FieldDescriptorProto, ParseError> {
+    self.record(path, &field.location);
+    let mut descriptor = FieldDescriptorProto::new();
+    descriptor.set_name(field.name.clone());
+    descriptor.set_number(field.number);
+    descriptor.set_json_name(compat::json_name(field));
+    descriptor.set_label(match field.label {
+      Label::Required => FieldDescriptorProto_Label::LABEL_REQUIRED,
+      Label::Repeated => FieldDescriptorProto_Label::LABEL_REPEATED,
+      Label::None | Label::Optional => FieldDescriptorProto_Label::LABEL_OPTIONAL,
+    });
+    if map_types(&field.type_name).is_some() {
+      descriptor.set_label(FieldDescriptorProto_Label::LABEL_REPEATED);
+      descriptor.set_field_type(FieldDescriptorProto_Type::TYPE_MESSAGE);
+      let entry = qualify(scope, &entry_name(&field.name));
+      descriptor.set_type_name(format!(".{}", entry));
+    } else {
+      self.set_type(
+        &mut descriptor,
+        &field.type_name,
+        field.group,
+        scope,
+        &field.location,
+      )?;
+    }
+    let mut options = FieldOptions::new();
+    let mut any = false;
+    for option in &field.options {
+      let set = match option.name.as_str() {
+        "default" => {
+          let value = unquote(&option.value).unwrap_or_else(|| option.value.clone());
+          descriptor.set_default_value(value);
+          continue;
+        }
+        // Already taken into account by `compat::json_name`.
+        "json_name" => continue,
+        "ctype" => enumeration::<FieldOptions_CType>(option).map(|v| options.set_ctype(v)),
+        "packed" => boolean(option).map(|v| options.set_packed(v)),
+        "jstype" => enumeration::<FieldOptions_JSType>(option).map(|v| options.set_jstype(v)),
+        "lazy" => boolean(option).map(|v| options.set_lazy(v)),
+        "deprecated" => boolean(option).map(|v| options.set_deprecated(v)),
+        "weak" => boolean(option).map(|v| options.set_weak(v)),
+        _ => custom(option).map(|v| options.mut_uninterpreted_option().push(v)),
+      };
+      set.map_err(|message| self.error(&option.location, message))?;
+      any = true;
+    }
+    if any {
+      descriptor.set_options(options);
+    }
+    Ok(descriptor)
+  }
+
+  fn enumeration(
+    &mut self,
+    enumeration: &Enum,
+    path: &[i32],
+  ) -> Result<EnumDescriptorProto, ParseError> {
+    self.record(path, &enumeration.location);
+    let mut descriptor = EnumDescriptorProto::new();
+    descriptor.set_name(enumeration.name.clone());
+    for (i, value) in enumeration.values.iter().enumerate() {
+      let mut value_path = path.to_vec();
+      value_path.extend(&[ENUM_VALUES, i as i32]);
+      self.record(&value_path, &value.location);
+      let mut linked = EnumValueDescriptorProto::new();
+      linked.set_name(value.name.clone());
+      linked.set_number(value.number);
+      if !value.options.is_empty() {
+        let mut options = EnumValueOptions::new();
+        for option in &value.options {
+          let set = match option.name.as_str() {
+            "deprecated" => boolean(option).map(|v| options.set_deprecated(v)),
+            _ => custom(option).map(|v| options.mut_uninterpreted_option().push(v)),
+          };
+          set.map_err(|message| self.error(&option.location, message))?;
+        }
+        linked.set_options(options);
+      }
+      descriptor.mut_value().push(linked);
+    }
+    let Reserved { ranges, names } = &enumeration.reserved;
+    for &(start, end) in ranges {
+      let mut range = EnumDescriptorProto_EnumReservedRange::new();
+      range.set_start(start);
+      range.set_end(end);
+      descriptor.mut_reserved_range().push(range);
+    }
+    descriptor.set_reserved_name(names.clone().into());
+    if !enumeration.options.is_empty() {
+      let mut options = EnumOptions::new();
+      for option in &enumeration.options {
+        let set = match option.name.as_str() {
+          "allow_alias" => boolean(option).map(|v| options.set_allow_alias(v)),
+          "deprecated" => boolean(option).map(|v| options.set_deprecated(v)),
+          _ => custom(option).map(|v| options.mut_uninterpreted_option().push(v)),
+        };
+        set.map_err(|message| self.error(&option.location, message))?;
+      }
+      descriptor.set_options(options);
+    }
+    Ok(descriptor)
+  }
+
+  fn service(
+    &mut self,
+    service: &Service,
+    scope: &str,
+    path: &[i32],
+  ) -> Result<ServiceDescriptorProto, ParseError> {
+    self.record(path, &service.location);
+    let mut descriptor = ServiceDescriptorProto::new();
+    descriptor.set_name(service.name.clone());
+    for (i, rpc) in service.rpcs.iter().enumerate() {
+      let mut method_path = path.to_vec();
+      method_path.extend(&[SERVICE_METHODS, i as i32]);
+      self.record(&method_path, &rpc.location);
+      let mut method = MethodDescriptorProto::new();
+      method.set_name(rpc.name.clone());
+      method.set_input_type(self.resolve_message(&rpc.input_type, scope, &rpc.location)?);
+      method.set_output_type(self.resolve_message(&rpc.output_type, scope, &rpc.location)?);
+      if rpc.client_streaming {
+        method.set_client_streaming(true);
+      }
+      if rpc.server_streaming {
+        method.set_server_streaming(true);
+      }
+      if !rpc.options.is_empty() {
+        let mut options = MethodOptions::new();
+        for option in &rpc.options {
+          let set = match option.name.as_str() {
+            "deprecated" => boolean(option).map(|v| options.set_deprecated(v)),
+            "idempotency_level" => enumeration::<MethodOptions_IdempotencyLevel>(option)
+              .map(|v| options.set_idempotency_level(v)),
+            _ => custom(option).map(|v| options.mut_uninterpreted_option().push(v)),
+          };
+          set.map_err(|message| self.error(&option.location, message))?;
+        }
+        method.set_options(options);
+      }
+      descriptor.mut_method().push(method);
+    }
+    if !service.options.is_empty() {
+      let mut options = ServiceOptions::new();
+      for option in &service.options {
+        let set = match option.name.as_str() {
+          "deprecated" => boolean(option).map(|v| options.set_deprecated(v)),
+          _ => custom(option).map(|v| options.mut_uninterpreted_option().push(v)),
+        };
+        set.map_err(|message| self.error(&option.location, message))?;
+      }
+      descriptor.set_options(options);
+    }
+    Ok(descriptor)
+  }
+
+  fn message_options(&self, declared: &[ProtoOption]) -> Result<MessageOptions, ParseError> {
+    let mut options = MessageOptions::new();
+    for option in declared {
+      let set = match option.name.as_str() {
+        "message_set_wire_format" => {
+          boolean(option).map(|v| options.set_message_set_wire_format(v))
+        }
+        "no_standard_descriptor_accessor" => {
+          boolean(option).map(|v| options.set_no_standard_descriptor_accessor(v))
+        }
+        "deprecated" => boolean(option).map(|v| options.set_deprecated(v)),
+        _ => custom(option).map(|v| options.mut_uninterpreted_option().push(v)),
+      };
+      set.map_err(|message| self.error(&option.location, message))?;
+    }
+    Ok(options)
+  }
+
+  fn file_options(&self, declared: &[ProtoOption]) -> Result<FileOptions, ParseError> {
+    let mut options = FileOptions::new();
+    for option in declared {
+      let set = match option.name.as_str() {
+        "java_package" => string(option).map(|v| options.set_java_package(v)),
+        "java_outer_classname" => string(option).map(|v| options.set_java_outer_classname(v)),
+        "java_multiple_files" => boolean(option).map(|v| options.set_java_multiple_files(v)),
+        "java_generate_equals_and_hash" => {
+          boolean(option).map(|v| options.set_java_generate_equals_and_hash(v))
+        }
+        "java_string_check_utf8" => boolean(option).map(|v| options.set_java_string_check_utf8(v)),
+        "optimize_for" => {
+          enumeration::<FileOptions_OptimizeMode>(option).map(|v| options.set_optimize_for(v))
+        }
+        "go_package" => string(option).map(|v| options.set_go_package(v)),
+        "cc_generic_services" => boolean(option).map(|v| options.set_cc_generic_services(v)),
+        "java_generic_services" => boolean(option).map(|v| options.set_java_generic_services(v)),
+        "py_generic_services" => boolean(option).map(|v| options.set_py_generic_services(v)),
+        "php_generic_services" => boolean(option).map(|v| options.set_php_generic_services(v)),
+        "deprecated" => boolean(option).map(|v| options.set_deprecated(v)),
+        "cc_enable_arenas" => boolean(option).map(|v| options.set_cc_enable_arenas(v)),
+        "objc_class_prefix" => string(option).map(|v| options.set_objc_class_prefix(v)),
+        "csharp_namespace" => string(option).map(|v| options.set_csharp_namespace(v)),
+        "swift_prefix" => string(option).map(|v| options.set_swift_prefix(v)),
+        "php_class_prefix" => string(option).map(|v| options.set_php_class_prefix(v)),
+        "php_namespace" => string(option).map(|v| options.set_php_namespace(v)),
+        "php_metadata_namespace" => string(option).map(|v| options.set_php_metadata_namespace(v)),
+        "ruby_package" => string(option).map(|v| options.set_ruby_package(v)),
+        _ => custom(option).map(|v| options.mut_uninterpreted_option().push(v)),
+      };
+      set.map_err(|message| self.error(&option.location, message))?;
+    }
+    Ok(options)
+  }
+
+  fn uninterpreted(&self, option: &ProtoOption) -> Result<UninterpretedOption, ParseError> {
+    custom(option).map_err(|message| self.error(&option.location, message))
+  }
+}
+
+/// The text of a string constant, undoing the escaping `ProtoOption` values
+/// are quoted again with.
+fn unquote(value: &str) -> Option<String> {
+  if value.len() < 2 || !value.starts_with('"') || !value.ends_with('"') {
+    return None;
+  }
+  let mut text = String::new();
+  let mut chars = value[1..value.len() - 1].chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      text.push(c);
+      continue;
+    }
+    match chars.next()? {
+      'n' => text.push('\n'),
+      'r' => text.push('\r'),
+      't' => text.push('\t'),
+      '0' => text.push('\0'),
+      'u' => {
+        let code: String = chars.by_ref().skip(1).take_while(|c| *c != '}').collect();
+        text.push(std::char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+      }
+      escaped => text.push(escaped),
+    }
+  }
+  Some(text)
+}
+
+fn string(option: &ProtoOption) -> Result<String, String> {
+  unquote(&option.value).ok_or_else(|| format!("option `{}` takes a string", option.name))
+}
+
+fn boolean(option: &ProtoOption) -> Result<bool, String> {
+  match option.value.as_str() {
+    "true" => Ok(true),
+    "false" => Ok(false),
+    _ => Err(format!("option `{}` takes `true` or `false`", option.name)),
+  }
+}
+
+fn enumeration<E: ProtobufEnum + fmt::Debug>(option: &ProtoOption) -> Result<E, String> {
+  let values = E::values();
+  values
+    .iter()
+    .find(|value| format!("{:?}", value) == option.value)
+    .cloned()
+    .ok_or_else(|| {
+      let names: Vec<String> = values.iter().map(|value| format!("{:?}", value)).collect();
+      format!("option `{}` takes one of {}", option.name, names.join(", "))
+    })
+}
+
+/// A custom option, left uninterpreted the way protoc keeps options before
+/// it has the extensions defining them.
+fn custom(option: &ProtoOption) -> Result<UninterpretedOption, String> {
+  if !option.name.starts_with('(') {
+    return Err(format!("unknown option `{}`", option.name));
+  }
+  let mut uninterpreted = UninterpretedOption::new();
+  let mut rest = option.name.as_str();
+  while !rest.is_empty() {
+    let mut part = UninterpretedOption_NamePart::new();
+    if rest.starts_with('(') {
+      let end = rest.find(')').unwrap_or(rest.len() - 1);
+      part.set_name_part(rest[1..end].to_string());
+      part.set_is_extension(true);
+      rest = &rest[end + 1..];
+    } else {
+      let end = rest.find('.').unwrap_or(rest.len());
+      part.set_name_part(rest[..end].to_string());
+      part.set_is_extension(false);
+      rest = &rest[end..];
+    }
+    rest = rest.trim_start_matches('.');
+    uninterpreted.mut_name().push(part);
+  }
+  let value = option.value.as_str();
+  if let Some(text) = unquote(value) {
+    uninterpreted.set_string_value(text.into_bytes());
+  } else if value.starts_with('{') {
+    let aggregate = value.trim_start_matches('{').trim_end_matches('}');
+    uninterpreted.set_aggregate_value(aggregate.trim().to_string());
+  } else if value.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+    uninterpreted.set_identifier_value(value.to_string());
+  } else if let Ok(number) = value.trim_start_matches('+').parse::<u64>() {
+    uninterpreted.set_positive_int_value(number);
+  } else if let Ok(number) = value.parse::<i64>() {
+    uninterpreted.set_negative_int_value(number);
+  } else if let Ok(number) = value.parse::<f64>() {
+    uninterpreted.set_double_value(number);
+  } else {
+    return Err(format!(
+      "option `{}` has an invalid value `{}`",
+      option.name, value
+    ));
+  }
+  Ok(uninterpreted)
+}
+
+#[cfg(test)]
+fn sources(files: &[(&str, &str)]) -> Vec<SourceFile> {
+  files
+    .iter()
+    .map(|(path, source)| SourceFile {
+      path: std::path::PathBuf::from(path),
+      file: crate::proto::parse(source).unwrap(),
+    })
+    .collect()
+}
+
+#[test]
+fn should_link_descriptors() {
+  let files = sources(&[
+    (
+      "acme/common/money.proto",
+      "syntax = \"proto3\"; package acme.common;
+       message Money { string currency = 1; int64 units = 2; }",
+    ),
+    (
+      "acme/orders/v1/orders.proto",
+      "syntax = \"proto3\";
+package acme.orders.v1;
+import public \"acme/common/money.proto\";
+option java_package = \"com.acme.orders\";
+
+// An order.
+message Order {
+  string order_id = 1 [json_name = \"id\"];
+  map<string, common.Money> totals = 2;
+  oneof payment { string card = 3; Voucher voucher = 4; }
+  optional string note = 5;
+  message Voucher { string code = 1; }
+  Status status = 6 [deprecated = true, (acme.sensitive) = true];
+  reserved 7 to max;
+  enum Status { STATUS_UNKNOWN = 0; STATUS_OPEN = 1; reserved 5 to 9; }
+}
+
+service Orders {
+  rpc Watch (Order) returns (stream .acme.orders.v1.Order) {
+    option idempotency_level = NO_SIDE_EFFECTS;
+  }
+}",
+    ),
+  ]);
+  let linked = link(&files).unwrap();
+  let file = &linked[1];
+  assert_eq!(file.get_name(), "acme/orders/v1/orders.proto");
+  assert_eq!(file.get_dependency(), ["acme/common/money.proto"]);
+  assert_eq!(file.get_public_dependency(), [0]);
+  assert_eq!(file.get_syntax(), "proto3");
+  assert_eq!(file.get_options().get_java_package(), "com.acme.orders");
+  let order = &file.get_message_type()[0];
+  let fields: Vec<(&str, &str, FieldDescriptorProto_Type, &str)> = order
+    .get_field()
+    .iter()
+    .map(|field| {
+      (
+        field.get_name(),
+        field.get_json_name(),
+        field.get_field_type(),
+        field.get_type_name(),
+      )
+    })
+    .collect();
+  assert_eq!(
+    fields,
+    vec![
+      ("order_id", "id", FieldDescriptorProto_Type::TYPE_STRING, ""),
+      (
+        "totals",
+        "totals",
+        FieldDescriptorProto_Type::TYPE_MESSAGE,
+        ".acme.orders.v1.Order.TotalsEntry"
+      ),
+      ("card", "card", FieldDescriptorProto_Type::TYPE_STRING, ""),
+      (
+        "voucher",
+        "voucher",
+        FieldDescriptorProto_Type::TYPE_MESSAGE,
+        ".acme.orders.v1.Order.Voucher"
+      ),
+      ("note", "note", FieldDescriptorProto_Type::TYPE_STRING, ""),
+      (
+        "status",
+        "status",
+        FieldDescriptorProto_Type::TYPE_ENUM,
+        ".acme.orders.v1.Order.Status"
+      ),
+    ]
+  );
+  assert_eq!(
+    order.get_field()[1].get_label(),
+    FieldDescriptorProto_Label::LABEL_REPEATED
+  );
+  let oneofs: Vec<&str> = order
+    .get_oneof_decl()
+    .iter()
+    .map(|o| o.get_name())
+    .collect();
+  assert_eq!(oneofs, vec!["payment", "_note"]);
+  assert_eq!(order.get_field()[3].get_oneof_index(), 0);
+  assert!(order.get_field()[4].get_proto3_optional());
+  assert_eq!(order.get_field()[4].get_oneof_index(), 1);
+  let status_options = order.get_field()[5].get_options();
+  assert!(status_options.get_deprecated());
+  assert_eq!(
+    status_options.get_uninterpreted_option()[0].get_name()[0].get_name_part(),
+    "acme.sensitive"
+  );
+  let nested: Vec<&str> = order
+    .get_nested_type()
+    .iter()
+    .map(|m| m.get_name())
+    .collect();
+  assert_eq!(nested, vec!["TotalsEntry", "Voucher"]);
+  let entry = &order.get_nested_type()[0];
+  assert!(entry.get_options().get_map_entry());
+  assert_eq!(entry.get_field()[1].get_type_name(), ".acme.common.Money");
+  assert_eq!(order.get_reserved_range()[0].get_start(), 7);
+  assert_eq!(order.get_reserved_range()[0].get_end(), 536_870_912);
+  assert_eq!(
+    order.get_enum_type()[0].get_reserved_range()[0].get_end(),
+    9
+  );
+  let watch = &file.get_service()[0].get_method()[0];
+  assert_eq!(watch.get_input_type(), ".acme.orders.v1.Order");
+  assert!(!watch.get_client_streaming() && watch.get_server_streaming());
+  assert_eq!(
+    watch.get_options().get_idempotency_level(),
+    MethodOptions_IdempotencyLevel::NO_SIDE_EFFECTS
+  );
+  let order_location = file
+    .get_source_code_info()
+    .get_location()
+    .iter()
+    .find(|location| location.get_path() == [FILE_MESSAGES, 0])
+    .unwrap();
+  assert_eq!(order_location.get_span(), [6, 0, 15, 1]);
+  assert_eq!(order_location.get_leading_comments(), " An order.\n");
+}
+
+#[test]
+fn should_point_link_errors_at_their_source() {
+  let error = |files: &[(&str, &str)]| link(&sources(files)).unwrap_err().to_string();
+  assert_eq!(
+    error(&[(
+      "orders.proto",
+      "syntax = \"proto3\";\nmessage Order {\n  Money total = 1;\n}"
+    )]),
+    "orders.proto:3:3: unknown type `Money`"
+  );
+  assert_eq!(
+    error(&[("orders.proto", "import \"money.proto\";")]),
+    "orders.proto:1:1: import `money.proto` not found"
+  );
+  assert_eq!(
+    error(&[
+      ("money.proto", "message Money {}"),
+      (
+        "orders.proto",
+        "message Order { optional Money total = 1; }"
+      ),
+    ]),
+    "orders.proto:1:17: `Money` is defined in money.proto, which isn't imported"
+  );
+  assert_eq!(
+    error(&[(
+      "orders.proto",
+      "message Order {}\nservice Orders {\n  rpc Get (Order) returns (Order) { option speed = FAST; }\n}"
+    )]),
+    "orders.proto:3:37: unknown option `speed`"
+  );
+  assert_eq!(
+    error(&[
+      ("a.proto", "package acme; message Order {}"),
+      ("b.proto", "package acme;\nenum Order { UNKNOWN = 0; }"),
+    ]),
+    "b.proto:2:1: `acme.Order` is already defined in a.proto"
+  );
+}
@@ -0,0 +1,1005 @@
+use crate::artifact::Artifact;
+use crate::compat::{self, Compatibility, Mode, Schema, Violation};
+use crate::manifest::MANIFEST_FILE_NAME;
+use crate::package_metadata::PackageMetadata;
+use crate::proto;
+use crate::proto::{Enum, Message, ProtoOption, Service, SourceFile};
+use crate::registry;
+use serde_derive::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[cfg(test)]
+use crate::proto::sources;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+  Added,
+  Removed,
+  Renamed,
+  Moved,
+  Changed,
+}
+
+impl fmt::Display for ChangeKind {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ChangeKind::Added => write!(f, "added"),
+      ChangeKind::Removed => write!(f, "removed"),
+      ChangeKind::Renamed => write!(f, "renamed"),
+      ChangeKind::Moved => write!(f, "moved"),
+      ChangeKind::Changed => write!(f, "changed"),
+    }
+  }
+}
+
+/// The kind of definition a change is about.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Element {
+  File,
+  Service,
+  Rpc,
+  Message,
+  Field,
+  Enum,
+  EnumValue,
+}
+
+impl fmt::Display for Element {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Element::File => write!(f, "file"),
+      Element::Service => write!(f, "service"),
+      Element::Rpc => write!(f, "rpc"),
+      Element::Message => write!(f, "message"),
+      Element::Field => write!(f, "field"),
+      Element::Enum => write!(f, "enum"),
+      Element::EnumValue => write!(f, "enum value"),
+    }
+  }
+}
+
+/// One difference between two versions of a schema.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+  pub kind: ChangeKind,
+  pub element: Element,
+  /// Fully-qualified name of the element, as of the previous version for
+  /// renamed fields and enum values.
+  pub path: String,
+  /// The new name of a renamed definition, or the file a moved definition
+  /// or file is in now.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub to: Option<String>,
+  /// What changed, e.g. "type changed from `int32` to `int64`".
+  pub details: Vec<String>,
+  /// The compatibility rules the change breaks, empty if it doesn't break
+  /// anything.
+  pub breaking: Vec<&'static str>,
+}
+
+impl Change {
+  fn new(kind: ChangeKind, element: Element, path: String) -> Self {
+    Change {
+      kind,
+      element,
+      path,
+      to: None,
+      details: Vec::new(),
+      breaking: Vec::new(),
+    }
+  }
+
+  pub fn is_breaking(&self) -> bool {
+    !self.breaking.is_empty()
+  }
+}
+
+impl fmt::Display for Change {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} {} {}", self.kind, self.element, self.path)?;
+    if let Some(ref to) = self.to {
+      write!(f, " to {}", to)?;
+    }
+    if !self.details.is_empty() {
+      write!(f, ": {}", self.details.join(", "))?;
+    }
+    Ok(())
+  }
+}
+
+/// The files, services, rpcs, messages, fields, enums and enum values that
+/// differ between `previous` and `current`, matched the way `compat::check`
+/// matches them: definitions by fully-qualified name, fields and enum values
+/// by number and rpcs by name. The members of added and removed definitions
+/// aren't listed on their own.
+///
+/// A message or enum that was removed while one with the same fields or
+/// values was added is listed as renamed, and definitions that are in a
+/// different file as moved, or the whole file as moved if everything in it
+/// went to a new one.
+pub fn diff(previous: &[SourceFile], current: &[SourceFile]) -> Vec<Change> {
+  walk(previous, current, None)
+}
+
+/// Like `diff`, with the changes that break the guarantees of
+/// `compatibility` marked with the rules they break, found by the checks
+/// `compat::check_compatibility` runs, applied to each element as it's
+/// compared. Suppressions don't apply, a suppressed change still breaks
+/// consumers.
+pub fn diff_with(
+  previous: &[SourceFile],
+  current: &[SourceFile],
+  compatibility: &Compatibility,
+) -> Vec<Change> {
+  walk(previous, current, Some(compatibility))
+}
+
+fn walk(
+  previous: &[SourceFile],
+  current: &[SourceFile],
+  compatibility: Option<&Compatibility>,
+) -> Vec<Change> {
+  let old = Schema::new(previous);
+  let new = Schema::new(current);
+  let mut changes = Vec::new();
+  for (name, old_service) in &old.services {
+    match new.services.get(name) {
+      Some(new_service) => diff_service(
+        &old,
+        &new,
+        name,
+        old_service,
+        new_service,
+        compatibility,
+        &mut changes,
+      ),
+      None => {
+        let mut change = Change::new(ChangeKind::Removed, Element::Service, name.clone());
+        mark(
+          &mut change,
+          compatibility,
+          |_| vec![compat::service_removed(name)],
+          |_| vec![],
+        );
+        changes.push(change);
+      }
+    }
+  }
+  for name in new.services.keys() {
+    if !old.services.contains_key(name) {
+      let mut change = Change::new(ChangeKind::Added, Element::Service, name.clone());
+      mark(
+        &mut change,
+        compatibility,
+        |_| vec![],
+        |_| vec![compat::service_removed(name)],
+      );
+      changes.push(change);
+    }
+  }
+  for (name, old_message) in &old.messages {
+    match new.messages.get(name) {
+      Some(new_message) => diff_message(
+        &old,
+        &new,
+        name,
+        old_message,
+        new_message,
+        compatibility,
+        &mut changes,
+      ),
+      None => {
+        let mut change = Change::new(ChangeKind::Removed, Element::Message, name.clone());
+        mark(
+          &mut change,
+          compatibility,
+          |mode| compat::message_removed(mode, name).into_iter().collect(),
+          |_| vec![],
+        );
+        changes.push(change);
+      }
+    }
+  }
+  for name in new.messages.keys() {
+    if !old.messages.contains_key(name) {
+      let mut change = Change::new(ChangeKind::Added, Element::Message, name.clone());
+      mark(
+        &mut change,
+        compatibility,
+        |_| vec![],
+        |mode| compat::message_removed(mode, name).into_iter().collect(),
+      );
+      changes.push(change);
+    }
+  }
+  for (name, old_enum) in &old.enums {
+    match new.enums.get(name) {
+      Some(new_enum) => diff_enum(name, old_enum, new_enum, compatibility, &mut changes),
+      None => {
+        let mut change = Change::new(ChangeKind::Removed, Element::Enum, name.clone());
+        mark(
+          &mut change,
+          compatibility,
+          |mode| compat::enum_removed(mode, name).into_iter().collect(),
+          |_| vec![],
+        );
+        changes.push(change);
+      }
+    }
+  }
+  for name in new.enums.keys() {
+    if !old.enums.contains_key(name) {
+      let mut change = Change::new(ChangeKind::Added, Element::Enum, name.clone());
+      mark(
+        &mut change,
+        compatibility,
+        |_| vec![],
+        |mode| compat::enum_removed(mode, name).into_iter().collect(),
+      );
+      changes.push(change);
+    }
+  }
+  detect_renames(&old, &new, &mut changes);
+  let mut files = diff_files(previous, current, &old, &new, &changes);
+  files.append(&mut changes);
+  files
+}
+
+/// Marks `change` with the rules it breaks under `compatibility`, if any,
+/// from the violations comparing its element `backward`, previous to
+/// current, and `forward`, the other way around, in the configured mode.
+fn mark<B, F>(change: &mut Change, compatibility: Option<&Compatibility>, backward: B, forward: F)
+where
+  B: FnOnce(Mode) -> Vec<Violation>,
+  F: FnOnce(Mode) -> Vec<Violation>,
+{
+  if let Some(compatibility) = compatibility {
+    change.breaking = compat::breaking_rules(
+      compatibility,
+      backward(compatibility.mode),
+      forward(compatibility.mode),
+    );
+  }
+}
+
+fn diff_service(
+  old: &Schema,
+  new: &Schema,
+  name: &str,
+  old_service: &Service,
+  new_service: &Service,
+  compatibility: Option<&Compatibility>,
+  changes: &mut Vec<Change>,
+) {
+  if !proto::same_options(&old_service.options, &new_service.options) {
+    let mut change = Change::new(ChangeKind::Changed, Element::Service, name.to_string());
+    change
+      .details
+      .push(options_changed(&old_service.options, &new_service.options));
+    changes.push(change);
+  }
+  let scope = compat::parent_scope(name);
+  for old_rpc in &old_service.rpcs {
+    let path = format!("{}.{}", name, old_rpc.name);
+    let new_rpc = match new_service.rpcs.iter().find(|rpc| rpc.name == old_rpc.name) {
+      Some(new_rpc) => new_rpc,
+      None => {
+        let mut change = Change::new(ChangeKind::Removed, Element::Rpc, path);
+        mark(
+          &mut change,
+          compatibility,
+          |_| vec![compat::rpc_removed(name, old_rpc)],
+          |_| vec![],
+        );
+        changes.push(change);
+        continue;
+      }
+    };
+    let mut change = Change::new(ChangeKind::Changed, Element::Rpc, path);
+    let types = [
+      ("request", &old_rpc.input_type, &new_rpc.input_type),
+      ("response", &old_rpc.output_type, &new_rpc.output_type),
+    ];
+    for (kind, old_type, new_type) in types.iter() {
+      let old_type = old.resolve(scope, old_type);
+      let new_type = new.resolve(scope, new_type);
+      if old_type != new_type {
+        change.details.push(format!(
+          "{} type changed from `{}` to `{}`",
+          kind, old_type, new_type
+        ));
+      }
+    }
+    let streaming = [
+      ("client", old_rpc.client_streaming, new_rpc.client_streaming),
+      ("server", old_rpc.server_streaming, new_rpc.server_streaming),
+    ];
+    for (side, old_streaming, new_streaming) in streaming.iter() {
+      if old_streaming != new_streaming {
+        change.details.push(format!(
+          "{} streaming {}",
+          side,
+          if *new_streaming { "added" } else { "removed" }
+        ));
+      }
+    }
+    if !proto::same_options(&old_rpc.options, &new_rpc.options) {
+      change
+        .details
+        .push(options_changed(&old_rpc.options, &new_rpc.options));
+    }
+    mark(
+      &mut change,
+      compatibility,
+      |_| compat::check_rpc(old, new, name, old_rpc, new_rpc),
+      |_| compat::check_rpc(new, old, name, new_rpc, old_rpc),
+    );
+    if !change.details.is_empty() || change.is_breaking() {
+      changes.push(change);
+    }
+  }
+  for rpc in &new_service.rpcs {
+    if !old_service.rpcs.iter().any(|old| old.name == rpc.name) {
+      let mut change = Change::new(
+        ChangeKind::Added,
+        Element::Rpc,
+        format!("{}.{}", name, rpc.name),
+      );
+      mark(
+        &mut change,
+        compatibility,
+        |_| vec![],
+        |_| vec![compat::rpc_removed(name, rpc)],
+      );
+      changes.push(change);
+    }
+  }
+}
+
+fn diff_message(
+  old: &Schema,
+  new: &Schema,
+  name: &str,
+  old_message: &Message,
+  new_message: &Message,
+  compatibility: Option<&Compatibility>,
+  changes: &mut Vec<Change>,
+) {
+  if !proto::same_options(&old_message.options, &new_message.options) {
+    let mut change = Change::new(ChangeKind::Changed, Element::Message, name.to_string());
+    change
+      .details
+      .push(options_changed(&old_message.options, &new_message.options));
+    changes.push(change);
+  }
+  for old_field in &old_message.fields {
+    let path = format!("{}.{}", name, old_field.name);
+    let new_field = match new_message
+      .fields
+      .iter()
+      .find(|field| field.number == old_field.number)
+    {
+      Some(new_field) => new_field,
+      None => {
+        let mut change = Change::new(ChangeKind::Removed, Element::Field, path);
+        if new_message.reserved.contains_number(old_field.number) {
+          change
+            .details
+            .push(format!("number {} reserved", old_field.number));
+        }
+        mark(
+          &mut change,
+          compatibility,
+          |_| {
+            compat::field_removed(name, old_field, new_message)
+              .into_iter()
+              .collect()
+          },
+          |_| vec![],
+        );
+        changes.push(change);
+        continue;
+      }
+    };
+    let mut change = Change::new(ChangeKind::Changed, Element::Field, path);
+    if new_field.name != old_field.name {
+      change
+        .details
+        .push(format!("renamed to `{}`", new_field.name));
+    } else if compat::json_name(old_field) != compat::json_name(new_field) {
+      change.details.push(format!(
+        "JSON name changed from `{}` to `{}`",
+        compat::json_name(old_field),
+        compat::json_name(new_field)
+      ));
+    }
+    let old_type = old.resolve(name, &old_field.type_name);
+    let new_type = new.resolve(name, &new_field.type_name);
+    if old_type != new_type {
+      change.details.push(format!(
+        "type changed from `{}` to `{}`",
+        old_type, new_type
+      ));
+    }
+    if old_field.label != new_field.label {
+      change.details.push(format!(
+        "label changed from {} to {}",
+        compat::label_name(old_field.label),
+        compat::label_name(new_field.label)
+      ));
+    }
+    if old_field.oneof != new_field.oneof {
+      change.details.push(format!(
+        "moved from {} to {}",
+        compat::oneof_name(&old_field.oneof),
+        compat::oneof_name(&new_field.oneof)
+      ));
+    }
+    let old_options = field_options(&old_field.options);
+    let new_options = field_options(&new_field.options);
+    if !proto::same_options(&old_options, &new_options) {
+      change
+        .details
+        .push(options_changed(&old_options, &new_options));
+    }
+    mark(
+      &mut change,
+      compatibility,
+      |mode| compat::check_field(old, new, mode, name, old_field, new_field),
+      |mode| compat::check_field(new, old, mode, name, new_field, old_field),
+    );
+    if !change.details.is_empty() || change.is_breaking() {
+      changes.push(change);
+    }
+  }
+  for field in &new_message.fields {
+    if !old_message
+      .fields
+      .iter()
+      .any(|old| old.number == field.number)
+    {
+      let mut change = Change::new(
+        ChangeKind::Added,
+        Element::Field,
+        format!("{}.{}", name, field.name),
+      );
+      change.details.push(format!("number {}", field.number));
+      mark(
+        &mut change,
+        compatibility,
+        |_| vec![],
+        |_| {
+          compat::field_removed(name, field, old_message)
+            .into_iter()
+            .collect()
+        },
+      );
+      changes.push(change);
+    }
+  }
+}
+
+fn diff_enum(
+  name: &str,
+  old_enum: &Enum,
+  new_enum: &Enum,
+  compatibility: Option<&Compatibility>,
+  changes: &mut Vec<Change>,
+) {
+  if !proto::same_options(&old_enum.options, &new_enum.options) {
+    let mut change = Change::new(ChangeKind::Changed, Element::Enum, name.to_string());
+    change
+      .details
+      .push(options_changed(&old_enum.options, &new_enum.options));
+    changes.push(change);
+  }
+  for old_value in &old_enum.values {
+    let path = format!("{}.{}", name, old_value.name);
+    let same_number: Vec<_> = new_enum
+      .values
+      .iter()
+      .filter(|value| value.number == old_value.number)
+      .collect();
+    if same_number.is_empty() {
+      let mut change = Change::new(ChangeKind::Removed, Element::EnumValue, path);
+      if new_enum.reserved.contains_number(old_value.number) {
+        change
+          .details
+          .push(format!("number {} reserved", old_value.number));
+      }
+      mark(
+        &mut change,
+        compatibility,
+        |mode| {
+          compat::check_enum_value(mode, name, old_value, new_enum)
+            .into_iter()
+            .collect()
+        },
+        |_| vec![],
+      );
+      changes.push(change);
+      continue;
+    }
+    let mut change = Change::new(ChangeKind::Changed, Element::EnumValue, path);
+    let new_value = match same_number
+      .iter()
+      .find(|value| value.name == old_value.name)
+    {
+      Some(new_value) => new_value,
+      None => {
+        change
+          .details
+          .push(format!("renamed to `{}`", same_number[0].name));
+        same_number[0]
+      }
+    };
+    if !proto::same_options(&old_value.options, &new_value.options) {
+      change
+        .details
+        .push(options_changed(&old_value.options, &new_value.options));
+    }
+    mark(
+      &mut change,
+      compatibility,
+      |mode| {
+        compat::check_enum_value(mode, name, old_value, new_enum)
+          .into_iter()
+          .collect()
+      },
+      |mode| {
+        same_number
+          .iter()
+          .filter_map(|value| compat::check_enum_value(mode, name, value, old_enum))
+          .collect()
+      },
+    );
+    if !change.details.is_empty() || change.is_breaking() {
+      changes.push(change);
+    }
+  }
+  for value in &new_enum.values {
+    if !old_enum.values.iter().any(|old| old.number == value.number) {
+      let mut change = Change::new(
+        ChangeKind::Added,
+        Element::EnumValue,
+        format!("{}.{}", name, value.name),
+      );
+      change.details.push(format!("number {}", value.number));
+      mark(
+        &mut change,
+        compatibility,
+        |_| vec![],
+        |mode| {
+          compat::check_enum_value(mode, name, value, old_enum)
+            .into_iter()
+            .collect()
+        },
+      );
+      changes.push(change);
+    }
+  }
+}
+
+/// Turns each removed message or enum into a rename if exactly one added
+/// definition of the same kind has the same fields or values, and no other
+/// removed one does too. Empty definitions are never matched.
+fn detect_renames(old: &Schema, new: &Schema, changes: &mut Vec<Change>) {
+  let signature = |change: &Change, schema: &Schema| -> Option<Vec<String>> {
+    let signature: Vec<String> = match change.element {
+      Element::Message => schema.messages[&change.path]
+        .fields
+        .iter()
+        .map(|field| {
+          format!(
+            "{} {} {} {} {:?}",
+            field.number,
+            field.name,
+            compat::label_name(field.label),
+            field.type_name,
+            field.oneof
+          )
+        })
+        .collect(),
+      Element::Enum => schema.enums[&change.path]
+        .values
+        .iter()
+        .map(|value| format!("{} {}", value.number, value.name))
+        .collect(),
+      _ => return None,
+    };
+    if signature.is_empty() {
+      None
+    } else {
+      Some(signature)
+    }
+  };
+  let removed: Vec<(usize, Vec<String>)> = changes
+    .iter()
+    .enumerate()
+    .filter(|(_, change)| change.kind == ChangeKind::Removed)
+    .filter_map(|(i, change)| signature(change, old).map(|signature| (i, signature)))
+    .collect();
+  let added: Vec<(usize, Vec<String>)> = changes
+    .iter()
+    .enumerate()
+    .filter(|(_, change)| change.kind == ChangeKind::Added)
+    .filter_map(|(i, change)| signature(change, new).map(|signature| (i, signature)))
+    .collect();
+  let mut renamed = Vec::new();
+  for (i, signature) in &removed {
+    let element = changes[*i].element;
+    let same =
+      |(j, other): &&(usize, Vec<String>)| changes[*j].element == element && other == signature;
+    let candidates: Vec<&(usize, Vec<String>)> = added.iter().filter(same).collect();
+    if candidates.len() == 1 && removed.iter().filter(same).count() == 1 {
+      renamed.push((*i, candidates[0].0));
+    }
+  }
+  for (i, j) in &renamed {
+    changes[*i].kind = ChangeKind::Renamed;
+    changes[*i].to = Some(changes[*j].path.clone());
+    for rule in changes[*j].breaking.clone() {
+      if !changes[*i].breaking.contains(&rule) {
+        changes[*i].breaking.push(rule);
+      }
+    }
+  }
+  let mut replaced: Vec<usize> = renamed.iter().map(|(_, j)| *j).collect();
+  replaced.sort();
+  for j in replaced.into_iter().rev() {
+    changes.remove(j);
+  }
+}
+
+/// The files that were added, removed or moved, and the definitions that
+/// are in a different file than before.
+fn diff_files(
+  previous: &[SourceFile],
+  current: &[SourceFile],
+  old: &Schema,
+  new: &Schema,
+  changes: &[Change],
+) -> Vec<Change> {
+  let display = |path: &Path| path.display().to_string();
+  let mut moves: Vec<(String, String, String)> = Vec::new();
+  for (name, old_file) in &old.files {
+    let new_name = changes
+      .iter()
+      .find(|change| change.kind == ChangeKind::Renamed && change.path == *name)
+      .and_then(|change| change.to.as_ref())
+      .unwrap_or(name);
+    if let Some(new_file) = new.files.get(new_name) {
+      if old_file != new_file {
+        moves.push((new_name.clone(), display(old_file), display(new_file)));
+      }
+    }
+  }
+  let old_paths: Vec<String> = previous
+    .iter()
+    .map(|source| display(&source.path))
+    .collect();
+  let new_paths: Vec<String> = current.iter().map(|source| display(&source.path)).collect();
+  let mut file_changes = Vec::new();
+  for path in &old_paths {
+    if new_paths.contains(path) {
+      continue;
+    }
+    let definitions: Vec<&String> = old
+      .files
+      .iter()
+      .filter(|(_, file)| display(file) == *path)
+      .map(|(name, _)| name)
+      .collect();
+    let destinations: Vec<&String> = moves
+      .iter()
+      .filter(|(_, from, _)| from == path)
+      .map(|(_, _, to)| to)
+      .collect();
+    let whole_file = !definitions.is_empty()
+      && destinations.len() == definitions.len()
+      && destinations.iter().all(|to| *to == destinations[0])
+      && !old_paths.contains(destinations[0]);
+    let mut change = Change::new(ChangeKind::Removed, Element::File, path.clone());
+    if whole_file {
+      change.kind = ChangeKind::Moved;
+      change.to = Some(destinations[0].clone());
+      moves.retain(|(_, from, _)| from != path);
+    }
+    file_changes.push(change);
+  }
+  for path in &new_paths {
+    let moved_here = file_changes
+      .iter()
+      .any(|change| change.to.as_ref() == Some(path));
+    if !old_paths.contains(path) && !moved_here {
+      file_changes.push(Change::new(ChangeKind::Added, Element::File, path.clone()));
+    }
+  }
+  for (name, from, to) in moves {
+    let element = if new.messages.contains_key(&name) {
+      Element::Message
+    } else if new.enums.contains_key(&name) {
+      Element::Enum
+    } else {
+      Element::Service
+    };
+    let mut change = Change::new(ChangeKind::Moved, element, name);
+    change.to = Some(to);
+    change.details.push(format!("from {}", from));
+    file_changes.push(change);
+  }
+  file_changes
+}
+
+// `json_name` is reported as a JSON name change on its own.
+fn field_options(options: &[ProtoOption]) -> Vec<ProtoOption> {
+  options
+    .iter()
+    .filter(|option| option.name != "json_name")
+    .cloned()
+    .collect()
+}
+
+fn options_changed(old_options: &[ProtoOption], new_options: &[ProtoOption]) -> String {
+  format!(
+    "options changed from {} to {}",
+    format_options(old_options),
+    format_options(new_options)
+  )
+}
+
+fn format_options(options: &[ProtoOption]) -> String {
+  if options.is_empty() {
+    return "none".to_string();
+  }
+  let options: Vec<String> = options
+    .iter()
+    .map(|option| format!("{} = {}", option.name, option.value))
+    .collect();
+  format!("`[{}]`", options.join(", "))
+}
+
+/// One side of a diff: a published version, a package directory or an
+/// artifact file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+  Published { name: String, version: String },
+  Dir(PathBuf),
+  Artifact(PathBuf),
+}
+
+impl Target {
+  /// The parsed protos of the target with its `[compatibility]` settings, the
+  /// defaults for directories without a buff.toml.
+  pub fn load(&self, registry_url: &str) -> (Vec<SourceFile>, Compatibility) {
+    let artifact = match self {
+      Target::Published { name, version } => registry::fetch(registry_url, name, version)
+        .unwrap_or_else(|err| panic!("Failed to fetch {} {}: {}", name, version, err)),
+      Target::Artifact(path) => Artifact::open(path),
+      Target::Dir(dir) => {
+        let manifest_path = dir.join(MANIFEST_FILE_NAME);
+        let compatibility = if manifest_path.is_file() {
+          PackageMetadata::new(manifest_path.to_str().unwrap()).compatibility
+        } else {
+          Compatibility::default()
+        };
+        let files = proto::parse_dir(dir).unwrap_or_else(|err| panic!("Failed to parse {}", err));
+        return (files, compatibility);
+      }
+    };
+    (
+      proto::parse_sources(
+        artifact
+          .proto_sources()
+          .unwrap_or_else(|err| panic!("Failed to read artifact: {}", err)),
+      )
+      .unwrap_or_else(|err| panic!("Failed to parse {}", err)),
+      artifact.metadata().compatibility,
+    )
+  }
+}
+
+impl fmt::Display for Target {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Target::Published { name, version } => write!(f, "{}@{}", name, version),
+      Target::Dir(path) | Target::Artifact(path) => write!(f, "{}", path.display()),
+    }
+  }
+}
+
+/// The changes between two targets, breaking ones marked with the
+/// guarantees the newer one is held to.
+#[derive(Debug, Serialize)]
+pub struct Diff {
+  pub from: String,
+  pub to: String,
+  pub changes: Vec<Change>,
+}
+
+impl Diff {
+  pub fn new(from: &Target, to: &Target, registry_url: &str) -> Self {
+    let (previous, _) = from.load(registry_url);
+    let (current, compatibility) = to.load(registry_url);
+    Diff {
+      from: from.to_string(),
+      to: to.to_string(),
+      changes: diff_with(&previous, &current, &compatibility),
+    }
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).expect("Failed to serialize diff")
+  }
+}
+
+// Paths win over <package>@<version>, a directory can have an
+// `@` in its name.
+impl FromStr for Target {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let path = Path::new(s);
+    if path.is_dir() {
+      return Ok(Target::Dir(path.to_path_buf()));
+    }
+    if path.is_file() {
+      return Ok(Target::Artifact(path.to_path_buf()));
+    }
+    match s.rfind('@') {
+      Some(at) if at > 0 && at < s.len() - 1 => Ok(Target::Published {
+        name: s[..at].to_string(),
+        version: s[at + 1..].to_string(),
+      }),
+      _ => Err(format!(
+        "`{}` is not a directory, an artifact or <package>@<version>",
+        s
+      )),
+    }
+  }
+}
+
+#[test]
+fn should_diff_schemas() {
+  let previous = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { string id = 1; int32 count = 2; string legacy = 3; }
+     enum Status { UNKNOWN = 0; OPEN = 1; }
+     service Orders { rpc Get (Order) returns (Order); rpc Cancel (Order) returns (Order); }",
+  );
+  let current = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { reserved 3; string id = 1 [deprecated = true]; int64 count = 2; string note = 4; }
+     message Page {}
+     enum Status { UNKNOWN = 0; ACTIVE = 1; CLOSED = 2; }
+     service Orders { rpc Get (Order) returns (stream Order); rpc List (Page) returns (Order); }",
+  );
+  let changes: Vec<String> = diff(&previous, &current)
+    .iter()
+    .map(|change| change.to_string())
+    .collect();
+  assert_eq!(
+    changes,
+    vec![
+      "changed rpc acme.v1.Orders.Get: server streaming added",
+      "removed rpc acme.v1.Orders.Cancel",
+      "added rpc acme.v1.Orders.List",
+      "changed field acme.v1.Order.id: options changed from none to `[deprecated = true]`",
+      "changed field acme.v1.Order.count: type changed from `int32` to `int64`",
+      "removed field acme.v1.Order.legacy: number 3 reserved",
+      "added field acme.v1.Order.note: number 4",
+      "added message acme.v1.Page",
+      "changed enum value acme.v1.Status.OPEN: renamed to `ACTIVE`",
+      "added enum value acme.v1.Status.CLOSED: number 2",
+    ]
+  );
+}
+
+#[test]
+fn should_mark_breaking_changes() {
+  let previous = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { string id = 1; int32 count = 2; }
+     service Orders { rpc Get (Order) returns (Order); rpc Cancel (Order) returns (Order); }",
+  );
+  let current = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { string id = 1; int64 count = 2; string note = 3; }
+     service Orders { rpc Get (Order) returns (Order); }",
+  );
+  let breaking = |compatibility: &Compatibility| -> Vec<(String, Vec<&'static str>)> {
+    diff_with(&previous, &current, compatibility)
+      .into_iter()
+      .map(|change| (change.path, change.breaking))
+      .collect()
+  };
+  assert_eq!(
+    breaking(&Compatibility::default()),
+    vec![
+      ("acme.v1.Orders.Cancel".to_string(), vec!["RPC_REMOVED"]),
+      (
+        "acme.v1.Order.count".to_string(),
+        vec!["FIELD_TYPE_CHANGED"]
+      ),
+      ("acme.v1.Order.note".to_string(), vec![]),
+    ]
+  );
+  let wire: Compatibility = toml::from_str("mode = \"wire\"").unwrap();
+  assert_eq!(
+    breaking(&wire),
+    vec![
+      ("acme.v1.Orders.Cancel".to_string(), vec!["RPC_REMOVED"]),
+      ("acme.v1.Order.count".to_string(), vec![]),
+      ("acme.v1.Order.note".to_string(), vec![]),
+    ]
+  );
+}
+
+#[test]
+fn should_detect_renames_and_moves() {
+  let file = |path: &str, source: &str| SourceFile {
+    path: PathBuf::from(path),
+    file: proto::parse(source).unwrap(),
+  };
+  let previous = vec![
+    file(
+      "orders.proto",
+      "syntax = \"proto3\"; package acme.v1;
+       message Order { string id = 1; int32 count = 2; }
+       enum Status { UNKNOWN = 0; OPEN = 1; }",
+    ),
+    file(
+      "users.proto",
+      "syntax = \"proto3\"; package acme.v1; message User { string id = 1; }",
+    ),
+  ];
+  let current = vec![
+    file(
+      "orders.proto",
+      "syntax = \"proto3\"; package acme.v1;
+       message Purchase { string id = 1; int32 count = 2; }",
+    ),
+    file(
+      "status.proto",
+      "syntax = \"proto3\"; package acme.v1; enum Status { UNKNOWN = 0; OPEN = 1; }",
+    ),
+    file(
+      "accounts/users.proto",
+      "syntax = \"proto3\"; package acme.v1; message User { string id = 1; }",
+    ),
+  ];
+  let changes = diff_with(&previous, &current, &Compatibility::default());
+  let described: Vec<String> = changes.iter().map(|change| change.to_string()).collect();
+  assert_eq!(
+    described,
+    vec![
+      "moved file users.proto to accounts/users.proto",
+      "added file status.proto",
+      "moved enum acme.v1.Status to status.proto: from orders.proto",
+      "renamed message acme.v1.Order to acme.v1.Purchase",
+    ]
+  );
+  assert_eq!(changes[3].breaking, vec!["MESSAGE_REMOVED"]);
+}
+
+#[test]
+fn should_parse_diff_targets() {
+  let dir = tempdir::TempDir::new("buff_test").unwrap();
+  assert_eq!(
+    dir.path().to_str().unwrap().parse(),
+    Ok(Target::Dir(dir.path().to_path_buf()))
+  );
+  assert_eq!(
+    "acme.orders@1.2.0".parse(),
+    Ok(Target::Published {
+      name: "acme.orders".to_string(),
+      version: "1.2.0".to_string(),
+    })
+  );
+  assert!("acme.orders".parse::<Target>().is_err());
+  assert!("acme.orders@".parse::<Target>().is_err());
+}
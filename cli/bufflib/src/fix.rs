@@ -0,0 +1,393 @@
+use crate::buff_cli_config::BuffCliConfig;
+use crate::compat::{self, Schema};
+use crate::manifest;
+use crate::package_metadata::PackageMetadata;
+use crate::proto;
+use crate::proto::{qualify, Enum, Message, ParseError, SourceFile, Token};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Field or value numbers and names a message or enum has to reserve because
+/// the previous version used them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reservation {
+  pub file: PathBuf,
+  /// Fully-qualified name of the message or enum.
+  pub path: String,
+  pub numbers: Vec<i32>,
+  pub names: Vec<String>,
+}
+
+impl Reservation {
+  /// The `reserved` statements to add, numbers and names separately since
+  /// one statement can't mix them.
+  fn statements(&self) -> Vec<String> {
+    let mut statements = Vec::new();
+    if !self.numbers.is_empty() {
+      let numbers: Vec<String> = self.numbers.iter().map(i32::to_string).collect();
+      statements.push(format!("reserved {};", numbers.join(", ")));
+    }
+    if !self.names.is_empty() {
+      let names: Vec<String> = self
+        .names
+        .iter()
+        .map(|name| format!("\"{}\"", name))
+        .collect();
+      statements.push(format!("reserved {};", names.join(", ")));
+    }
+    statements
+  }
+}
+
+impl fmt::Display for Reservation {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{}: {} {}",
+      self.file.display(),
+      self.path,
+      self.statements().join(" ")
+    )
+  }
+}
+
+/// The numbers and names of fields and enum values in `previous` that
+/// `current` removed without reserving them. Definitions that were removed
+/// altogether have nothing to reserve them in.
+pub fn plan(previous: &[SourceFile], current: &[SourceFile]) -> Vec<Reservation> {
+  let old = Schema::new(previous);
+  let mut reservations = Vec::new();
+  for source in current {
+    let scope = source.file.package.clone().unwrap_or_default();
+    for message in &source.file.messages {
+      plan_message(&old, &source.path, &scope, message, &mut reservations);
+    }
+    for enumeration in &source.file.enums {
+      plan_enum(&old, &source.path, &scope, enumeration, &mut reservations);
+    }
+  }
+  reservations
+}
+
+fn plan_message(
+  old: &Schema,
+  file: &Path,
+  scope: &str,
+  message: &Message,
+  reservations: &mut Vec<Reservation>,
+) {
+  let name = qualify(scope, &message.name);
+  for nested in &message.messages {
+    plan_message(old, file, &name, nested, reservations);
+  }
+  for enumeration in &message.enums {
+    plan_enum(old, file, &name, enumeration, reservations);
+  }
+  let old_message = match old.messages.get(&name) {
+    Some(old_message) => old_message,
+    None => return,
+  };
+  let mut reservation = Reservation {
+    file: file.to_path_buf(),
+    path: name,
+    numbers: Vec::new(),
+    names: Vec::new(),
+  };
+  for old_field in &old_message.fields {
+    if message
+      .fields
+      .iter()
+      .any(|field| field.number == old_field.number)
+    {
+      continue;
+    }
+    if !message.reserved.contains_number(old_field.number)
+      && !reservation.numbers.contains(&old_field.number)
+    {
+      reservation.numbers.push(old_field.number);
+    }
+    if !message.reserved.contains_name(&old_field.name)
+      && !message
+        .fields
+        .iter()
+        .any(|field| field.name == old_field.name)
+      && !reservation.names.contains(&old_field.name)
+    {
+      reservation.names.push(old_field.name.clone());
+    }
+  }
+  if !reservation.numbers.is_empty() || !reservation.names.is_empty() {
+    reservations.push(reservation);
+  }
+}
+
+fn plan_enum(
+  old: &Schema,
+  file: &Path,
+  scope: &str,
+  enumeration: &Enum,
+  reservations: &mut Vec<Reservation>,
+) {
+  let name = qualify(scope, &enumeration.name);
+  let old_enum = match old.enums.get(&name) {
+    Some(old_enum) => old_enum,
+    None => return,
+  };
+  let mut reservation = Reservation {
+    file: file.to_path_buf(),
+    path: name,
+    numbers: Vec::new(),
+    names: Vec::new(),
+  };
+  for old_value in &old_enum.values {
+    if enumeration
+      .values
+      .iter()
+      .any(|value| value.number == old_value.number)
+    {
+      continue;
+    }
+    if !enumeration.reserved.contains_number(old_value.number)
+      && !reservation.numbers.contains(&old_value.number)
+    {
+      reservation.numbers.push(old_value.number);
+    }
+    if !enumeration.reserved.contains_name(&old_value.name)
+      && !enumeration
+        .values
+        .iter()
+        .any(|value| value.name == old_value.name)
+      && !reservation.names.contains(&old_value.name)
+    {
+      reservation.names.push(old_value.name.clone());
+    }
+  }
+  if !reservation.numbers.is_empty() || !reservation.names.is_empty() {
+    reservations.push(reservation);
+  }
+}
+
+/// Adds the `reserved` statements of `reservations` to `source` right before
+/// the closing brace of each message or enum, on their own lines indented
+/// like the rest of the body, or inline if the brace isn't on a line of its
+/// own. Everything else is left as it was.
+pub fn apply(source: &str, reservations: &[Reservation]) -> Result<String, ParseError> {
+  let braces = closing_braces(&proto::tokenize(source)?);
+  let lines: Vec<&str> = source.split('\n').collect();
+  let mut line_starts = Vec::with_capacity(lines.len());
+  let mut offset = 0;
+  for line in &lines {
+    line_starts.push(offset);
+    offset += line.len() + 1;
+  }
+  let mut edits: Vec<(usize, String)> = Vec::new();
+  for reservation in reservations {
+    let (line, column) = match braces.get(&reservation.path) {
+      Some(position) => *position,
+      None => continue,
+    };
+    let text = lines[line - 1];
+    let brace = text
+      .char_indices()
+      .nth(column - 1)
+      .map_or(text.len(), |(i, _)| i);
+    let before = &text[..brace];
+    let statements = reservation.statements();
+    if before.trim().is_empty() {
+      let indent = body_indent(&lines[..line - 1], before);
+      let inserted: String = statements
+        .iter()
+        .map(|statement| format!("{}{}\n", indent, statement))
+        .collect();
+      edits.push((line_starts[line - 1], inserted));
+    } else {
+      let separator = if before.ends_with(char::is_whitespace) {
+        ""
+      } else {
+        " "
+      };
+      edits.push((
+        line_starts[line - 1] + brace,
+        format!("{}{} ", separator, statements.join(" ")),
+      ));
+    }
+  }
+  edits.sort_by(|a, b| b.0.cmp(&a.0));
+  let mut fixed = source.to_string();
+  for (offset, text) in edits {
+    fixed.insert_str(offset, &text);
+  }
+  Ok(fixed)
+}
+
+/// The indentation of the last non-blank line of a body, if it's deeper than
+/// the closing brace's, or else the brace's plus two spaces.
+fn body_indent(before_brace: &[&str], brace_indent: &str) -> String {
+  let last = before_brace
+    .iter()
+    .rev()
+    .find(|line| !line.trim().is_empty());
+  if let Some(line) = last {
+    let indent = &line[..line.len() - line.trim_start().len()];
+    if indent.len() > brace_indent.len() && indent.starts_with(brace_indent) {
+      return indent.to_string();
+    }
+  }
+  format!("{}  ", brace_indent)
+}
+
+/// The line and column of the closing brace of each message and enum, by
+/// fully-qualified name.
+fn closing_braces(tokens: &[(Token, usize, usize)]) -> BTreeMap<String, (usize, usize)> {
+  let mut braces = BTreeMap::new();
+  let mut package = String::new();
+  // Other blocks, e.g. services, oneofs and option values, are
+  // kept as `None` to match their braces.
+  let mut scopes: Vec<Option<String>> = Vec::new();
+  let mut i = 0;
+  while i < tokens.len() {
+    match (&tokens[i].0, tokens.get(i + 1), tokens.get(i + 2)) {
+      (Token::Word(keyword), Some((Token::Word(name), _, _)), _)
+        if keyword == "package" && scopes.is_empty() =>
+      {
+        package = name.clone();
+        i += 2;
+        continue;
+      }
+      (Token::Word(keyword), Some((Token::Word(name), _, _)), Some((Token::Symbol('{'), _, _)))
+        if keyword == "message" || keyword == "enum" =>
+      {
+        let scope = scopes
+          .iter()
+          .rev()
+          .filter_map(Option::as_ref)
+          .next()
+          .unwrap_or(&package);
+        scopes.push(Some(qualify(scope, name)));
+        i += 3;
+        continue;
+      }
+      (Token::Symbol('{'), _, _) => scopes.push(None),
+      (Token::Symbol('}'), _, _) => {
+        if let Some(Some(path)) = scopes.pop() {
+          braces.insert(path, (tokens[i].1, tokens[i].2));
+        }
+      }
+      _ => (),
+    }
+    i += 1;
+  }
+  braces
+}
+
+/// Adds `reserved` statements to the package at `manifest_path` for the
+/// fields and enum values removed since the latest published version, or
+/// with `check` only works out which ones are missing. Returns `None` if
+/// the package hasn't been published.
+pub fn fix_package(manifest_path: &Path, check: bool) -> Option<Vec<Reservation>> {
+  let metadata = PackageMetadata::new(manifest_path.to_str().unwrap());
+  let registry_url = BuffCliConfig::new().preferred_registry;
+  let (_, previous) = compat::published_baselines(&registry_url, &metadata.package.name, false)
+    .unwrap_or_else(|err| panic!("Failed to check `{}`: {}", metadata.package.name, err))
+    .pop()?;
+  let package_dir = manifest::package_dir(manifest_path);
+  let current =
+    proto::parse_dir(&package_dir).unwrap_or_else(|err| panic!("Failed to parse {}", err));
+  let reservations = plan(&previous, &current);
+  if !check {
+    let mut files: Vec<&PathBuf> = reservations
+      .iter()
+      .map(|reservation| &reservation.file)
+      .collect();
+    files.dedup();
+    for file in files {
+      let path = package_dir.join(file);
+      let source = fs::read_to_string(&path).expect("Failed to read proto file");
+      let in_file: Vec<Reservation> = reservations
+        .iter()
+        .filter(|reservation| reservation.file == *file)
+        .cloned()
+        .collect();
+      let fixed = apply(&source, &in_file)
+        .unwrap_or_else(|err| panic!("Failed to parse {}", err.in_file(file)));
+      fs::write(&path, fixed).expect("Failed to write proto file");
+    }
+  }
+  Some(reservations)
+}
+
+#[test]
+fn should_reserve_removed_fields_and_values() {
+  let file = |source: &str| SourceFile {
+    path: PathBuf::from("orders.proto"),
+    file: proto::parse(source).unwrap(),
+  };
+  let previous = vec![file(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order {
+       string id = 1;
+       string legacy_id = 2;
+       message Item { string sku = 1; int32 count = 2; }
+       int32 total = 3;
+     }
+     enum Status { UNKNOWN = 0; OPEN = 1; CLOSED = 2; }",
+  )];
+  let current_source = "syntax = \"proto3\";
+package acme.v1;
+
+// Orders placed by customers.
+message Order {
+  string id = 1;
+  reserved 3;
+  message Item { string sku = 1; }
+
+  // Replaces legacy_id.
+  string external_id = 4;
+}
+
+enum Status {
+    UNKNOWN = 0;
+    OPEN = 1; // still used
+}
+";
+  let current = vec![file(current_source)];
+  let reservations = plan(&previous, &current);
+  let described: Vec<String> = reservations.iter().map(|r| r.to_string()).collect();
+  assert_eq!(
+    described,
+    vec![
+      "orders.proto: acme.v1.Order.Item reserved 2; reserved \"count\";",
+      "orders.proto: acme.v1.Order reserved 2; reserved \"legacy_id\", \"total\";",
+      "orders.proto: acme.v1.Status reserved 2; reserved \"CLOSED\";",
+    ]
+  );
+  assert_eq!(
+    apply(current_source, &reservations).unwrap(),
+    "syntax = \"proto3\";
+package acme.v1;
+
+// Orders placed by customers.
+message Order {
+  string id = 1;
+  reserved 3;
+  message Item { string sku = 1; reserved 2; reserved \"count\"; }
+
+  // Replaces legacy_id.
+  string external_id = 4;
+  reserved 2;
+  reserved \"legacy_id\", \"total\";
+}
+
+enum Status {
+    UNKNOWN = 0;
+    OPEN = 1; // still used
+    reserved 2;
+    reserved \"CLOSED\";
+}
+"
+  );
+  let fixed = vec![file(&apply(current_source, &reservations).unwrap())];
+  assert_eq!(plan(&previous, &fixed), vec![]);
+}
@@ -0,0 +1,826 @@
+use crate::manifest;
+use crate::package_metadata::PackageMetadata;
+use crate::proto::{self, Comment, ParseError, Span, Token};
+use ignore::Walk;
+use serde_derive::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const INDENT: &str = "  ";
+/// How many unchanged lines diffs show around each change.
+const CONTEXT: usize = 3;
+
+/// The `[fmt]` section of buff.toml.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FmtConfig {
+  /// Line up the `=` of consecutive fields and enum values.
+  pub align_numbers: bool,
+}
+
+/// A comment or a blank line between statements.
+#[derive(Debug, Clone)]
+enum Trivia {
+  Blank,
+  Comment(Comment),
+}
+
+/// A token as written, or a comment in the middle of a statement.
+#[derive(Debug, Clone)]
+enum Part {
+  Token(Token, String),
+  Comment(Comment),
+}
+
+#[derive(Debug, Clone)]
+struct Statement {
+  /// The comments and blank lines since the previous statement.
+  leading: Vec<Trivia>,
+  /// Everything up to the `;`, or the `{` opening the body.
+  parts: Vec<Part>,
+  /// The comment following the `;` or `{` on the same line.
+  trailing: Option<Comment>,
+  body: Option<Body>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Body {
+  statements: Vec<Statement>,
+  /// The comments and blank lines before the closing `}`, or the end of the
+  /// file.
+  closing: Vec<Trivia>,
+  /// The comment following the `}` on the same line.
+  trailing: Option<Comment>,
+}
+
+impl Statement {
+  fn words(&self) -> impl Iterator<Item = &str> {
+    self.parts.iter().filter_map(|part| match part {
+      Part::Token(_, text) => Some(text.as_str()),
+      Part::Comment(_) => None,
+    })
+  }
+
+  fn keyword(&self) -> &str {
+    self.words().next().unwrap_or_default()
+  }
+
+  /// What imports and options are sorted by: the imported path or the
+  /// option's name.
+  fn sort_key(&self) -> String {
+    match self.keyword() {
+      "import" => self
+        .parts
+        .iter()
+        .find_map(|part| match part {
+          Part::Token(Token::Str(path), _) => Some(path.clone()),
+          _ => None,
+        })
+        .unwrap_or_default(),
+      "option" => self
+        .words()
+        .skip(1)
+        .take_while(|word| *word != "=")
+        .collect(),
+      _ => String::new(),
+    }
+  }
+
+  /// Fields and enum values, whose numbers can be lined up.
+  fn is_numbered(&self) -> bool {
+    let keywords = [
+      "syntax",
+      "edition",
+      "package",
+      "import",
+      "option",
+      "reserved",
+      "extensions",
+    ];
+    self.body.is_none()
+      && !keywords.contains(&self.keyword())
+      && self.words().any(|word| word == "=")
+      && self
+        .parts
+        .iter()
+        .all(|part| matches!(part, Part::Token(_, _)))
+  }
+
+  fn separated(&self) -> bool {
+    matches!(self.leading.first(), Some(Trivia::Blank))
+  }
+}
+
+enum Item {
+  Token(Token, Span),
+  Comment(Comment),
+}
+
+/// Splits a file into statements, keeping every comment and where blank
+/// lines were.
+struct Reader {
+  items: Vec<Item>,
+  position: usize,
+  chars: Vec<char>,
+  /// Where each line starts in `chars`.
+  line_starts: Vec<usize>,
+  /// The line the last item read ends on.
+  line: usize,
+}
+
+impl Reader {
+  fn new(source: &str) -> Result<Self, ParseError> {
+    let (tokens, comments) = proto::tokens_and_comments(source)?;
+    let mut items: Vec<Item> = tokens
+      .into_iter()
+      .map(|(token, span)| Item::Token(token, span))
+      .chain(comments.into_iter().map(Item::Comment))
+      .collect();
+    items.sort_by_key(|item| match item {
+      Item::Token(_, span) => span.start,
+      Item::Comment(comment) => comment.span.start,
+    });
+    let chars: Vec<char> = source.chars().collect();
+    let mut line_starts = vec![0];
+    for (i, c) in chars.iter().enumerate() {
+      if *c == '\n' {
+        line_starts.push(i + 1);
+      }
+    }
+    Ok(Reader {
+      items,
+      position: 0,
+      chars,
+      line_starts,
+      line: 0,
+    })
+  }
+
+  fn text(&self, span: Span) -> String {
+    let start = self.line_starts[span.start.line - 1] + span.start.column - 1;
+    let end = self.line_starts[span.end.line - 1] + span.end.column - 1;
+    self.chars[start..end].iter().collect()
+  }
+
+  fn peek_token(&self) -> Option<&Token> {
+    match self.items.get(self.position) {
+      Some(Item::Token(token, _)) => Some(token),
+      _ => None,
+    }
+  }
+
+  fn next_token(&mut self) -> Option<(Token, String)> {
+    match self.items.get(self.position) {
+      Some(Item::Token(token, span)) => {
+        let read = (token.clone(), self.text(*span));
+        self.line = span.end.line;
+        self.position += 1;
+        Some(read)
+      }
+      _ => None,
+    }
+  }
+
+  /// The comments before the next token, with a blank for every gap of one
+  /// or more empty lines.
+  fn trivia(&mut self) -> Vec<Trivia> {
+    let mut trivia = Vec::new();
+    while let Some(Item::Comment(comment)) = self.items.get(self.position) {
+      if comment.span.start.line > self.line + 1 {
+        trivia.push(Trivia::Blank);
+      }
+      self.line = comment.span.end.line;
+      trivia.push(Trivia::Comment(comment.clone()));
+      self.position += 1;
+    }
+    if let Some(Item::Token(_, span)) = self.items.get(self.position) {
+      if span.start.line > self.line + 1 {
+        trivia.push(Trivia::Blank);
+      }
+    }
+    trivia
+  }
+
+  /// A comment starting on the line the last token ended on.
+  fn trailing(&mut self) -> Option<Comment> {
+    match self.items.get(self.position) {
+      Some(Item::Comment(comment)) if comment.span.start.line == self.line => {
+        let comment = comment.clone();
+        self.line = comment.span.end.line;
+        self.position += 1;
+        Some(comment)
+      }
+      _ => None,
+    }
+  }
+
+  /// Reads statements up to the `}` closing the body, or the end of the
+  /// file. Empty statements are dropped.
+  fn body(&mut self) -> Body {
+    let mut body = Body::default();
+    let mut leading = Vec::new();
+    loop {
+      leading.extend(self.trivia());
+      match self.peek_token() {
+        None => break,
+        Some(Token::Symbol('}')) => {
+          self.next_token();
+          if self.peek_token() == Some(&Token::Symbol(';')) {
+            self.next_token();
+          }
+          body.trailing = self.trailing();
+          break;
+        }
+        Some(Token::Symbol(';')) => {
+          self.next_token();
+        }
+        Some(_) => {
+          let statement = self.statement(leading);
+          leading = Vec::new();
+          body.statements.push(statement);
+        }
+      }
+    }
+    body.closing = leading;
+    body
+  }
+
+  fn statement(&mut self, leading: Vec<Trivia>) -> Statement {
+    let mut statement = Statement {
+      leading,
+      parts: Vec::new(),
+      trailing: None,
+      body: None,
+    };
+    // Braces inside brackets or after `option` are aggregate
+    // values rather than bodies.
+    let mut depth = 0;
+    loop {
+      if let Some(Item::Comment(comment)) = self.items.get(self.position) {
+        let comment = comment.clone();
+        self.line = comment.span.end.line;
+        self.position += 1;
+        statement.parts.push(Part::Comment(comment));
+        continue;
+      }
+      let (token, text) = match self.next_token() {
+        Some(next) => next,
+        None => return statement,
+      };
+      match token {
+        Token::Symbol(';') if depth == 0 => {
+          statement.trailing = self.trailing();
+          return statement;
+        }
+        Token::Symbol('{') if depth == 0 && statement.keyword() != "option" => {
+          statement.trailing = self.trailing();
+          statement.body = Some(self.body());
+          return statement;
+        }
+        Token::Symbol('(') | Token::Symbol('[') | Token::Symbol('{') => depth += 1,
+        Token::Symbol(')') | Token::Symbol(']') | Token::Symbol('}') => depth -= 1,
+        _ => (),
+      }
+      statement.parts.push(Part::Token(token, text));
+    }
+  }
+}
+
+/// Where a top-level statement goes: `syntax`, then `package`, imports,
+/// options and definitions.
+fn top_level_rank(statement: &Statement) -> u8 {
+  match statement.keyword() {
+    "syntax" | "edition" => 0,
+    "package" => 1,
+    "import" => 2,
+    "option" => 3,
+    _ => 4,
+  }
+}
+
+fn is_exponent(word: &str) -> bool {
+  word.starts_with(|c: char| c.is_ascii_digit()) && word.ends_with(&['e', 'E'][..])
+}
+
+fn space_between(previous: &str, next: &str, rpc_name: bool) -> bool {
+  match next {
+    ";" | "," | ")" | "]" | ">" | ":" => return false,
+    "<" if previous == "map" => return false,
+    "(" if rpc_name => return false,
+    "}" if previous == "{" => return false,
+    "-" | "+" if is_exponent(previous) => return false,
+    _ if next.starts_with('.') && previous == ")" => return false,
+    _ => (),
+  }
+  !matches!(previous, "(" | "[" | "<" | "-" | "+")
+}
+
+/// Re-indents a comment, moving the lines of a block comment along with its
+/// first one.
+fn comment_text(comment: &Comment, indent: &str) -> String {
+  let original = comment.span.start.column - 1;
+  let mut lines = comment.text.split('\n');
+  let mut text = format!("{}{}", indent, lines.next().unwrap_or_default().trim_end());
+  for line in lines {
+    let line = line.trim_end();
+    text.push('\n');
+    if !line.is_empty() {
+      let whitespace = line.len() - line.trim_start().len();
+      text.push_str(indent);
+      text.push_str(&line[whitespace.min(original)..]);
+    }
+  }
+  text
+}
+
+struct Printer<'a> {
+  config: &'a FmtConfig,
+  out: String,
+}
+
+impl<'a> Printer<'a> {
+  fn file(&mut self, mut body: Body) {
+    // Comments at the top of the file set apart by a blank line,
+    // e.g. a license, stay on top whatever moves up above the first statement.
+    let mut header = Vec::new();
+    if let Some(first) = body.statements.first_mut() {
+      if let Some(blank) = first
+        .leading
+        .iter()
+        .rposition(|trivia| matches!(trivia, Trivia::Blank))
+      {
+        header = first.leading.drain(..blank).collect();
+      }
+    }
+    self.closing(&header, 0, false);
+    // Sorting is stable, so definitions keep their order.
+    body.statements.sort_by(|a, b| {
+      let rank = top_level_rank(a).cmp(&top_level_rank(b));
+      rank.then_with(|| a.sort_key().cmp(&b.sort_key()))
+    });
+    let mut previous: Option<u8> = None;
+    for statement in &body.statements {
+      let rank = top_level_rank(statement);
+      let separated = match previous {
+        None => !header.is_empty(),
+        Some(previous) => previous != rank || rank == 4,
+      };
+      self.statement(statement, 0, separated, None);
+      previous = Some(rank);
+    }
+    self.closing(&body.closing, 0, !body.statements.is_empty());
+    while self.out.ends_with("\n\n") {
+      self.out.pop();
+    }
+    if !self.out.is_empty() && !self.out.ends_with('\n') {
+      self.out.push('\n');
+    }
+  }
+
+  fn body(&mut self, mut body: Body, level: usize) {
+    body.statements.sort_by(|a, b| {
+      let a_option = a.keyword() == "option";
+      let b_option = b.keyword() == "option";
+      b_option
+        .cmp(&a_option)
+        .then_with(|| match (a_option, b_option) {
+          (true, true) => a.sort_key().cmp(&b.sort_key()),
+          _ => std::cmp::Ordering::Equal,
+        })
+    });
+    let widths = self.alignment(&body.statements);
+    for (i, statement) in body.statements.iter().enumerate() {
+      self.statement(statement, level, i > 0 && statement.separated(), widths[i]);
+    }
+    self.closing(&body.closing, level, !body.statements.is_empty());
+  }
+
+  /// How wide to pad the part before `=` of each statement, when lining up
+  /// the numbers of runs of fields or enum values not broken up by blank
+  /// lines or other statements.
+  fn alignment(&self, statements: &[Statement]) -> Vec<Option<usize>> {
+    let mut widths = vec![None; statements.len()];
+    if !self.config.align_numbers {
+      return widths;
+    }
+    let mut run: Vec<(usize, usize)> = Vec::new();
+    let mut runs = Vec::new();
+    for (i, statement) in statements.iter().enumerate() {
+      if !statement.is_numbered() || statement.separated() {
+        runs.push(std::mem::take(&mut run));
+      }
+      if statement.is_numbered() {
+        let line = self.line(statement, 0);
+        let width = line
+          .find(" = ")
+          .map_or(0, |end| line[..end].chars().count());
+        run.push((i, width));
+      }
+    }
+    runs.push(run);
+    for run in runs {
+      let widest = run.iter().map(|(_, width)| *width).max();
+      for (i, _) in run {
+        widths[i] = widest;
+      }
+    }
+    widths
+  }
+
+  /// The statement's tokens on one line, or more if comments in the middle
+  /// of it end lines.
+  fn line(&self, statement: &Statement, level: usize) -> String {
+    let continuation = INDENT.repeat(level + 2);
+    let mut line = String::new();
+    let mut previous: Option<&str> = None;
+    let mut tokens = 0;
+    let mut first = "";
+    for part in &statement.parts {
+      match part {
+        Part::Token(_, text) => {
+          let rpc_name = first == "rpc" && tokens == 2;
+          if let Some(previous) = previous {
+            if !line.ends_with('\n') && space_between(previous, text, rpc_name) {
+              line.push(' ');
+            }
+          }
+          if line.ends_with('\n') {
+            line.push_str(&continuation);
+          }
+          line.push_str(text);
+          if tokens == 0 {
+            first = text;
+          }
+          tokens += 1;
+          previous = Some(text);
+        }
+        Part::Comment(comment) => {
+          if line.ends_with('\n') {
+            line.push_str(&comment_text(comment, &continuation));
+          } else {
+            if !line.is_empty() {
+              line.push(' ');
+            }
+            line.push_str(comment.text.trim_end());
+          }
+          if comment.text.starts_with("//") {
+            line.push('\n');
+          } else {
+            previous = Some("");
+          }
+        }
+      }
+    }
+    while line.ends_with('\n') {
+      line.pop();
+    }
+    line
+  }
+
+  fn trivia(&mut self, trivia: &[Trivia], level: usize) {
+    let indent = INDENT.repeat(level);
+    for item in trivia {
+      match item {
+        Trivia::Blank => self.out.push('\n'),
+        Trivia::Comment(comment) => {
+          self.out.push_str(&comment_text(comment, &indent));
+          self.out.push('\n');
+        }
+      }
+    }
+  }
+
+  fn statement(
+    &mut self,
+    statement: &Statement,
+    level: usize,
+    separated: bool,
+    width: Option<usize>,
+  ) {
+    let indent = INDENT.repeat(level);
+    if separated {
+      self.out.push('\n');
+    }
+    let leading = match statement.leading.first() {
+      Some(Trivia::Blank) => &statement.leading[1..],
+      _ => &statement.leading[..],
+    };
+    self.trivia(leading, level);
+    let mut line = self.line(statement, level);
+    if let (Some(width), Some(end)) = (width, line.find(" = ")) {
+      let padding = width.saturating_sub(line[..end].chars().count());
+      line.insert_str(end, &" ".repeat(padding));
+    }
+    self.out.push_str(&indent);
+    self.out.push_str(&line);
+    let body = match statement.body {
+      None => {
+        self.out.push(';');
+        self.trailing(&statement.trailing);
+        return;
+      }
+      Some(ref body) => body,
+    };
+    if body.statements.is_empty() && body.closing.is_empty() && statement.trailing.is_none() {
+      self.out.push_str(" {}");
+      self.trailing(&body.trailing);
+      return;
+    }
+    self.out.push_str(" {");
+    self.trailing(&statement.trailing);
+    self.body(body.clone(), level + 1);
+    self.out.push_str(&indent);
+    self.out.push('}');
+    self.trailing(&body.trailing);
+  }
+
+  fn trailing(&mut self, comment: &Option<Comment>) {
+    if let Some(comment) = comment {
+      self.out.push(' ');
+      self.out.push_str(comment.text.trim_end());
+    }
+    self.out.push('\n');
+  }
+
+  /// The comments at the end of a body, keeping a blank line between them
+  /// and the statements above but none before the `}`.
+  fn closing(&mut self, closing: &[Trivia], level: usize, after_statements: bool) {
+    let mut closing = closing;
+    if !after_statements {
+      while let Some(Trivia::Blank) = closing.first() {
+        closing = &closing[1..];
+      }
+    }
+    while let Some(Trivia::Blank) = closing.last() {
+      closing = &closing[..closing.len() - 1];
+    }
+    self.trivia(closing, level);
+  }
+}
+
+/// Lays `source` out canonically: two-space indentation, one statement per
+/// line, `syntax`, `package`, sorted imports and sorted options at the top
+/// of the file, options first in every body, and at most one blank line in
+/// a row, with one between top-level definitions. Every comment is kept.
+pub fn format(source: &str, config: &FmtConfig) -> Result<String, ParseError> {
+  proto::parse(source)?;
+  let body = Reader::new(source)?.body();
+  let mut printer = Printer {
+    config,
+    out: String::new(),
+  };
+  printer.file(body);
+  Ok(printer.out)
+}
+
+/// A .proto file that isn't laid out canonically.
+#[derive(Debug)]
+pub struct Unformatted {
+  /// The path relative to the package root.
+  pub path: PathBuf,
+  pub original: String,
+  pub formatted: String,
+}
+
+impl Unformatted {
+  /// The changes formatting makes, as a unified diff.
+  pub fn diff(&self) -> String {
+    unified_diff(&self.path, &self.original, &self.formatted)
+  }
+}
+
+/// Formats every .proto file in the package at `manifest_path`, or with
+/// `check` only works out which ones aren't formatted.
+pub fn format_package(manifest_path: &Path, check: bool) -> Result<Vec<Unformatted>, ParseError> {
+  let metadata = PackageMetadata::new(manifest_path.to_str().unwrap());
+  let package_dir = manifest::package_dir(manifest_path);
+  let mut paths = Vec::new();
+  for entry in Walk::new(&package_dir) {
+    let path = entry.unwrap().into_path();
+    if path.is_file() && path.extension().map_or(false, |ext| ext == "proto") {
+      paths.push(path);
+    }
+  }
+  paths.sort();
+  let mut unformatted = Vec::new();
+  for path in paths {
+    let relative = path.strip_prefix(&package_dir).unwrap().to_path_buf();
+    let original = fs::read_to_string(&path).expect("Failed to read proto file");
+    let formatted = format(&original, &metadata.fmt).map_err(|err| err.in_file(&relative))?;
+    if formatted == original {
+      continue;
+    }
+    if !check {
+      fs::write(&path, &formatted).expect("Failed to write proto file");
+    }
+    unformatted.push(Unformatted {
+      path: relative,
+      original,
+      formatted,
+    });
+  }
+  Ok(unformatted)
+}
+
+/// The line-by-line difference between `original` and `changed`, with a few
+/// lines of context around every change.
+pub fn unified_diff(path: &Path, original: &str, changed: &str) -> String {
+  let old: Vec<&str> = original.lines().collect();
+  let new: Vec<&str> = changed.lines().collect();
+  // `common[i][j]` is the length of the longest common
+  // subsequence of `old[i..]` and `new[j..]`.
+  let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+  for i in (0..old.len()).rev() {
+    for j in (0..new.len()).rev() {
+      common[i][j] = if old[i] == new[j] {
+        common[i + 1][j + 1] + 1
+      } else {
+        common[i + 1][j].max(common[i][j + 1])
+      };
+    }
+  }
+  let mut lines: Vec<(char, &str)> = Vec::new();
+  let (mut i, mut j) = (0, 0);
+  while i < old.len() || j < new.len() {
+    if i < old.len() && j < new.len() && old[i] == new[j] {
+      lines.push((' ', old[i]));
+      i += 1;
+      j += 1;
+    } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+      lines.push(('-', old[i]));
+      i += 1;
+    } else {
+      lines.push(('+', new[j]));
+      j += 1;
+    }
+  }
+  let changes: Vec<usize> = (0..lines.len()).filter(|&k| lines[k].0 != ' ').collect();
+  let mut diff = format!("--- a/{0}\n+++ b/{0}\n", path.display());
+  let mut k = 0;
+  while k < changes.len() {
+    let start = changes[k].saturating_sub(CONTEXT);
+    while k + 1 < changes.len() && changes[k + 1] - changes[k] <= 2 * CONTEXT {
+      k += 1;
+    }
+    let end = (changes[k] + CONTEXT + 1).min(lines.len());
+    k += 1;
+    let count =
+      |lines: &[(char, &str)], skip: char| lines.iter().filter(|(op, _)| *op != skip).count();
+    let (old_before, new_before) = (count(&lines[..start], '+'), count(&lines[..start], '-'));
+    let (old_count, new_count) = (
+      count(&lines[start..end], '+'),
+      count(&lines[start..end], '-'),
+    );
+    let first = |before: usize, count: usize| if count == 0 { before } else { before + 1 };
+    diff.push_str(&format!(
+      "@@ -{},{} +{},{} @@\n",
+      first(old_before, old_count),
+      old_count,
+      first(new_before, new_count),
+      new_count
+    ));
+    for (op, line) in &lines[start..end] {
+      diff.push(*op);
+      diff.push_str(line);
+      diff.push('\n');
+    }
+  }
+  diff
+}
+
+#[cfg(test)]
+fn definitions(source: &str) -> Vec<proto::SourceFile> {
+  let mut file = proto::parse(source).unwrap();
+  file.imports.sort_by(|a, b| a.path.cmp(&b.path));
+  file.options.sort_by(|a, b| a.name.cmp(&b.name));
+  vec![proto::SourceFile {
+    path: PathBuf::from("orders.proto"),
+    file,
+  }]
+}
+
+#[test]
+fn should_format_canonically() {
+  let source = r#"// Copyright Acme.
+
+package acme.orders.v1;
+syntax="proto3";
+import "google/protobuf/timestamp.proto";
+import public   "acme/common/money.proto";
+option java_package="com.acme.orders";
+option go_package = "acme/orders";
+
+
+/* Orders placed
+ * by customers. */
+message Order{
+    string id=1;// the id
+  repeated   Item items = 2 [deprecated=true];
+
+  map<string,acme.common.Money>totals=3;
+  option (acme.resource) = { type: "order" };
+  oneof payment {
+     string card = 4;
+     // A voucher code.
+     string voucher_code = 15;
+  }
+  reserved 5 , 6 to 9;
+  message Item { string sku = 1; }
+}
+enum Status { STATUS_UNKNOWN = 0; STATUS_OPEN = 1 ; };
+service Orders {
+  rpc Get ( GetRequest ) returns ( Order ) {}
+  rpc Watch(stream GetRequest) returns (stream Order) { option deprecated = true; } // watch
+}
+// trailing file comment
+"#;
+  let expected = r#"// Copyright Acme.
+
+syntax = "proto3";
+
+package acme.orders.v1;
+
+import public "acme/common/money.proto";
+import "google/protobuf/timestamp.proto";
+
+option go_package = "acme/orders";
+option java_package = "com.acme.orders";
+
+/* Orders placed
+ * by customers. */
+message Order {
+  option (acme.resource) = { type: "order" };
+  string id = 1; // the id
+  repeated Item items = 2 [deprecated = true];
+
+  map<string, acme.common.Money> totals = 3;
+  oneof payment {
+    string card = 4;
+    // A voucher code.
+    string voucher_code = 15;
+  }
+  reserved 5, 6 to 9;
+  message Item {
+    string sku = 1;
+  }
+}
+
+enum Status {
+  STATUS_UNKNOWN = 0;
+  STATUS_OPEN = 1;
+}
+
+service Orders {
+  rpc Get(GetRequest) returns (Order) {}
+  rpc Watch(stream GetRequest) returns (stream Order) {
+    option deprecated = true;
+  } // watch
+}
+// trailing file comment
+"#;
+  let formatted = format(source, &FmtConfig::default()).unwrap();
+  assert_eq!(formatted, expected);
+  assert_eq!(
+    format(&formatted, &FmtConfig::default()).unwrap(),
+    formatted
+  );
+  assert!(proto::same_definitions(
+    &definitions(&formatted),
+    &definitions(source)
+  ));
+
+  let aligned = format(
+    "syntax = \"proto3\";\nmessage A {\n  string id = 1;\n  repeated int64 counts = 2;\n\n  bool ok = 3;\n}\n",
+    &FmtConfig { align_numbers: true },
+  )
+  .unwrap();
+  assert_eq!(
+    aligned,
+    "syntax = \"proto3\";\n\nmessage A {\n  string id             = 1;\n  repeated int64 counts = 2;\n\n  bool ok = 3;\n}\n"
+  );
+  assert_eq!(
+    format(
+      &aligned,
+      &FmtConfig {
+        align_numbers: true
+      }
+    )
+    .unwrap(),
+    aligned
+  );
+  assert!(format("message A {", &FmtConfig::default()).is_err());
+}
+
+#[test]
+fn should_render_unified_diffs() {
+  let original = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+  let changed = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+  assert_eq!(
+    unified_diff(Path::new("x.proto"), original, changed),
+    "--- a/x.proto\n+++ b/x.proto\n@@ -1,5 +1,5 @@\n a\n-b\n+B\n c\n d\n e\n@@ -8,3 +8,4 @@\n h\n i\n j\n+k\n"
+  );
+  assert_eq!(
+    unified_diff(Path::new("x.proto"), original, original),
+    "--- a/x.proto\n+++ b/x.proto\n"
+  );
+}
@@ -0,0 +1,388 @@
+use git2::{ObjectType, Repository, Status, StatusOptions, TreeWalkMode, TreeWalkResult};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// The state of the git repository a package lives in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GitState {
+  pub commit: String,
+  /// `None` when HEAD is detached.
+  pub branch: Option<String>,
+  /// Whether files in the package directory differ from the commit.
+  pub dirty: bool,
+  /// URL of the `origin` remote.
+  pub remote_url: Option<String>,
+}
+
+/// Describes the repository containing `package_dir`, or `None` if it isn't
+/// in one or the repository has no commits yet.
+pub fn state(package_dir: &Path) -> Option<GitState> {
+  let repo = Repository::discover(package_dir).ok()?;
+  let head = repo.head().ok()?;
+  let commit = head.peel_to_commit().ok()?.id().to_string();
+  let branch = if head.is_branch() {
+    head.shorthand().map(String::from)
+  } else {
+    None
+  };
+  let remote_url = repo
+    .find_remote("origin")
+    .ok()
+    .and_then(|remote| remote.url().map(String::from));
+  Some(GitState {
+    commit,
+    branch,
+    dirty: !changes_in(&repo, package_dir).is_empty(),
+    remote_url,
+  })
+}
+
+/// The git `user.email` of whoever is running buff.
+pub fn user_email(package_dir: &Path) -> Option<String> {
+  let repo = Repository::discover(package_dir).ok()?;
+  let config = repo.config().ok()?;
+  config.get_string("user.email").ok()
+}
+
+/// Files that would go into the artifact of the package in `package_dir`
+/// but differ from the current commit, whether modified, staged, deleted or
+/// untracked, relative to `package_dir`. Empty when the package isn't in a
+/// git repository.
+pub fn uncommitted_changes(package_dir: &Path) -> Vec<PathBuf> {
+  match Repository::discover(package_dir) {
+    Ok(repo) => changes_in(&repo, package_dir),
+    Err(_) => Vec::new(),
+  }
+}
+
+fn changes_in(repo: &Repository, package_dir: &Path) -> Vec<PathBuf> {
+  let (workdir, package_dir) = match (
+    repo.workdir().and_then(|dir| dir.canonicalize().ok()),
+    package_dir.canonicalize().ok(),
+  ) {
+    (Some(workdir), Some(package_dir)) => (workdir, package_dir),
+    _ => return Vec::new(),
+  };
+  let mut options = StatusOptions::new();
+  options
+    .include_untracked(true)
+    .recurse_untracked_dirs(true)
+    .include_ignored(false);
+  if let Some(pathspec) = pathspec(&workdir, &package_dir) {
+    options.pathspec(pathspec);
+  }
+  let statuses = match repo.statuses(Some(&mut options)) {
+    Ok(statuses) => statuses,
+    Err(_) => return Vec::new(),
+  };
+  // Files buff leaves out of the artifact, e.g. through .ignore,
+  // don't count even when git doesn't ignore them.
+  let published: HashSet<PathBuf> = ignore::Walk::new(&package_dir)
+    .filter_map(Result::ok)
+    .map(|entry| entry.path().to_path_buf())
+    .collect();
+  let mut changes: Vec<PathBuf> = statuses
+    .iter()
+    .filter_map(|entry| {
+      let status = entry.status();
+      if status == Status::CURRENT || status.contains(Status::IGNORED) {
+        return None;
+      }
+      let path = workdir.join(entry.path()?);
+      let deleted = status.intersects(Status::WT_DELETED | Status::INDEX_DELETED);
+      if deleted || published.contains(&path) {
+        path.strip_prefix(&package_dir).ok().map(Path::to_path_buf)
+      } else {
+        None
+      }
+    })
+    .collect();
+  changes.sort();
+  changes
+}
+
+/// Checks that HEAD is on a branch of the remote `repository_url` points
+/// at, as of the last fetch, so the published sources can be found there.
+pub fn verify_pushed(package_dir: &Path, repository_url: &str) -> Result<(), String> {
+  let repo = Repository::discover(package_dir)
+    .map_err(|_| "the package isn't in a git repository".to_string())?;
+  let head = repo
+    .head()
+    .and_then(|head| head.peel_to_commit())
+    .map_err(|_| "the repository has no commits".to_string())?
+    .id();
+  let remotes = repo.remotes().map_err(|err| err.message().to_string())?;
+  let remote = remotes
+    .iter()
+    .filter_map(|name| name)
+    .find(|name| {
+      repo
+        .find_remote(name)
+        .ok()
+        .and_then(|remote| remote.url().map(|url| same_repository(url, repository_url)))
+        .unwrap_or(false)
+    })
+    .ok_or_else(|| format!("no git remote points at {}", repository_url))?;
+  let branches = repo
+    .references_glob(&format!("refs/remotes/{}/*", remote))
+    .map_err(|err| err.message().to_string())?;
+  for branch in branches.filter_map(Result::ok) {
+    if let Ok(commit) = branch.peel_to_commit() {
+      if commit.id() == head || repo.graph_descendant_of(commit.id(), head).unwrap_or(false) {
+        return Ok(());
+      }
+    }
+  }
+  Err(format!(
+    "commit {} isn't on any branch of `{}` ({}), push it first",
+    head, remote, repository_url
+  ))
+}
+
+fn same_repository(a: &str, b: &str) -> bool {
+  normalize_url(a) == normalize_url(b)
+}
+
+// Reduces https, ssh and scp-like URLs of the same repository to
+// one form, e.g. `git@github.com:acme/protos.git` and
+// `https://github.com/acme/protos` both become `github.com/acme/protos`.
+fn normalize_url(url: &str) -> String {
+  let url = url.trim().trim_end_matches('/');
+  let url = url.trim_end_matches(".git");
+  let url = url
+    .find("://")
+    .map_or(url, |scheme_end| &url[scheme_end + 3..]);
+  let url = url.find('@').map_or(url, |user_end| &url[user_end + 1..]);
+  url.replacen(':', "/", 1).to_lowercase()
+}
+
+/// Tags HEAD of the repository containing `package_dir`, annotated when git
+/// knows who the user is.
+pub fn create_tag(package_dir: &Path, tag: &str, message: &str) -> Result<(), String> {
+  let repo = Repository::discover(package_dir)
+    .map_err(|_| "the package isn't in a git repository".to_string())?;
+  let head = repo
+    .head()
+    .and_then(|head| head.peel(ObjectType::Commit))
+    .map_err(|err| err.message().to_string())?;
+  let created = match repo.signature() {
+    Ok(signature) => repo.tag(tag, &head, &signature, message, false),
+    Err(_) => repo.tag_lightweight(tag, &head, false),
+  };
+  created.map(|_| ()).map_err(|err| err.message().to_string())
+}
+
+/// The .proto files of the package in `package_dir` as of `revision`, any
+/// revision git understands, e.g. `main` or `HEAD~2`, read straight from the
+/// object database without touching the working tree. Paths are relative to
+/// the package directory. `None` when the package didn't exist yet.
+pub fn proto_sources_at(
+  package_dir: &Path,
+  revision: &str,
+) -> Result<Option<Vec<(PathBuf, String)>>, String> {
+  let repo = Repository::discover(package_dir)
+    .map_err(|_| "the package isn't in a git repository".to_string())?;
+  let tree = repo
+    .revparse_single(revision)
+    .and_then(|object| object.peel_to_tree())
+    .map_err(|err| format!("unknown revision `{}`: {}", revision, err.message()))?;
+  let (workdir, package_dir) = match (
+    repo.workdir().and_then(|dir| dir.canonicalize().ok()),
+    package_dir.canonicalize().ok(),
+  ) {
+    (Some(workdir), Some(package_dir)) => (workdir, package_dir),
+    _ => return Err("the repository has no working tree".to_string()),
+  };
+  let tree = match package_dir.strip_prefix(&workdir) {
+    Ok(relative) if relative.as_os_str().is_empty() => tree,
+    Ok(relative) => match tree.get_path(relative) {
+      Ok(entry) => entry
+        .to_object(&repo)
+        .and_then(|object| object.peel_to_tree())
+        .map_err(|err| err.message().to_string())?,
+      Err(_) => return Ok(None),
+    },
+    Err(_) => return Err("the package is outside the repository".to_string()),
+  };
+  // Unlike the working tree, ignore files aren't applied here, so
+  // ignored .proto files that were committed anyway are part of the baseline.
+  let mut blobs = Vec::new();
+  tree
+    .walk(TreeWalkMode::PreOrder, |root, entry| {
+      if let Some(name) = entry.name() {
+        if entry.kind() == Some(ObjectType::Blob) && name.ends_with(".proto") {
+          blobs.push((PathBuf::from(root).join(name), entry.id()));
+        }
+      }
+      TreeWalkResult::Ok
+    })
+    .map_err(|err| err.message().to_string())?;
+  let mut sources = Vec::new();
+  for (path, id) in blobs {
+    let blob = repo
+      .find_blob(id)
+      .map_err(|err| err.message().to_string())?;
+    let source = String::from_utf8(blob.content().to_vec())
+      .map_err(|_| format!("{} at {} isn't valid UTF-8", path.display(), revision))?;
+    sources.push((path, source));
+  }
+  Ok(Some(sources))
+}
+
+fn pathspec(workdir: &Path, package_dir: &Path) -> Option<String> {
+  let relative = package_dir.strip_prefix(workdir).ok()?;
+  if relative.as_os_str().is_empty() {
+    None
+  } else {
+    Some(relative.to_string_lossy().replace('\\', "/"))
+  }
+}
+
+/// A repository whose first branch is `master`, whatever `init.defaultBranch`
+/// says.
+#[cfg(test)]
+fn init_repo(root: &Path) -> Repository {
+  Repository::init_opts(
+    root,
+    git2::RepositoryInitOptions::new().initial_head("master"),
+  )
+  .unwrap()
+}
+
+#[cfg(test)]
+fn commit_all(repo: &Repository, message: &str) {
+  let mut index = repo.index().unwrap();
+  index
+    .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+    .unwrap();
+  index.write().unwrap();
+  let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+  let signature = git2::Signature::now("buff", "buff@example.com").unwrap();
+  let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+  let parents: Vec<&git2::Commit> = parent.iter().collect();
+  repo
+    .commit(
+      Some("HEAD"),
+      &signature,
+      &signature,
+      message,
+      &tree,
+      &parents,
+    )
+    .unwrap();
+}
+
+#[test]
+fn should_describe_repository() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let root = tmp_dir.path();
+  assert_eq!(state(root), None);
+  let repo = init_repo(root);
+  repo
+    .remote("origin", "https://example.com/acme/protos.git")
+    .unwrap();
+  let package_dir = root.join("billing");
+  std::fs::create_dir_all(&package_dir).unwrap();
+  std::fs::write(package_dir.join("billing.proto"), "syntax = \"proto3\";").unwrap();
+  std::fs::write(root.join("README.md"), "protos").unwrap();
+  commit_all(&repo, "Initial commit");
+  let clean = state(&package_dir).unwrap();
+  assert_eq!(clean.commit.len(), 40);
+  assert_eq!(clean.branch, Some("master".to_string()));
+  assert!(!clean.dirty);
+  assert_eq!(
+    clean.remote_url,
+    Some("https://example.com/acme/protos.git".to_string())
+  );
+  std::fs::write(root.join("README.md"), "changed outside the package").unwrap();
+  assert!(!state(&package_dir).unwrap().dirty);
+  std::fs::write(package_dir.join("new.proto"), "syntax = \"proto3\";").unwrap();
+  assert!(state(&package_dir).unwrap().dirty);
+}
+
+#[test]
+fn should_list_uncommitted_changes() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let root = tmp_dir.path();
+  let repo = init_repo(root);
+  std::fs::write(root.join("a.proto"), "syntax = \"proto3\";").unwrap();
+  std::fs::write(root.join("b.proto"), "syntax = \"proto3\";").unwrap();
+  std::fs::write(root.join(".ignore"), "*.tar.gz\n").unwrap();
+  commit_all(&repo, "Initial commit");
+  assert!(uncommitted_changes(root).is_empty());
+  std::fs::write(root.join("a.proto"), "syntax = \"proto2\";").unwrap();
+  std::fs::remove_file(root.join("b.proto")).unwrap();
+  std::fs::write(root.join("c.proto"), "syntax = \"proto3\";").unwrap();
+  std::fs::write(root.join("a-0.1.0.tar.gz"), "artifact").unwrap();
+  assert_eq!(
+    uncommitted_changes(root),
+    vec![
+      PathBuf::from("a.proto"),
+      PathBuf::from("b.proto"),
+      PathBuf::from("c.proto")
+    ]
+  );
+}
+
+#[test]
+fn should_verify_pushed_commits() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let root = tmp_dir.path();
+  let repo = init_repo(root);
+  repo
+    .remote("origin", "git@github.com:acme/protos.git")
+    .unwrap();
+  std::fs::write(root.join("a.proto"), "syntax = \"proto3\";").unwrap();
+  commit_all(&repo, "Initial commit");
+  let url = "https://github.com/acme/protos";
+  assert!(verify_pushed(root, url).is_err());
+  let head = repo.head().unwrap().peel_to_commit().unwrap();
+  repo
+    .reference("refs/remotes/origin/master", head.id(), false, "fetch")
+    .unwrap();
+  assert_eq!(verify_pushed(root, url), Ok(()));
+  assert!(verify_pushed(root, "https://github.com/acme/other").is_err());
+  std::fs::write(root.join("a.proto"), "syntax = \"proto2\";").unwrap();
+  commit_all(&repo, "Unpushed commit");
+  assert!(verify_pushed(root, url).is_err());
+  create_tag(root, "protos-v0.1.0", "protos 0.1.0").unwrap();
+  assert!(repo.revparse_single("protos-v0.1.0").is_ok());
+  assert!(create_tag(root, "protos-v0.1.0", "protos 0.1.0").is_err());
+}
+
+#[test]
+fn should_read_protos_at_a_revision() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let root = tmp_dir.path();
+  let repo = init_repo(root);
+  let package_dir = root.join("billing");
+  std::fs::create_dir_all(package_dir.join("protos")).unwrap();
+  std::fs::write(
+    package_dir.join("protos/invoice.proto"),
+    "syntax = \"proto3\";",
+  )
+  .unwrap();
+  std::fs::write(package_dir.join("buff.toml"), "").unwrap();
+  commit_all(&repo, "Initial commit");
+  std::fs::write(
+    package_dir.join("protos/invoice.proto"),
+    "syntax = \"proto2\";",
+  )
+  .unwrap();
+  std::fs::write(
+    package_dir.join("protos/refund.proto"),
+    "syntax = \"proto3\";",
+  )
+  .unwrap();
+  assert_eq!(
+    proto_sources_at(&package_dir, "HEAD"),
+    Ok(Some(vec![(
+      PathBuf::from("protos/invoice.proto"),
+      "syntax = \"proto3\";".to_string()
+    )]))
+  );
+  std::fs::create_dir_all(root.join("shipping")).unwrap();
+  assert_eq!(proto_sources_at(&root.join("shipping"), "HEAD"), Ok(None));
+  assert!(proto_sources_at(&package_dir, "no-such-branch").is_err());
+}
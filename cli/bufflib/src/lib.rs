@@ -1,5 +1,24 @@
 pub mod artifact;
 pub mod buff_cli_config;
+pub mod changelog;
+pub mod codec;
+pub mod compat;
+pub mod dependencies;
+pub mod descriptor;
+pub mod diff;
+pub mod fix;
+pub mod fmt;
+pub mod git;
+pub mod lint;
+pub mod lockfile;
+pub mod manifest;
 pub mod package_metadata;
+pub mod proto;
+pub mod provenance;
 pub mod protobuffers;
 pub mod registry;
+pub mod scaffold;
+pub mod signing;
+pub mod verify;
+pub mod version;
+pub mod workspace;
@@ -0,0 +1,788 @@
+use crate::compat::{self, Schema};
+use crate::manifest;
+use crate::package_metadata::PackageMetadata;
+use crate::proto;
+use crate::proto::{qualify, Enum, Message, Service, SourceFile};
+use regex::Regex;
+use serde::de::{self, Deserialize, Deserializer};
+use serde_derive::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Every rule with what it checks.
+pub const RULES: &[(&str, &str)] = &[
+  ("PACKAGE_DEFINED", "Files have a package statement"),
+  (
+    "PACKAGE_DIRECTORY_MATCH",
+    "Files are in a directory matching their package, e.g. acme/v1 for acme.v1",
+  ),
+  ("MESSAGE_PASCAL_CASE", "Messages are PascalCase"),
+  ("FIELD_LOWER_SNAKE_CASE", "Fields are lower_snake_case"),
+  ("ENUM_PASCAL_CASE", "Enums are PascalCase"),
+  (
+    "ENUM_VALUE_UPPER_SNAKE_CASE",
+    "Enum values are UPPER_SNAKE_CASE",
+  ),
+  ("ENUM_ZERO_VALUE_FIRST", "The first enum value is 0"),
+  (
+    "ENUM_VALUE_PREFIX",
+    "Enum values are prefixed with the enum's name in UPPER_SNAKE_CASE",
+  ),
+  (
+    "ENUM_ZERO_VALUE_SUFFIX",
+    "The zero value of an enum is named <PREFIX>_UNSPECIFIED",
+  ),
+  ("SERVICE_PASCAL_CASE", "Services are PascalCase"),
+  ("RPC_PASCAL_CASE", "Rpcs are PascalCase"),
+  (
+    "RPC_REQUEST_STANDARD_NAME",
+    "Requests are named <Rpc>Request or <Service><Rpc>Request",
+  ),
+  (
+    "RPC_RESPONSE_STANDARD_NAME",
+    "Responses are named <Rpc>Response or <Service><Rpc>Response",
+  ),
+  (
+    "RPC_REQUEST_RESPONSE_UNIQUE",
+    "Every request and response message is used by a single rpc",
+  ),
+  ("COMMENT_MESSAGE", "Messages have a comment"),
+  ("COMMENT_FIELD", "Fields have a comment"),
+  ("COMMENT_ENUM", "Enums have a comment"),
+  ("COMMENT_ENUM_VALUE", "Enum values have a comment"),
+  ("COMMENT_SERVICE", "Services have a comment"),
+  ("COMMENT_RPC", "Rpcs have a comment"),
+  (
+    FORBIDDEN_PATTERN,
+    "Names, field types and imports don't match the patterns in `forbidden`",
+  ),
+];
+
+/// Checked whenever `forbidden` has patterns, so it isn't in any group.
+const FORBIDDEN_PATTERN: &str = "FORBIDDEN_PATTERN";
+
+/// Rule groups, each one including the rules of the ones before it except
+/// for `COMMENTS`.
+const GROUPS: &[(&str, &[&str])] = &[
+  ("MINIMAL", &["PACKAGE_DEFINED"]),
+  (
+    "BASIC",
+    &[
+      "PACKAGE_DEFINED",
+      "MESSAGE_PASCAL_CASE",
+      "FIELD_LOWER_SNAKE_CASE",
+      "ENUM_PASCAL_CASE",
+      "ENUM_VALUE_UPPER_SNAKE_CASE",
+      "ENUM_ZERO_VALUE_FIRST",
+      "SERVICE_PASCAL_CASE",
+      "RPC_PASCAL_CASE",
+    ],
+  ),
+  (
+    "STANDARD",
+    &[
+      "PACKAGE_DEFINED",
+      "MESSAGE_PASCAL_CASE",
+      "FIELD_LOWER_SNAKE_CASE",
+      "ENUM_PASCAL_CASE",
+      "ENUM_VALUE_UPPER_SNAKE_CASE",
+      "ENUM_ZERO_VALUE_FIRST",
+      "SERVICE_PASCAL_CASE",
+      "RPC_PASCAL_CASE",
+      "PACKAGE_DIRECTORY_MATCH",
+      "ENUM_VALUE_PREFIX",
+      "ENUM_ZERO_VALUE_SUFFIX",
+      "RPC_REQUEST_STANDARD_NAME",
+      "RPC_RESPONSE_STANDARD_NAME",
+      "RPC_REQUEST_RESPONSE_UNIQUE",
+    ],
+  ),
+  (
+    "COMMENTS",
+    &[
+      "COMMENT_MESSAGE",
+      "COMMENT_FIELD",
+      "COMMENT_ENUM",
+      "COMMENT_ENUM_VALUE",
+      "COMMENT_SERVICE",
+      "COMMENT_RPC",
+    ],
+  ),
+];
+
+/// The `[lint]` section of buff.toml, e.g.
+///
+/// ```toml
+/// [lint]
+/// use = ["STANDARD", "COMMENT_SERVICE"]
+/// except = ["ENUM_VALUE_PREFIX"]
+/// ignore = ["protos/vendor"]
+/// ignore_only = { FIELD_LOWER_SNAKE_CASE = ["protos/legacy.proto"] }
+/// forbidden = [{ pattern = "^google\\.protobuf\\.Any$", message = "use a oneof" }]
+/// ```
+///
+/// `use` takes groups, `MINIMAL`, `BASIC`, `STANDARD` or `COMMENTS`, and
+/// single rules, `BASIC` by default. `ignore` skips files and directories,
+/// relative to the package, and `ignore_only` skips them for some rules or
+/// groups.
+/// `forbidden` patterns are regular expressions matched against the
+/// fully-qualified names of elements, field types and imports.
+#[derive(Debug, Clone)]
+pub struct LintConfig {
+  pub rules: Vec<&'static str>,
+  pub ignore: Vec<PathBuf>,
+  pub ignore_only: BTreeMap<String, Vec<PathBuf>>,
+  pub forbidden: Vec<ForbiddenPattern>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ForbiddenPattern {
+  pub pattern: Regex,
+  /// Why the pattern is forbidden, shown instead of the pattern.
+  pub message: Option<String>,
+}
+
+impl Default for LintConfig {
+  fn default() -> Self {
+    LintConfig {
+      rules: expand("BASIC").unwrap(),
+      ignore: Vec::new(),
+      ignore_only: BTreeMap::new(),
+      forbidden: Vec::new(),
+    }
+  }
+}
+
+impl LintConfig {
+  /// Whether `rule` is checked in `file`.
+  fn checks(&self, rule: &str, file: &Path) -> bool {
+    (rule == FORBIDDEN_PATTERN || self.rules.contains(&rule))
+      && !self.ignore.iter().any(|ignored| file.starts_with(ignored))
+      && !self.ignore_only.get(rule).map_or(false, |ignored| {
+        ignored.iter().any(|path| file.starts_with(path))
+      })
+  }
+}
+
+/// The rules a group or single rule in `use` or `except` stands for.
+fn expand(name: &str) -> Result<Vec<&'static str>, String> {
+  if let Some((_, rules)) = GROUPS.iter().find(|(group, _)| *group == name) {
+    return Ok(rules.to_vec());
+  }
+  match RULES.iter().find(|(rule, _)| *rule == name) {
+    Some((rule, _)) => Ok(vec![rule]),
+    None => Err(format!("unknown lint rule or group `{}`", name)),
+  }
+}
+
+impl<'de> Deserialize<'de> for LintConfig {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    #[derive(Deserialize)]
+    struct Forbidden {
+      pattern: String,
+      message: Option<String>,
+    }
+    #[derive(Deserialize)]
+    struct Section {
+      #[serde(rename = "use")]
+      uses: Option<Vec<String>>,
+      #[serde(default)]
+      except: Vec<String>,
+      #[serde(default)]
+      ignore: Vec<PathBuf>,
+      #[serde(default)]
+      ignore_only: BTreeMap<String, Vec<PathBuf>>,
+      #[serde(default)]
+      forbidden: Vec<Forbidden>,
+    }
+    let section = Section::deserialize(deserializer)?;
+    let mut config = LintConfig::default();
+    if let Some(uses) = section.uses {
+      config.rules = Vec::new();
+      for name in &uses {
+        for rule in expand(name).map_err(de::Error::custom)? {
+          if !config.rules.contains(&rule) {
+            config.rules.push(rule);
+          }
+        }
+      }
+    }
+    for name in &section.except {
+      let excepted = expand(name).map_err(de::Error::custom)?;
+      config.rules.retain(|rule| !excepted.contains(rule));
+    }
+    for (name, paths) in section.ignore_only {
+      for rule in expand(&name).map_err(de::Error::custom)? {
+        config
+          .ignore_only
+          .entry(rule.to_string())
+          .or_insert_with(Vec::new)
+          .extend(paths.iter().cloned());
+      }
+    }
+    config.ignore = section.ignore;
+    for forbidden in section.forbidden {
+      let pattern = Regex::new(&forbidden.pattern).map_err(|err| {
+        de::Error::custom(format!(
+          "invalid forbidden pattern `{}`: {}",
+          forbidden.pattern, err
+        ))
+      })?;
+      config.forbidden.push(ForbiddenPattern {
+        pattern,
+        message: forbidden.message,
+      });
+    }
+    Ok(config)
+  }
+}
+
+/// A style problem in a .proto file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintIssue {
+  pub rule: &'static str,
+  pub file: PathBuf,
+  /// The line the element is declared on, 0 for issues with the whole file.
+  pub line: usize,
+  /// Fully-qualified name of the element, e.g. `acme.v1.User.user_id`.
+  pub path: String,
+  pub message: String,
+}
+
+impl fmt::Display for LintIssue {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.file.display())?;
+    if self.line > 0 {
+      write!(f, ":{}", self.line)?;
+    }
+    write!(f, ": {} {}: {}", self.rule, self.path, self.message)
+  }
+}
+
+struct Linter<'a> {
+  file: &'a SourceFile,
+  config: &'a LintConfig,
+  issues: Vec<LintIssue>,
+}
+
+impl<'a> Linter<'a> {
+  fn report(&mut self, rule: &'static str, path: String, message: String) {
+    if !self.config.checks(rule, &self.file.path) {
+      return;
+    }
+    let line = self
+      .file
+      .file
+      .declarations
+      .iter()
+      .find(|declaration| declaration.path == path)
+      .map_or(0, |declaration| declaration.line);
+    self.issues.push(LintIssue {
+      rule,
+      file: self.file.path.clone(),
+      line,
+      path,
+      message,
+    });
+  }
+
+  fn check_case(&mut self, rule: &'static str, case: Case, path: &str, name: &str) {
+    if !case.matches(name) {
+      self.report(
+        rule,
+        path.to_string(),
+        format!("`{}` should be {}", name, case.describe()),
+      );
+    }
+  }
+
+  fn check_comment(&mut self, rule: &'static str, path: &str) {
+    let undocumented = self
+      .file
+      .file
+      .declarations
+      .iter()
+      .any(|declaration| declaration.path == path && !declaration.documented);
+    if undocumented {
+      self.report(rule, path.to_string(), "has no comment".to_string());
+    }
+  }
+
+  /// Checks `text`, a name, type or import of the element at `path`, against
+  /// the forbidden patterns.
+  fn check_forbidden(&mut self, path: &str, text: &str) {
+    let config = self.config;
+    for forbidden in &config.forbidden {
+      if forbidden.pattern.is_match(text) {
+        let message = match forbidden.message {
+          Some(ref message) => format!("`{}` is forbidden: {}", text, message),
+          None => format!(
+            "`{}` matches the forbidden pattern `{}`",
+            text, forbidden.pattern
+          ),
+        };
+        self.report(FORBIDDEN_PATTERN, path.to_string(), message);
+      }
+    }
+  }
+
+  fn lint_message(&mut self, scope: &str, message: &Message) {
+    let path = qualify(scope, &message.name);
+    self.check_case("MESSAGE_PASCAL_CASE", Case::Pascal, &path, &message.name);
+    self.check_comment("COMMENT_MESSAGE", &path);
+    self.check_forbidden(&path, &path);
+    for field in &message.fields {
+      let field_path = qualify(&path, &field.name);
+      self.check_case(
+        "FIELD_LOWER_SNAKE_CASE",
+        Case::LowerSnake,
+        &field_path,
+        &field.name,
+      );
+      self.check_comment("COMMENT_FIELD", &field_path);
+      self.check_forbidden(&field_path, &field_path);
+      self.check_forbidden(&field_path, field.type_name.trim_start_matches('.'));
+    }
+    for nested in &message.messages {
+      self.lint_message(&path, nested);
+    }
+    for enumeration in &message.enums {
+      self.lint_enum(&path, enumeration);
+    }
+  }
+
+  fn lint_enum(&mut self, scope: &str, enumeration: &Enum) {
+    let path = qualify(scope, &enumeration.name);
+    self.check_case("ENUM_PASCAL_CASE", Case::Pascal, &path, &enumeration.name);
+    self.check_comment("COMMENT_ENUM", &path);
+    self.check_forbidden(&path, &path);
+    let prefix = format!("{}_", upper_snake(&enumeration.name));
+    for value in &enumeration.values {
+      let value_path = qualify(&path, &value.name);
+      self.check_case(
+        "ENUM_VALUE_UPPER_SNAKE_CASE",
+        Case::UpperSnake,
+        &value_path,
+        &value.name,
+      );
+      if !value.name.starts_with(&prefix) {
+        self.report(
+          "ENUM_VALUE_PREFIX",
+          value_path.clone(),
+          format!("`{}` should start with `{}`", value.name, prefix),
+        );
+      }
+      let unspecified = format!("{}UNSPECIFIED", prefix);
+      if value.number == 0 && value.name != unspecified {
+        self.report(
+          "ENUM_ZERO_VALUE_SUFFIX",
+          value_path.clone(),
+          format!("the zero value should be `{}`", unspecified),
+        );
+      }
+      self.check_comment("COMMENT_ENUM_VALUE", &value_path);
+      self.check_forbidden(&value_path, &value_path);
+    }
+    if let Some(first) = enumeration.values.first() {
+      if first.number != 0 {
+        self.report(
+          "ENUM_ZERO_VALUE_FIRST",
+          path.clone(),
+          format!("the first value should be 0, not {}", first.number),
+        );
+      }
+    }
+  }
+
+  fn lint_service(&mut self, scope: &str, service: &Service) {
+    let path = qualify(scope, &service.name);
+    self.check_case("SERVICE_PASCAL_CASE", Case::Pascal, &path, &service.name);
+    self.check_comment("COMMENT_SERVICE", &path);
+    self.check_forbidden(&path, &path);
+    for rpc in &service.rpcs {
+      let rpc_path = qualify(&path, &rpc.name);
+      self.check_case("RPC_PASCAL_CASE", Case::Pascal, &rpc_path, &rpc.name);
+      self.check_comment("COMMENT_RPC", &rpc_path);
+      self.check_forbidden(&rpc_path, &rpc_path);
+      let types = [
+        ("RPC_REQUEST_STANDARD_NAME", "Request", &rpc.input_type),
+        ("RPC_RESPONSE_STANDARD_NAME", "Response", &rpc.output_type),
+      ];
+      for (rule, suffix, type_name) in types.iter() {
+        let name = type_name.rsplit('.').next().unwrap_or("");
+        let expected = format!("{}{}", rpc.name, suffix);
+        if name != expected && name != format!("{}{}", service.name, expected) {
+          self.report(
+            rule,
+            rpc_path.clone(),
+            format!(
+              "{} type should be `{}`, not `{}`",
+              suffix.to_lowercase(),
+              expected,
+              name
+            ),
+          );
+        }
+      }
+    }
+  }
+}
+
+#[derive(Clone, Copy)]
+enum Case {
+  Pascal,
+  LowerSnake,
+  UpperSnake,
+}
+
+impl Case {
+  fn matches(self, name: &str) -> bool {
+    let first = match name.chars().next() {
+      Some(first) => first,
+      None => return false,
+    };
+    match self {
+      Case::Pascal => first.is_ascii_uppercase() && name.chars().all(|c| c.is_ascii_alphanumeric()),
+      Case::LowerSnake => {
+        first.is_ascii_lowercase()
+          && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+      }
+      Case::UpperSnake => {
+        first.is_ascii_uppercase()
+          && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+      }
+    }
+  }
+
+  fn describe(self) -> &'static str {
+    match self {
+      Case::Pascal => "PascalCase",
+      Case::LowerSnake => "lower_snake_case",
+      Case::UpperSnake => "UPPER_SNAKE_CASE",
+    }
+  }
+}
+
+/// `name` in UPPER_SNAKE_CASE, e.g. `HTTP_STATUS` for `HTTPStatus`.
+fn upper_snake(name: &str) -> String {
+  let chars: Vec<char> = name.chars().collect();
+  let mut snake = String::new();
+  for (i, c) in chars.iter().enumerate() {
+    if i > 0 && c.is_uppercase() {
+      let previous = chars[i - 1];
+      let next_is_lower = chars.get(i + 1).map_or(false, |next| next.is_lowercase());
+      if previous.is_lowercase()
+        || previous.is_ascii_digit()
+        || (previous.is_uppercase() && next_is_lower)
+      {
+        snake.push('_');
+      }
+    }
+    snake.extend(c.to_uppercase());
+  }
+  snake
+}
+
+pub fn lint(files: &[SourceFile], config: &LintConfig) -> Vec<LintIssue> {
+  let mut issues = Vec::new();
+  for source in files {
+    let mut linter = Linter {
+      file: source,
+      config,
+      issues: Vec::new(),
+    };
+    let scope = source.file.package.clone().unwrap_or_default();
+    match source.file.package {
+      None => linter.report(
+        "PACKAGE_DEFINED",
+        String::new(),
+        "file has no package statement".to_string(),
+      ),
+      Some(ref package) => {
+        let expected: PathBuf = package.split('.').collect();
+        let in_dir = source
+          .path
+          .parent()
+          .map_or(false, |dir| dir.ends_with(&expected));
+        if !in_dir {
+          linter.report(
+            "PACKAGE_DIRECTORY_MATCH",
+            String::new(),
+            format!(
+              "files in package `{}` should be in a `{}` directory",
+              package,
+              expected.display()
+            ),
+          );
+        }
+      }
+    }
+    for import in &source.file.imports {
+      linter.check_forbidden(&import.path, &import.path);
+    }
+    for message in &source.file.messages {
+      linter.lint_message(&scope, message);
+    }
+    for enumeration in &source.file.enums {
+      linter.lint_enum(&scope, enumeration);
+    }
+    for service in &source.file.services {
+      linter.lint_service(&scope, service);
+    }
+    issues.extend(linter.issues);
+  }
+  issues.extend(lint_unique_messages(files, config));
+  issues
+}
+
+/// Reports rpcs whose request or response is already used by another rpc,
+/// or by the same one the other way around.
+fn lint_unique_messages(files: &[SourceFile], config: &LintConfig) -> Vec<LintIssue> {
+  let schema = Schema::new(files);
+  let mut users: BTreeMap<String, String> = BTreeMap::new();
+  let mut issues = Vec::new();
+  for source in files {
+    let mut linter = Linter {
+      file: source,
+      config,
+      issues: Vec::new(),
+    };
+    let scope = source.file.package.clone().unwrap_or_default();
+    for service in &source.file.services {
+      let service_path = qualify(&scope, &service.name);
+      for rpc in &service.rpcs {
+        let rpc_path = qualify(&service_path, &rpc.name);
+        let mut reported = Vec::new();
+        for type_name in &[&rpc.input_type, &rpc.output_type] {
+          let resolved = schema.resolve(compat::parent_scope(&service_path), type_name);
+          let message = match users.get(&resolved) {
+            None => {
+              users.insert(resolved, rpc_path.clone());
+              continue;
+            }
+            Some(user) if *user == rpc_path => {
+              format!("`{}` is both the request and the response", resolved)
+            }
+            Some(user) => format!("`{}` is already used by {}", resolved, user),
+          };
+          if !reported.contains(&resolved) {
+            linter.report("RPC_REQUEST_RESPONSE_UNIQUE", rpc_path.clone(), message);
+            reported.push(resolved);
+          }
+        }
+      }
+    }
+    issues.append(&mut linter.issues);
+  }
+  issues
+}
+
+/// Lints the package at `manifest_path` with its `[lint]` settings.
+pub fn lint_package(manifest_path: &Path) -> Vec<LintIssue> {
+  let metadata = PackageMetadata::new(manifest_path.to_str().unwrap());
+  let files = proto::parse_dir(&manifest::package_dir(manifest_path))
+    .unwrap_or_else(|err| panic!("Failed to parse {}", err));
+  lint(&files, &metadata.lint)
+}
+
+/// How `buff lint` prints issues.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+  Human,
+  Json,
+  /// SARIF 2.1.0, for code scanning tools.
+  Sarif,
+}
+
+impl FromStr for Format {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "human" => Ok(Format::Human),
+      "json" => Ok(Format::Json),
+      "sarif" => Ok(Format::Sarif),
+      _ => Err(format!(
+        "unknown format `{}`, expected human, json or sarif",
+        s
+      )),
+    }
+  }
+}
+
+pub fn render(issues: &[LintIssue], format: Format) -> String {
+  match format {
+    Format::Human => issues.iter().map(|issue| format!("{}\n", issue)).collect(),
+    Format::Json => serde_json::to_string_pretty(issues).expect("Failed to serialize issues"),
+    Format::Sarif => {
+      serde_json::to_string_pretty(&sarif(issues)).expect("Failed to serialize issues")
+    }
+  }
+}
+
+fn sarif(issues: &[LintIssue]) -> serde_json::Value {
+  let rules: Vec<serde_json::Value> = RULES
+    .iter()
+    .map(|(id, description)| json!({ "id": id, "shortDescription": { "text": description } }))
+    .collect();
+  let results: Vec<serde_json::Value> = issues
+    .iter()
+    .map(|issue| {
+      let mut location = json!({
+        "artifactLocation": { "uri": issue.file.to_string_lossy().replace('\\', "/") }
+      });
+      if issue.line > 0 {
+        location["region"] = json!({ "startLine": issue.line });
+      }
+      let text = if issue.path.is_empty() {
+        issue.message.clone()
+      } else {
+        format!("{}: {}", issue.path, issue.message)
+      };
+      json!({
+        "ruleId": issue.rule,
+        "level": "error",
+        "message": { "text": text },
+        "locations": [{ "physicalLocation": location }],
+      })
+    })
+    .collect();
+  json!({
+    "version": "2.1.0",
+    "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+    "runs": [{
+      "tool": {
+        "driver": {
+          "name": "buff",
+          "informationUri": "https://github.com/BlueHotDog/buff",
+          "rules": rules,
+        }
+      },
+      "results": results,
+    }],
+  })
+}
+
+#[cfg(test)]
+fn source_file(path: &str, source: &str) -> SourceFile {
+  SourceFile {
+    path: PathBuf::from(path),
+    file: crate::proto::parse(source).unwrap(),
+  }
+}
+
+#[test]
+fn should_lint() {
+  let source = "syntax = \"proto3\";
+    message user_profile { string UserId = 1; string display_name = 2; }
+    enum Status { active = 1; DONE = 2; }
+    service Users { rpc get_user (user_profile) returns (user_profile); }";
+  let files = vec![source_file("users.proto", source)];
+  let found: Vec<String> = lint(&files, &LintConfig::default())
+    .iter()
+    .map(|issue| format!("{} {}", issue.rule, issue.path))
+    .collect();
+  assert_eq!(
+    found,
+    vec![
+      "PACKAGE_DEFINED ",
+      "MESSAGE_PASCAL_CASE user_profile",
+      "FIELD_LOWER_SNAKE_CASE user_profile.UserId",
+      "ENUM_VALUE_UPPER_SNAKE_CASE Status.active",
+      "ENUM_ZERO_VALUE_FIRST Status",
+      "RPC_PASCAL_CASE Users.get_user",
+    ]
+  );
+}
+
+#[test]
+fn should_lint_with_configured_rules() {
+  let config: LintConfig = toml::from_str(
+    "use = [\"STANDARD\", \"COMMENT_RPC\"]
+     except = [\"ENUM_VALUE_PREFIX\"]
+     ignore = [\"vendor\"]
+     ignore_only = { RPC_REQUEST_STANDARD_NAME = [\"acme/v1/legacy.proto\"], COMMENTS = [\"acme/v1/legacy.proto\"] }
+     forbidden = [{ pattern = \"^google\\\\.protobuf\\\\.Any$\", message = \"use a oneof\" }]",
+  )
+  .unwrap();
+  let files = vec![
+    source_file(
+      "acme/v1/orders.proto",
+      "syntax = \"proto3\"; package acme.v1;
+       import \"google/protobuf/any.proto\";
+       enum OrderStatus {
+         UNKNOWN = 0;
+         ORDER_STATUS_OPEN = 1;
+       }
+       message GetOrderRequest { google.protobuf.Any details = 1; }
+       message Order {}
+       // Places and looks up orders.
+       service Orders {
+         // Looks up an order.
+         rpc GetOrder (GetOrderRequest) returns (Order);
+         rpc ListOrders (GetOrderRequest) returns (Order);
+       }",
+    ),
+    source_file(
+      "acme/v1/legacy.proto",
+      "syntax = \"proto3\"; package acme.v1;
+       service Legacy {
+         rpc Fetch (Order) returns (Order);
+       }",
+    ),
+    source_file("vendor/bad.proto", "message bad {}"),
+    source_file("orders.proto", "syntax = \"proto3\"; package acme.v1;"),
+  ];
+  let found: Vec<String> = lint(&files, &config)
+    .iter()
+    .map(ToString::to_string)
+    .collect();
+  assert_eq!(
+    found,
+    vec![
+      "acme/v1/orders.proto:7: FORBIDDEN_PATTERN acme.v1.GetOrderRequest.details: `google.protobuf.Any` is forbidden: use a oneof",
+      "acme/v1/orders.proto:4: ENUM_ZERO_VALUE_SUFFIX acme.v1.OrderStatus.UNKNOWN: the zero value should be `ORDER_STATUS_UNSPECIFIED`",
+      "acme/v1/orders.proto:12: RPC_RESPONSE_STANDARD_NAME acme.v1.Orders.GetOrder: response type should be `GetOrderResponse`, not `Order`",
+      "acme/v1/orders.proto:13: COMMENT_RPC acme.v1.Orders.ListOrders: has no comment",
+      "acme/v1/orders.proto:13: RPC_REQUEST_STANDARD_NAME acme.v1.Orders.ListOrders: request type should be `ListOrdersRequest`, not `GetOrderRequest`",
+      "acme/v1/orders.proto:13: RPC_RESPONSE_STANDARD_NAME acme.v1.Orders.ListOrders: response type should be `ListOrdersResponse`, not `Order`",
+      "acme/v1/legacy.proto:3: RPC_RESPONSE_STANDARD_NAME acme.v1.Legacy.Fetch: response type should be `FetchResponse`, not `Order`",
+      "orders.proto: PACKAGE_DIRECTORY_MATCH : files in package `acme.v1` should be in a `acme/v1` directory",
+      "acme/v1/orders.proto:13: RPC_REQUEST_RESPONSE_UNIQUE acme.v1.Orders.ListOrders: `acme.v1.GetOrderRequest` is already used by acme.v1.Orders.GetOrder",
+      "acme/v1/orders.proto:13: RPC_REQUEST_RESPONSE_UNIQUE acme.v1.Orders.ListOrders: `acme.v1.Order` is already used by acme.v1.Orders.GetOrder",
+      "acme/v1/legacy.proto:3: RPC_REQUEST_RESPONSE_UNIQUE acme.v1.Legacy.Fetch: `acme.v1.Order` is already used by acme.v1.Orders.GetOrder",
+    ]
+  );
+  assert!(toml::from_str::<LintConfig>("use = [\"NAMING\"]").is_err());
+  assert!(toml::from_str::<LintConfig>("ignore_only = { NAMING = [\"a.proto\"] }").is_err());
+  assert!(toml::from_str::<LintConfig>("forbidden = [{ pattern = \"(\" }]").is_err());
+}
+
+#[test]
+fn should_render_sarif() {
+  let issues = lint(
+    &[source_file(
+      "users.proto",
+      "syntax = \"proto3\";\nmessage user {}",
+    )],
+    &LintConfig::default(),
+  );
+  let sarif: serde_json::Value = serde_json::from_str(&render(&issues, Format::Sarif)).unwrap();
+  let result = &sarif["runs"][0]["results"][1];
+  assert_eq!(result["ruleId"], "MESSAGE_PASCAL_CASE");
+  assert_eq!(
+    result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+    "users.proto"
+  );
+  assert_eq!(
+    result["locations"][0]["physicalLocation"]["region"]["startLine"],
+    2
+  );
+}
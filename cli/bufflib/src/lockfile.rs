@@ -0,0 +1,91 @@
+use crate::workspace;
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LOCKFILE_HEADER: &str =
+  "# This file is generated by buff, it is not meant to be edited by hand.\n";
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Lockfile {
+  #[serde(default, rename = "package")]
+  pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LockedPackage {
+  pub name: String,
+  pub version: String,
+  pub registry: String,
+}
+
+/// The lockfile lives next to the manifest it locks, or at the workspace root
+/// for workspace members.
+pub fn lockfile_path(manifest_path: &Path) -> PathBuf {
+  match workspace::find_workspace(manifest_path) {
+    Some(workspace) => workspace.lockfile_path(),
+    None => manifest_path.with_file_name("buff.lock"),
+  }
+}
+
+impl Lockfile {
+  /// Loads the lockfile at `path`, or an empty one if there isn't any yet.
+  pub fn load(path: &Path) -> Self {
+    if !path.exists() {
+      return Lockfile::default();
+    }
+    let toml_content = fs::read_to_string(path).expect("Failed to read buff.lock");
+    toml::from_str(&toml_content).expect("Failed to parse buff.lock")
+  }
+
+  pub fn save(&self, path: &Path) {
+    let toml_content = format!("{}\n{}", LOCKFILE_HEADER, toml::to_string(self).unwrap());
+    fs::write(path, toml_content).expect("Failed to save buff.lock");
+  }
+
+  pub fn get(&self, name: &str) -> Option<&LockedPackage> {
+    self.packages.iter().find(|package| package.name == name)
+  }
+
+  pub fn lock(&mut self, package: LockedPackage) {
+    self.unlock(&package.name);
+    self.packages.push(package);
+    self.packages.sort_by(|a, b| a.name.cmp(&b.name));
+  }
+
+  pub fn unlock(&mut self, name: &str) -> bool {
+    let count = self.packages.len();
+    self.packages.retain(|package| package.name != name);
+    self.packages.len() != count
+  }
+}
+
+#[test]
+fn should_save_and_load() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let path = tmp_dir.path().join("buff.lock");
+  let mut lockfile = Lockfile::load(&path);
+  assert!(lockfile.packages.is_empty());
+  lockfile.lock(LockedPackage {
+    name: "zesty".to_string(),
+    version: "2.1.0".to_string(),
+    registry: "localhost:50051".to_string(),
+  });
+  lockfile.lock(LockedPackage {
+    name: "yummy_package".to_string(),
+    version: "1.0.3".to_string(),
+    registry: "localhost:50051".to_string(),
+  });
+  lockfile.lock(LockedPackage {
+    name: "zesty".to_string(),
+    version: "2.2.0".to_string(),
+    registry: "localhost:50051".to_string(),
+  });
+  lockfile.save(&path);
+  let mut lockfile = Lockfile::load(&path);
+  let names: Vec<&str> = lockfile.packages.iter().map(|p| p.name.as_str()).collect();
+  assert_eq!(names, ["yummy_package", "zesty"]);
+  assert_eq!(lockfile.get("zesty").unwrap().version, "2.2.0");
+  assert!(lockfile.unlock("zesty"));
+  assert!(!lockfile.unlock("zesty"));
+}
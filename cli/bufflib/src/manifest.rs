@@ -0,0 +1,118 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE_NAME: &str = "buff.toml";
+
+/// Finds the buff.toml to operate on. An explicit `manifest_path` (either the
+/// file or its directory) wins, then the directory in `BUFF_TARGET_PATH`, and
+/// otherwise the nearest buff.toml in the current directory or its parents.
+pub fn find_manifest(manifest_path: Option<&Path>) -> PathBuf {
+  if let Some(path) = manifest_path {
+    let path = if path.is_dir() {
+      path.join(MANIFEST_FILE_NAME)
+    } else {
+      path.to_path_buf()
+    };
+    if !path.is_file() {
+      panic!("Manifest `{}` does not exist", path.display());
+    }
+    return path;
+  }
+  if let Ok(target_path) = env::var("BUFF_TARGET_PATH") {
+    return Path::new(&target_path).join(MANIFEST_FILE_NAME);
+  }
+  let current_dir = env::current_dir().unwrap();
+  discover_manifest(&current_dir).unwrap_or_else(|| {
+    panic!(
+      "Could not find {} in `{}` or any parent directory",
+      MANIFEST_FILE_NAME,
+      current_dir.display()
+    )
+  })
+}
+
+/// Walks up from `start` looking for a buff.toml, without crossing into
+/// another filesystem. Every workspace root has a buff.toml, so the walk
+/// never leaves the workspace `start` is in: a member directory without a
+/// buff.toml resolves to the workspace root, not to a manifest above it.
+pub fn discover_manifest(start: &Path) -> Option<PathBuf> {
+  let device = device_id(start);
+  for dir in start.ancestors() {
+    if device_id(dir) != device {
+      break;
+    }
+    let candidate = dir.join(MANIFEST_FILE_NAME);
+    if candidate.is_file() {
+      return Some(candidate);
+    }
+  }
+  None
+}
+
+/// The directory holding the package described by `manifest_path`.
+pub fn package_dir(manifest_path: &Path) -> PathBuf {
+  match manifest_path.parent() {
+    Some(dir) if dir.as_os_str().is_empty() => PathBuf::from("."),
+    Some(dir) => dir.to_path_buf(),
+    None => PathBuf::from("."),
+  }
+}
+
+#[cfg(unix)]
+fn device_id(path: &Path) -> Option<u64> {
+  use std::os::unix::fs::MetadataExt;
+  fs::metadata(path).ok().map(|metadata| metadata.dev())
+}
+
+#[cfg(not(unix))]
+fn device_id(path: &Path) -> Option<u64> {
+  fs::metadata(path).ok().map(|_| 0)
+}
+
+#[test]
+fn should_discover_manifest_in_parent() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let nested = tmp_dir.path().join("protos/acme/v1");
+  fs::create_dir_all(&nested).unwrap();
+  fs::write(tmp_dir.path().join(MANIFEST_FILE_NAME), "").unwrap();
+  assert_eq!(
+    discover_manifest(&nested),
+    Some(tmp_dir.path().join(MANIFEST_FILE_NAME))
+  );
+  assert_eq!(
+    package_dir(&discover_manifest(&nested).unwrap()),
+    tmp_dir.path()
+  );
+}
+
+#[test]
+fn should_stop_discovery_at_workspace_root() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let workspace_root = tmp_dir.path().join("workspace");
+  let member_protos = workspace_root.join("billing/protos");
+  fs::create_dir_all(&member_protos).unwrap();
+  fs::write(
+    tmp_dir.path().join(MANIFEST_FILE_NAME),
+    "[package]\nname = \"unrelated\"\n",
+  )
+  .unwrap();
+  fs::write(
+    workspace_root.join(MANIFEST_FILE_NAME),
+    "[workspace]\nmembers = [\"billing\"]\n",
+  )
+  .unwrap();
+  assert_eq!(
+    discover_manifest(&member_protos),
+    Some(workspace_root.join(MANIFEST_FILE_NAME))
+  );
+}
+
+#[test]
+fn should_find_manifest_from_dir() {
+  let fixture = Path::new("../tests/fixtures/test_artifact");
+  assert_eq!(
+    find_manifest(Some(fixture)),
+    fixture.join(MANIFEST_FILE_NAME)
+  );
+}
@@ -1,29 +1,234 @@
+use crate::codec::Codec;
+use crate::compat::Compatibility;
+use crate::fmt::FmtConfig;
+use crate::lint::LintConfig;
 use serde_derive::Deserialize;
 use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{table, value, Document, InlineTable};
 
 #[derive(Deserialize)]
 pub struct PackageMetadata {
-  package: Package,
+  pub package: Package,
+  #[serde(default)]
+  pub dependencies: HashMap<String, Dependency>,
+  #[serde(default)]
+  pub publish: PublishConfig,
+  #[serde(default)]
+  pub compatibility: Compatibility,
+  #[serde(default)]
+  pub lint: LintConfig,
+  #[serde(default)]
+  pub fmt: FmtConfig,
 }
 
 #[derive(Deserialize)]
-struct Package {
-  name: String,
-  description: String,
-  keywords: Vec<String>,
-  homepage: String,
-  repository_url: String,
+pub struct Package {
+  pub name: String,
+  pub version: String,
+  pub description: String,
+  pub keywords: Vec<String>,
+  pub homepage: String,
+  pub repository_url: String,
+  /// Codec for the artifact, e.g. `zstd:19`, gzip by default.
+  #[serde(default)]
+  pub compression: Option<Codec>,
+}
+
+/// The `[publish]` section, toggling the checks `buff publish` runs before
+/// uploading. Every check is on by default except `remote` and `tag`.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct PublishConfig {
+  /// Required fields are filled in and versions are valid semver.
+  pub manifest: bool,
+  /// Every .proto file in the artifact parses, and the ones that only import
+  /// files in the package compile.
+  pub protos: bool,
+  /// Imports point at files in the package or in declared dependencies.
+  pub imports: bool,
+  pub lint: bool,
+  /// No breaking changes against the latest published version, unless the
+  /// version is a major bump over it.
+  pub breaking: bool,
+  pub size: bool,
+  /// Largest artifact, in bytes, the size check lets through.
+  pub max_size: usize,
+  /// The commit being published is on a branch of the remote
+  /// `repository_url` points at.
+  pub remote: bool,
+  /// Tag the commit `<name>-v<version>` once the package is published.
+  pub tag: bool,
+}
+
+impl Default for PublishConfig {
+  fn default() -> Self {
+    PublishConfig {
+      manifest: true,
+      protos: true,
+      imports: true,
+      lint: true,
+      breaking: true,
+      size: true,
+      max_size: 10 * 1024 * 1024,
+      remote: false,
+      tag: false,
+    }
+  }
+}
+
+/// A dependency is either a plain version requirement, `foo = "1.0"`, or a
+/// table that also names the registry it comes from. In a workspace member
+/// the table can be `{ workspace = true }` to use the version declared in
+/// `[workspace.dependencies]`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Dependency {
+  Simple(String),
+  Detailed(DependencyDetail),
+}
+
+#[derive(Deserialize)]
+pub struct DependencyDetail {
+  pub version: Option<String>,
+  pub registry: Option<String>,
+  #[serde(default)]
+  pub workspace: bool,
+}
+
+impl Dependency {
+  /// The version requirement, `None` when it's inherited from the workspace.
+  pub fn version(&self) -> Option<&str> {
+    match self {
+      Dependency::Simple(version) => Some(version),
+      Dependency::Detailed(detail) => detail.version.as_ref().map(String::as_str),
+    }
+  }
+
+  pub fn registry(&self) -> Option<&str> {
+    match self {
+      Dependency::Simple(_) => None,
+      Dependency::Detailed(detail) => detail.registry.as_ref().map(String::as_str),
+    }
+  }
+
+  pub fn is_workspace(&self) -> bool {
+    match self {
+      Dependency::Simple(_) => false,
+      Dependency::Detailed(detail) => detail.workspace,
+    }
+  }
+}
+
+pub fn is_valid_package_name(name: &str) -> bool {
+  !name.is_empty()
+    && name
+      .chars()
+      .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
 }
 
 impl PackageMetadata {
   pub fn new(path: &str) -> Self {
     let toml_content = fs::read_to_string(path).unwrap();
-    let package_metadata: PackageMetadata = toml::from_str(&toml_content).unwrap();
+    PackageMetadata::parse(&toml_content)
+  }
+
+  pub fn parse(toml_content: &str) -> Self {
+    let package_metadata: PackageMetadata = toml::from_str(toml_content).unwrap();
     return package_metadata;
   }
 }
 
+/// A buff.toml opened for editing. Unlike `PackageMetadata` it keeps the
+/// comments, ordering and whitespace of everything it doesn't touch.
+pub struct ManifestDocument {
+  path: PathBuf,
+  document: Document,
+}
+
+impl ManifestDocument {
+  pub fn open(path: &Path) -> Self {
+    let toml_content = fs::read_to_string(path).expect("Failed to read buff.toml");
+    let document = toml_content
+      .parse::<Document>()
+      .expect("Failed to parse buff.toml");
+    ManifestDocument {
+      path: path.to_path_buf(),
+      document,
+    }
+  }
+
+  pub fn set_dependency(&mut self, name: &str, requirement: &str, registry: Option<&str>) {
+    let dependencies = self
+      .document
+      .as_table_mut()
+      .entry("dependencies")
+      .or_insert(table())
+      .as_table_mut()
+      .expect("[dependencies] in buff.toml must be a table");
+    let mut dependency = match registry {
+      None => value(requirement),
+      Some(registry) => {
+        let mut detail = InlineTable::new();
+        detail.get_or_insert("version", requirement);
+        detail.get_or_insert("registry", registry);
+        value(detail)
+      }
+    };
+    match dependencies.get_mut(name) {
+      Some(existing) => {
+        // Carry over the decor so a trailing comment on the
+        // replaced line survives.
+        if let (Some(old), Some(new)) = (existing.as_value(), dependency.as_value_mut()) {
+          *new.decor_mut() = old.decor().clone();
+        }
+        *existing = dependency;
+      }
+      None => {
+        let keys: Vec<&str> = dependencies.iter().map(|(key, _)| key).collect();
+        let was_sorted = keys.windows(2).all(|pair| pair[0] <= pair[1]);
+        dependencies.insert(name, dependency);
+        if was_sorted {
+          dependencies.sort_values();
+        }
+      }
+    }
+  }
+
+  /// Sets `version` in `[package]`, keeping any comment on its line.
+  pub fn set_version(&mut self, version: &str) {
+    let existing = self
+      .document
+      .as_table_mut()
+      .get_mut("package")
+      .and_then(|package| package.as_table_mut())
+      .and_then(|package| package.get_mut("version"))
+      .expect("buff.toml has no version in [package]");
+    let mut replacement = value(version);
+    if let (Some(old), Some(new)) = (existing.as_value(), replacement.as_value_mut()) {
+      *new.decor_mut() = old.decor().clone();
+    }
+    *existing = replacement;
+  }
+
+  pub fn remove_dependency(&mut self, name: &str) -> bool {
+    match self
+      .document
+      .as_table_mut()
+      .get_mut("dependencies")
+      .and_then(|item| item.as_table_mut())
+    {
+      Some(dependencies) => dependencies.remove(name).is_some(),
+      None => false,
+    }
+  }
+
+  pub fn save(&self) {
+    fs::write(&self.path, self.document.to_string()).expect("Failed to save buff.toml");
+  }
+}
+
 #[test]
 fn should_new() {
   let path = std::env::current_dir()
@@ -34,6 +239,7 @@ fn should_new() {
     .to_owned();
   let package_metadata = PackageMetadata::new(&path);
   assert_eq!(package_metadata.package.name, "test_package");
+  assert_eq!(package_metadata.package.version, "0.1.0");
   assert_eq!(
     package_metadata.package.description,
     "test_package description"
@@ -41,4 +247,47 @@ fn should_new() {
   assert_eq!(package_metadata.package.homepage, "https://example.com");
   assert_eq!(package_metadata.package.repository_url, "https://repo.com");
   assert_eq!(package_metadata.package.keywords, ["awesome", "great"]);
+  assert_eq!(
+    package_metadata.dependencies["yummy_package"].version(),
+    Some("1.0")
+  );
+}
+
+#[test]
+fn should_edit_dependencies_preserving_format() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let path = tmp_dir.path().join("buff.toml");
+  fs::write(
+    &path,
+    "# Our package\n[package]\nname = \"a\"\n\n[dependencies]\n# needed for food\nyummy_package = \"1.0\" # pinned\nzesty = \"2.1\"\n",
+  )
+  .unwrap();
+  let mut manifest = ManifestDocument::open(&path);
+  manifest.set_dependency("bland", "0.3", Some("localhost:50052"));
+  manifest.set_dependency("yummy_package", "1.2", None);
+  assert!(manifest.remove_dependency("zesty"));
+  assert!(!manifest.remove_dependency("missing"));
+  manifest.save();
+  assert_eq!(
+    fs::read_to_string(&path).unwrap(),
+    "# Our package\n[package]\nname = \"a\"\n\n[dependencies]\nbland = { version = \"0.3\", registry = \"localhost:50052\" }\n# needed for food\nyummy_package = \"1.2\" # pinned\n"
+  );
+}
+
+#[test]
+fn should_set_version_preserving_format() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let path = tmp_dir.path().join("buff.toml");
+  fs::write(
+    &path,
+    "[package]\nname = \"a\"\nversion   =   \"0.1.0\" # released monthly\ndescription = \"\"\n",
+  )
+  .unwrap();
+  let mut manifest = ManifestDocument::open(&path);
+  manifest.set_version("0.2.0");
+  manifest.save();
+  assert_eq!(
+    fs::read_to_string(&path).unwrap(),
+    "[package]\nname = \"a\"\nversion   =   \"0.2.0\" # released monthly\ndescription = \"\"\n"
+  );
 }
@@ -0,0 +1,1760 @@
+use ignore::Walk;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+  Word(String),
+  Str(String),
+  Symbol(char),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+  /// The file the error is in, when known.
+  pub file: Option<PathBuf>,
+  pub line: usize,
+  pub column: usize,
+  pub message: String,
+}
+
+impl ParseError {
+  /// The error, pointing into the file at `path`.
+  pub fn in_file(self, path: &Path) -> Self {
+    ParseError {
+      file: Some(path.to_path_buf()),
+      ..self
+    }
+  }
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if let Some(ref file) = self.file {
+      write!(f, "{}:", file.display())?;
+    }
+    write!(f, "{}:{}: {}", self.line, self.column, self.message)
+  }
+}
+
+/// A line and column in a .proto file, both starting at 1.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+  pub line: usize,
+  pub column: usize,
+}
+
+/// The text from `start` up to, but not including, `end`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+  pub start: Position,
+  pub end: Position,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+  pub span: Span,
+  /// The comment as written, including the `//` or `/* */`.
+  pub text: String,
+}
+
+impl Comment {
+  /// The text without the comment markers, the way protoc reports it: what
+  /// follows `//` up to and including the end of the line, or what's between
+  /// `/*` and `*/` with the leading `*` of each line dropped.
+  pub fn content(&self) -> String {
+    if self.text.starts_with("//") {
+      return format!("{}\n", &self.text[2..]);
+    }
+    let inner = &self.text[2..self.text.len() - 2];
+    let lines: Vec<&str> = inner
+      .split('\n')
+      .enumerate()
+      .map(|(i, line)| {
+        let trimmed = line.trim_start();
+        if i > 0 && trimmed.starts_with('*') {
+          &trimmed[1..]
+        } else {
+          line
+        }
+      })
+      .collect();
+    lines.join("\n")
+  }
+
+  fn is_directive(&self) -> bool {
+    self.text.starts_with("//")
+      && self
+        .text
+        .trim_start_matches('/')
+        .trim()
+        .starts_with(IGNORE_DIRECTIVE)
+  }
+}
+
+/// Where an element is declared and the comments documenting it. Neither
+/// is part of the schema, `same_definitions` compares files without them.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Location {
+  pub span: Span,
+  /// The comments right above the element, with no blank line in between.
+  pub leading_comments: Option<String>,
+  /// The comment following the element on the same line, or following the
+  /// `{` opening its body.
+  pub trailing_comments: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportKind {
+  Default,
+  Public,
+  Weak,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+  pub path: String,
+  pub kind: ImportKind,
+  pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtoOption {
+  pub name: String,
+  /// The value as written, with string constants quoted again.
+  pub value: String,
+  pub location: Location,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Label {
+  None,
+  Optional,
+  Required,
+  Repeated,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+  pub name: String,
+  pub number: i32,
+  pub label: Label,
+  /// The type as written, e.g. `string`, `.acme.Money` or `map<string, Item>`.
+  pub type_name: String,
+  /// The oneof the field belongs to, if any.
+  pub oneof: Option<String>,
+  /// Whether the field is a group, whose type is the nested message of the
+  /// same name.
+  pub group: bool,
+  pub options: Vec<ProtoOption>,
+  pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Oneof {
+  pub name: String,
+  pub options: Vec<ProtoOption>,
+  pub location: Location,
+}
+
+/// Field or value numbers and names taken out of use by `reserved`. Ranges
+/// are inclusive, `max` is stored as `i32::max_value()`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Reserved {
+  pub ranges: Vec<(i32, i32)>,
+  pub names: Vec<String>,
+}
+
+impl Reserved {
+  pub fn contains_number(&self, number: i32) -> bool {
+    self
+      .ranges
+      .iter()
+      .any(|(start, end)| *start <= number && number <= *end)
+  }
+
+  pub fn contains_name(&self, name: &str) -> bool {
+    self.names.iter().any(|n| n == name)
+  }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Message {
+  pub name: String,
+  pub fields: Vec<Field>,
+  pub oneofs: Vec<Oneof>,
+  pub messages: Vec<Message>,
+  pub enums: Vec<Enum>,
+  pub extends: Vec<Extend>,
+  /// Field numbers set aside for extensions, inclusive like reserved ones.
+  pub extensions: Vec<(i32, i32)>,
+  pub reserved: Reserved,
+  pub options: Vec<ProtoOption>,
+  pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumValue {
+  pub name: String,
+  pub number: i32,
+  pub options: Vec<ProtoOption>,
+  pub location: Location,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Enum {
+  pub name: String,
+  pub values: Vec<EnumValue>,
+  pub reserved: Reserved,
+  pub options: Vec<ProtoOption>,
+  pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rpc {
+  pub name: String,
+  pub input_type: String,
+  pub output_type: String,
+  pub client_streaming: bool,
+  pub server_streaming: bool,
+  pub options: Vec<ProtoOption>,
+  pub location: Location,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Service {
+  pub name: String,
+  pub rpcs: Vec<Rpc>,
+  pub options: Vec<ProtoOption>,
+  pub location: Location,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extend {
+  pub extendee: String,
+  pub fields: Vec<Field>,
+  pub location: Location,
+}
+
+/// A `// buff:ignore RULE...` comment, suppressing compatibility rules for
+/// the element declared on the same line or, failing that, the message, enum
+/// or service it's in. Outside of those it's for the next definition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ignore {
+  pub rules: Vec<String>,
+  /// Fully-qualified name of the element, e.g. `acme.v1.User`, or `None`
+  /// when no definition follows the comment.
+  pub path: Option<String>,
+  pub line: usize,
+}
+
+/// Where an element is declared, e.g. for linters to point at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+  /// Fully-qualified name of the element, e.g. `acme.v1.User.name`.
+  pub path: String,
+  pub line: usize,
+  /// Whether a comment ends on the line above or starts on the same line,
+  /// not counting `buff:ignore` ones.
+  pub documented: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ProtoFile {
+  pub syntax: Option<String>,
+  pub package: Option<String>,
+  pub imports: Vec<Import>,
+  pub options: Vec<ProtoOption>,
+  pub messages: Vec<Message>,
+  pub enums: Vec<Enum>,
+  pub services: Vec<Service>,
+  pub extends: Vec<Extend>,
+  pub ignores: Vec<Ignore>,
+  pub declarations: Vec<Declaration>,
+  /// Every comment in the file, in order.
+  pub comments: Vec<Comment>,
+}
+
+/// A parsed .proto file together with its path relative to the package root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceFile {
+  pub path: PathBuf,
+  pub file: ProtoFile,
+}
+
+const IGNORE_DIRECTIVE: &str = "buff:ignore";
+
+struct Lexer<'a> {
+  chars: std::iter::Peekable<std::str::Chars<'a>>,
+  line: usize,
+  column: usize,
+  /// Rules named in `// buff:ignore` comments, by line.
+  directives: Vec<(usize, Vec<String>)>,
+  comments: Vec<Comment>,
+}
+
+impl<'a> Lexer<'a> {
+  fn new(source: &'a str) -> Self {
+    Lexer {
+      chars: source.chars().peekable(),
+      line: 1,
+      column: 1,
+      directives: Vec::new(),
+      comments: Vec::new(),
+    }
+  }
+
+  fn bump(&mut self) -> Option<char> {
+    let c = self.chars.next()?;
+    if c == '\n' {
+      self.line += 1;
+      self.column = 1;
+    } else {
+      self.column += 1;
+    }
+    Some(c)
+  }
+
+  fn position(&self) -> Position {
+    Position {
+      line: self.line,
+      column: self.column,
+    }
+  }
+
+  fn error(&self, message: &str) -> ParseError {
+    ParseError {
+      file: None,
+      line: self.line,
+      column: self.column,
+      message: message.to_string(),
+    }
+  }
+
+  fn skip_trivia(&mut self) -> Result<(), ParseError> {
+    loop {
+      match self.chars.peek() {
+        Some(c) if c.is_whitespace() => {
+          self.bump();
+        }
+        Some('/') => {
+          let mut lookahead = self.chars.clone();
+          lookahead.next();
+          match lookahead.next() {
+            Some('/') => {
+              let start = self.position();
+              let mut text = String::new();
+              while let Some(&c) = self.chars.peek() {
+                if c == '\n' {
+                  break;
+                }
+                text.push(c);
+                self.bump();
+              }
+              let text = text.trim_end().to_string();
+              let comment = Comment {
+                span: Span {
+                  start,
+                  end: self.position(),
+                },
+                text,
+              };
+              if comment.is_directive() {
+                let rules = comment.text.trim_start_matches('/').trim()[IGNORE_DIRECTIVE.len()..]
+                  .split(|c: char| c == ',' || c.is_whitespace())
+                  .filter(|rule| !rule.is_empty())
+                  .map(String::from)
+                  .collect();
+                self.directives.push((start.line, rules));
+              }
+              self.comments.push(comment);
+            }
+            Some('*') => {
+              let start = self.position();
+              let mut text = String::from("/*");
+              self.bump();
+              self.bump();
+              let mut previous = ' ';
+              loop {
+                match self.bump() {
+                  Some('/') if previous == '*' => break,
+                  Some(c) => {
+                    text.push(c);
+                    previous = c;
+                  }
+                  None => return Err(self.error("unterminated block comment")),
+                }
+              }
+              text.push('/');
+              self.comments.push(Comment {
+                span: Span {
+                  start,
+                  end: self.position(),
+                },
+                text,
+              });
+            }
+            _ => return Ok(()),
+          }
+        }
+        _ => return Ok(()),
+      }
+    }
+  }
+
+  fn next_token(&mut self) -> Result<Option<(Token, usize, usize)>, ParseError> {
+    self.skip_trivia()?;
+    let (line, column) = (self.line, self.column);
+    let c = match self.chars.peek() {
+      Some(c) => *c,
+      None => return Ok(None),
+    };
+    let token = if c == '"' || c == '\'' {
+      self.bump();
+      let mut value = String::new();
+      loop {
+        match self.bump() {
+          Some('\\') => match self.bump() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some(escaped) => value.push(escaped),
+            None => return Err(self.error("unterminated string literal")),
+          },
+          Some('\n') | None => return Err(self.error("unterminated string literal")),
+          Some(q) if q == c => break,
+          Some(other) => value.push(other),
+        }
+      }
+      Token::Str(value)
+    } else if c.is_alphanumeric() || c == '_' || c == '.' {
+      let mut word = String::new();
+      while let Some(&c) = self.chars.peek() {
+        if c.is_alphanumeric() || c == '_' || c == '.' {
+          word.push(c);
+          self.bump();
+        } else {
+          break;
+        }
+      }
+      Token::Word(word)
+    } else {
+      self.bump();
+      Token::Symbol(c)
+    };
+    Ok(Some((token, line, column)))
+  }
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<(Token, usize, usize)>, ParseError> {
+  Ok(lex(source)?.tokens)
+}
+
+/// Every token with its span, and every comment, for rewriting a file
+/// without losing any of it.
+pub fn tokens_and_comments(source: &str) -> Result<(Vec<(Token, Span)>, Vec<Comment>), ParseError> {
+  let lexed = lex(source)?;
+  let tokens = lexed
+    .tokens
+    .into_iter()
+    .zip(lexed.ends)
+    .map(|((token, line, column), end)| {
+      let start = Position { line, column };
+      (token, Span { start, end })
+    })
+    .collect();
+  Ok((tokens, lexed.comments))
+}
+
+struct Lexed {
+  tokens: Vec<(Token, usize, usize)>,
+  /// Where each token ends.
+  ends: Vec<Position>,
+  directives: Vec<(usize, Vec<String>)>,
+  comments: Vec<Comment>,
+}
+
+fn lex(source: &str) -> Result<Lexed, ParseError> {
+  let mut lexer = Lexer::new(source);
+  let mut tokens = Vec::new();
+  let mut ends = Vec::new();
+  while let Some(token) = lexer.next_token()? {
+    tokens.push(token);
+    ends.push(lexer.position());
+  }
+  Ok(Lexed {
+    tokens,
+    ends,
+    directives: lexer.directives,
+    comments: lexer.comments,
+  })
+}
+
+struct Parser {
+  tokens: Vec<(Token, usize, usize)>,
+  ends: Vec<Position>,
+  /// The comments other than `buff:ignore` ones, to document elements with.
+  comments: Vec<Comment>,
+  position: usize,
+  /// Fully-qualified name of the message, enum or service being parsed.
+  scope: String,
+  /// The elements declared so far with the line their statement starts on,
+  /// to attach `buff:ignore` comments to.
+  declarations: Vec<(String, usize)>,
+  /// The messages, enums and services parsed so far with the first and last
+  /// line of their bodies, for the same.
+  bodies: Vec<(String, usize, usize)>,
+}
+
+impl Parser {
+  fn line(&self) -> usize {
+    self
+      .tokens
+      .get(self.position)
+      .map_or(0, |(_, line, _)| *line)
+  }
+
+  /// Records that the statement starting on `line` declares `name` in the
+  /// current scope, or is about the scope itself when `name` is `None`.
+  fn declare(&mut self, name: Option<&str>, line: usize) {
+    let path = match name {
+      Some(name) => qualify(&self.scope, name),
+      None => self.scope.clone(),
+    };
+    self.declarations.push((path, line));
+  }
+
+  /// Runs `parse` with the scope set to `name` inside the current one.
+  fn in_scope<T, F>(&mut self, name: &str, parse: F) -> Result<T, ParseError>
+  where
+    F: FnOnce(&mut Self) -> Result<T, ParseError>,
+  {
+    let outer = self.scope.clone();
+    if !self.scope.is_empty() {
+      self.scope.push('.');
+    }
+    self.scope.push_str(name);
+    let first_line = self.line();
+    let parsed = parse(self);
+    let last_line = self.ends[self.position - 1].line;
+    self
+      .bodies
+      .push((self.scope.clone(), first_line, last_line));
+    self.scope = outer;
+    parsed
+  }
+
+  /// Attaches each directive to the element declared on its line or, if
+  /// there's none, the innermost message, enum or service around it, so a
+  /// comment left where a field was removed covers its message. Directives
+  /// outside of any definition are for the next one, and never for the whole
+  /// file or package.
+  fn attach(&self, directives: Vec<(usize, Vec<String>)>) -> Vec<Ignore> {
+    directives
+      .into_iter()
+      .map(|(line, rules)| {
+        let on_line = self
+          .declarations
+          .iter()
+          .find(|(_, declared)| *declared == line)
+          .map(|(path, _)| path);
+        let enclosing = self
+          .bodies
+          .iter()
+          .filter(|(_, first, last)| *first <= line && line <= *last)
+          .min_by_key(|(_, first, last)| last - first)
+          .map(|(path, _, _)| path);
+        let next = self
+          .declarations
+          .iter()
+          .filter(|(_, declared)| *declared > line)
+          .min_by_key(|(_, declared)| *declared)
+          .map(|(path, _)| path);
+        let path = on_line.or(enclosing).or(next).cloned();
+        Ignore { rules, path, line }
+      })
+      .collect()
+  }
+
+  /// The element each statement declared, the first time it's declared.
+  fn describe(&self) -> Vec<Declaration> {
+    let mut declarations: Vec<Declaration> = Vec::new();
+    for (path, line) in &self.declarations {
+      if path.is_empty() || declarations.iter().any(|seen| seen.path == *path) {
+        continue;
+      }
+      declarations.push(Declaration {
+        path: path.clone(),
+        line: *line,
+        documented: self
+          .comments
+          .iter()
+          .any(|comment| comment.span.end.line + 1 == *line || comment.span.start.line == *line),
+      });
+    }
+    declarations
+  }
+
+  /// The location of the element whose statement starts with the token at
+  /// `start` and ends with the last one consumed.
+  fn locate(&self, start: usize) -> Location {
+    let (_, line, column) = self.tokens[start];
+    let span = Span {
+      start: Position { line, column },
+      end: self.ends[self.position - 1],
+    };
+    // Leading comments have to start on a line after the previous
+    // token, or they'd be trailing that token's statement instead.
+    let after = if start == 0 {
+      Position::default()
+    } else {
+      self.ends[start - 1]
+    };
+    let mut leading: Vec<&Comment> = Vec::new();
+    let mut next_line = line;
+    for comment in self.comments.iter().rev() {
+      if comment.span.start >= span.start || comment.span.end > span.start {
+        continue;
+      }
+      if comment.span.start.line <= after.line || comment.span.end.line + 1 < next_line {
+        break;
+      }
+      next_line = comment.span.start.line;
+      leading.push(comment);
+    }
+    leading.reverse();
+    let leading_comments = if leading.is_empty() {
+      None
+    } else {
+      Some(leading.iter().map(|comment| comment.content()).collect())
+    };
+    let header = self.header_end(start);
+    let trailing_comments = self
+      .comments
+      .iter()
+      .find(|comment| {
+        comment.span.start >= header
+          && comment.span.start.line == header.line
+          && self
+            .tokens
+            .iter()
+            .all(|(_, line, column)| comment.span.start.line != *line || *column < header.column)
+      })
+      .map(Comment::content);
+    Location {
+      span,
+      leading_comments,
+      trailing_comments,
+    }
+  }
+
+  /// Where the head of the statement starting at token `start` ends: right
+  /// after its `;`, or the `{` opening its body.
+  fn header_end(&self, start: usize) -> Position {
+    let mut depth = 0;
+    for i in start..self.position {
+      match self.tokens[i].0 {
+        Token::Symbol('[') => depth += 1,
+        Token::Symbol(']') => depth -= 1,
+        Token::Symbol(';') | Token::Symbol('{') if depth == 0 => return self.ends[i],
+        _ => (),
+      }
+    }
+    self.ends[self.position - 1]
+  }
+
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.position).map(|(token, _, _)| token)
+  }
+
+  fn peek_at(&self, offset: usize) -> Option<&Token> {
+    self
+      .tokens
+      .get(self.position + offset)
+      .map(|(token, _, _)| token)
+  }
+
+  fn error(&self, message: &str) -> ParseError {
+    let (line, column) = self
+      .tokens
+      .get(self.position)
+      .or_else(|| self.tokens.last())
+      .map(|(_, line, column)| (*line, *column))
+      .unwrap_or((1, 1));
+    ParseError {
+      file: None,
+      line,
+      column,
+      message: message.to_string(),
+    }
+  }
+
+  fn next(&mut self) -> Result<Token, ParseError> {
+    match self.tokens.get(self.position) {
+      Some((token, _, _)) => {
+        self.position += 1;
+        Ok(token.clone())
+      }
+      None => Err(self.error("unexpected end of file")),
+    }
+  }
+
+  fn is_symbol(&self, symbol: char) -> bool {
+    self.peek() == Some(&Token::Symbol(symbol))
+  }
+
+  fn is_word(&self, word: &str) -> bool {
+    match self.peek() {
+      Some(Token::Word(w)) => w == word,
+      _ => false,
+    }
+  }
+
+  fn eat_symbol(&mut self, symbol: char) -> bool {
+    if self.is_symbol(symbol) {
+      self.position += 1;
+      true
+    } else {
+      false
+    }
+  }
+
+  fn eat_word(&mut self, word: &str) -> bool {
+    if self.is_word(word) {
+      self.position += 1;
+      true
+    } else {
+      false
+    }
+  }
+
+  fn expect_symbol(&mut self, symbol: char) -> Result<(), ParseError> {
+    if self.eat_symbol(symbol) {
+      Ok(())
+    } else {
+      Err(self.error(&format!("expected `{}`", symbol)))
+    }
+  }
+
+  fn expect_word(&mut self, what: &str) -> Result<String, ParseError> {
+    match self.peek() {
+      Some(Token::Word(word)) => {
+        let word = word.clone();
+        self.position += 1;
+        Ok(word)
+      }
+      _ => Err(self.error(&format!("expected {}", what))),
+    }
+  }
+
+  fn expect_string(&mut self, what: &str) -> Result<String, ParseError> {
+    match self.peek() {
+      Some(Token::Str(value)) => {
+        let value = value.clone();
+        self.position += 1;
+        Ok(value)
+      }
+      _ => Err(self.error(&format!("expected {}", what))),
+    }
+  }
+
+  fn expect_integer(&mut self) -> Result<i32, ParseError> {
+    let negative = self.eat_symbol('-');
+    let word = self.expect_word("a number")?;
+    let parsed = if word.starts_with("0x") || word.starts_with("0X") {
+      i64::from_str_radix(&word[2..], 16)
+    } else if word.len() > 1 && word.starts_with('0') {
+      i64::from_str_radix(&word[1..], 8)
+    } else {
+      word.parse::<i64>()
+    };
+    let value = parsed.map_err(|_| {
+      self.position -= 1;
+      self.error(&format!("expected a number, found `{}`", word))
+    })?;
+    let value = if negative { -value } else { value };
+    if value < i64::from(i32::min_value()) || value > i64::from(i32::max_value()) {
+      self.position -= 1;
+      return Err(self.error(&format!("number `{}` is out of range", word)));
+    }
+    Ok(value as i32)
+  }
+
+  fn parse_file(&mut self) -> Result<ProtoFile, ParseError> {
+    let mut file = ProtoFile::default();
+    while self.peek().is_some() {
+      if self.eat_symbol(';') {
+        continue;
+      }
+      let start = self.position;
+      let line = self.line();
+      let keyword = self.expect_word("a top-level statement")?;
+      if let Some(Token::Word(name)) = self.peek() {
+        if ["message", "enum", "service"].contains(&keyword.as_str()) {
+          let name = name.clone();
+          self.declare(Some(&name), line);
+        }
+      }
+      match keyword.as_str() {
+        "syntax" => {
+          self.expect_symbol('=')?;
+          file.syntax = Some(self.expect_string("a syntax version")?);
+          self.expect_symbol(';')?;
+        }
+        "package" => {
+          let package = self.expect_word("a package name")?;
+          self.scope = package.clone();
+          file.package = Some(package);
+          self.expect_symbol(';')?;
+        }
+        "import" => {
+          let kind = if self.eat_word("public") {
+            ImportKind::Public
+          } else if self.eat_word("weak") {
+            ImportKind::Weak
+          } else {
+            ImportKind::Default
+          };
+          let path = self.expect_string("an import path")?;
+          self.expect_symbol(';')?;
+          file.imports.push(Import {
+            path,
+            kind,
+            location: self.locate(start),
+          });
+        }
+        "option" => file.options.push(self.parse_option_statement()?),
+        "message" => file.messages.push(self.parse_message(start)?),
+        "enum" => file.enums.push(self.parse_enum(start)?),
+        "service" => file.services.push(self.parse_service(start)?),
+        "extend" => file.extends.push(self.parse_extend(start)?),
+        _ => {
+          self.position -= 1;
+          return Err(self.error(&format!("unexpected `{}`", keyword)));
+        }
+      }
+    }
+    Ok(file)
+  }
+
+  fn parse_option_name(&mut self) -> Result<String, ParseError> {
+    let mut name = String::new();
+    loop {
+      if self.eat_symbol('(') {
+        name.push('(');
+        name.push_str(&self.expect_word("an option name")?);
+        self.expect_symbol(')')?;
+        name.push(')');
+      } else {
+        name.push_str(&self.expect_word("an option name")?);
+      }
+      match self.peek() {
+        Some(Token::Word(word)) if word.starts_with('.') => {
+          name.push_str(word);
+          self.position += 1;
+          if !name.ends_with('.') {
+            break;
+          }
+        }
+        _ => break,
+      }
+    }
+    Ok(name)
+  }
+
+  fn parse_constant(&mut self) -> Result<String, ParseError> {
+    if self.is_symbol('{') {
+      let mut depth = 0;
+      let mut parts = Vec::new();
+      loop {
+        let token = self.next()?;
+        match token {
+          Token::Symbol('{') => depth += 1,
+          Token::Symbol('}') => depth -= 1,
+          _ => (),
+        }
+        parts.push(token_text(&token));
+        if depth == 0 {
+          return Ok(parts.join(" "));
+        }
+      }
+    }
+    let mut value = String::new();
+    if self.eat_symbol('-') {
+      value.push('-');
+    } else if self.eat_symbol('+') {
+      value.push('+');
+    }
+    match self.next()? {
+      Token::Str(s) => {
+        let mut joined = s;
+        while let Some(Token::Str(next)) = self.peek() {
+          joined.push_str(next);
+          self.position += 1;
+        }
+        value.push_str(&token_text(&Token::Str(joined)));
+      }
+      Token::Word(word) => {
+        value.push_str(&word);
+        // The lexer splits exponents such as `1e-5` at the sign.
+        if word.ends_with(|c| c == 'e' || c == 'E')
+          && word.chars().next().map_or(false, |c| c.is_ascii_digit())
+        {
+          if self.eat_symbol('-') {
+            value.push('-');
+            value.push_str(&self.expect_word("an exponent")?);
+          } else if self.eat_symbol('+') {
+            value.push('+');
+            value.push_str(&self.expect_word("an exponent")?);
+          }
+        }
+      }
+      Token::Symbol(_) => {
+        self.position -= 1;
+        return Err(self.error("expected a constant"));
+      }
+    }
+    Ok(value)
+  }
+
+  /// Parses an option statement after its `option` keyword.
+  fn parse_option_statement(&mut self) -> Result<ProtoOption, ParseError> {
+    let start = self.position - 1;
+    let name = self.parse_option_name()?;
+    self.expect_symbol('=')?;
+    let value = self.parse_constant()?;
+    self.expect_symbol(';')?;
+    Ok(ProtoOption {
+      name,
+      value,
+      location: self.locate(start),
+    })
+  }
+
+  fn parse_field_options(&mut self) -> Result<Vec<ProtoOption>, ParseError> {
+    let mut options = Vec::new();
+    if !self.eat_symbol('[') {
+      return Ok(options);
+    }
+    loop {
+      let start = self.position;
+      let name = self.parse_option_name()?;
+      self.expect_symbol('=')?;
+      let value = self.parse_constant()?;
+      options.push(ProtoOption {
+        name,
+        value,
+        location: self.locate(start),
+      });
+      if self.eat_symbol(']') {
+        return Ok(options);
+      }
+      self.expect_symbol(',')?;
+    }
+  }
+
+  fn parse_reserved(&mut self) -> Result<Reserved, ParseError> {
+    let mut reserved = Reserved::default();
+    loop {
+      match self.peek() {
+        Some(Token::Str(name)) => {
+          reserved.names.push(name.clone());
+          self.position += 1;
+        }
+        Some(Token::Word(word)) if !word.starts_with(|c: char| c.is_ascii_digit()) => {
+          reserved.names.push(word.clone());
+          self.position += 1;
+        }
+        _ => reserved.ranges.push(self.parse_range()?),
+      }
+      if self.eat_symbol(';') {
+        return Ok(reserved);
+      }
+      self.expect_symbol(',')?;
+    }
+  }
+
+  fn parse_range(&mut self) -> Result<(i32, i32), ParseError> {
+    let start = self.expect_integer()?;
+    let end = if self.eat_word("to") {
+      if self.eat_word("max") {
+        i32::max_value()
+      } else {
+        self.expect_integer()?
+      }
+    } else {
+      start
+    };
+    Ok((start, end))
+  }
+
+  fn parse_extensions(&mut self) -> Result<Vec<(i32, i32)>, ParseError> {
+    let mut ranges = Vec::new();
+    loop {
+      ranges.push(self.parse_range()?);
+      // Options on extension ranges only declare what extensions
+      // are expected, so they're checked but not kept.
+      self.parse_field_options()?;
+      if self.eat_symbol(';') {
+        return Ok(ranges);
+      }
+      self.expect_symbol(',')?;
+    }
+  }
+
+  fn parse_message(&mut self, start: usize) -> Result<Message, ParseError> {
+    let name = self.expect_word("a message name")?;
+    self.parse_message_body(name, start)
+  }
+
+  fn parse_message_body(&mut self, name: String, start: usize) -> Result<Message, ParseError> {
+    let scope = name.clone();
+    self.in_scope(&scope, |parser| {
+      parser.parse_message_statements(name, start)
+    })
+  }
+
+  fn parse_message_statements(
+    &mut self,
+    name: String,
+    start: usize,
+  ) -> Result<Message, ParseError> {
+    let mut message = Message {
+      name,
+      ..Message::default()
+    };
+    self.expect_symbol('{')?;
+    while !self.eat_symbol('}') {
+      if self.eat_symbol(';') {
+        continue;
+      }
+      let statement = self.position;
+      self.declare_statement();
+      if self.eat_word("message") {
+        message.messages.push(self.parse_message(statement)?);
+      } else if self.eat_word("enum") {
+        message.enums.push(self.parse_enum(statement)?);
+      } else if self.eat_word("extend") {
+        message.extends.push(self.parse_extend(statement)?);
+      } else if self.eat_word("option") {
+        message.options.push(self.parse_option_statement()?);
+      } else if self.eat_word("extensions") {
+        let ranges = self.parse_extensions()?;
+        message.extensions.extend(ranges);
+      } else if self.eat_word("reserved") {
+        let reserved = self.parse_reserved()?;
+        message.reserved.ranges.extend(reserved.ranges);
+        message.reserved.names.extend(reserved.names);
+      } else if self.is_word("oneof") && self.peek_at(2) == Some(&Token::Symbol('{')) {
+        self.position += 1;
+        let oneof_name = self.expect_word("a oneof name")?;
+        let mut oneof = Oneof {
+          name: oneof_name.clone(),
+          options: Vec::new(),
+          location: Location::default(),
+        };
+        self.expect_symbol('{')?;
+        while !self.eat_symbol('}') {
+          if self.eat_symbol(';') {
+            continue;
+          }
+          let field_start = self.position;
+          self.declare_statement();
+          if self.eat_word("option") {
+            oneof.options.push(self.parse_option_statement()?);
+            continue;
+          }
+          let mut field = self.parse_field(Label::None, field_start, &mut message.messages)?;
+          field.oneof = Some(oneof_name.clone());
+          message.fields.push(field);
+        }
+        oneof.location = self.locate(statement);
+        message.oneofs.push(oneof);
+      } else {
+        let label = self.parse_label();
+        let field = self.parse_field(label, statement, &mut message.messages)?;
+        message.fields.push(field);
+      }
+    }
+    message.location = self.locate(start);
+    Ok(message)
+  }
+
+  /// Records what the statement at the current position declares, without
+  /// consuming it: the element named by a definition, field, enum value or
+  /// rpc, or the enclosing scope for `option`, `reserved` and the like.
+  fn declare_statement(&mut self) {
+    let line = self.line();
+    let word = |offset: usize| match self.peek_at(offset) {
+      Some(Token::Word(word)) => Some(word.clone()),
+      _ => None,
+    };
+    let first = word(0).unwrap_or_default();
+    let name = match first.as_str() {
+      "message" | "enum" | "rpc" => word(1),
+      "option" | "reserved" | "extensions" | "extend" | "oneof" => None,
+      _ if self.peek_at(1) == Some(&Token::Symbol('=')) => Some(first),
+      "optional" | "required" | "repeated"
+        if word(1).as_ref().map(String::as_str) == Some("map") =>
+      {
+        self.field_name_after_map(1)
+      }
+      "map" => self.field_name_after_map(0),
+      "optional" | "required" | "repeated" => word(2),
+      _ => word(1),
+    };
+    self.declare(name.as_ref().map(String::as_str), line);
+  }
+
+  /// The name of a `map<K, V> name = N` field starting `offset` tokens ahead.
+  fn field_name_after_map(&self, offset: usize) -> Option<String> {
+    let mut offset = offset;
+    while let Some(token) = self.peek_at(offset) {
+      offset += 1;
+      if *token == Token::Symbol('>') {
+        break;
+      }
+    }
+    match self.peek_at(offset) {
+      Some(Token::Word(word)) => Some(word.clone()),
+      _ => None,
+    }
+  }
+
+  fn parse_label(&mut self) -> Label {
+    if self.eat_word("optional") {
+      Label::Optional
+    } else if self.eat_word("required") {
+      Label::Required
+    } else if self.eat_word("repeated") {
+      Label::Repeated
+    } else {
+      Label::None
+    }
+  }
+
+  /// Parses a field after its label, given the statement's first token.
+  /// Groups also define a nested message, which is added to `messages`.
+  fn parse_field(
+    &mut self,
+    label: Label,
+    start: usize,
+    messages: &mut Vec<Message>,
+  ) -> Result<Field, ParseError> {
+    if self.is_word("group") && self.peek_at(2) == Some(&Token::Symbol('=')) {
+      self.position += 1;
+      let group_name = self.expect_word("a group name")?;
+      self.expect_symbol('=')?;
+      let number = self.expect_integer()?;
+      let options = self.parse_field_options()?;
+      messages.push(self.parse_message_body(group_name.clone(), start)?);
+      return Ok(Field {
+        name: group_name.to_lowercase(),
+        number,
+        label,
+        type_name: group_name,
+        oneof: None,
+        group: true,
+        options,
+        location: self.locate(start),
+      });
+    }
+    let type_name = if self.is_word("map") && self.peek_at(1) == Some(&Token::Symbol('<')) {
+      self.position += 2;
+      let key = self.expect_word("a map key type")?;
+      self.expect_symbol(',')?;
+      let value = self.expect_word("a map value type")?;
+      self.expect_symbol('>')?;
+      format!("map<{}, {}>", key, value)
+    } else {
+      self.expect_word("a field type")?
+    };
+    let name = self.expect_word("a field name")?;
+    self.expect_symbol('=')?;
+    let number = self.expect_integer()?;
+    let options = self.parse_field_options()?;
+    self.expect_symbol(';')?;
+    Ok(Field {
+      name,
+      number,
+      label,
+      type_name,
+      oneof: None,
+      group: false,
+      options,
+      location: self.locate(start),
+    })
+  }
+
+  fn parse_enum(&mut self, start: usize) -> Result<Enum, ParseError> {
+    let name = self.expect_word("an enum name")?;
+    let scope = name.clone();
+    self.in_scope(&scope, |parser| parser.parse_enum_values(name, start))
+  }
+
+  fn parse_enum_values(&mut self, name: String, start: usize) -> Result<Enum, ParseError> {
+    let mut enumeration = Enum {
+      name,
+      ..Enum::default()
+    };
+    self.expect_symbol('{')?;
+    while !self.eat_symbol('}') {
+      if self.eat_symbol(';') {
+        continue;
+      }
+      let statement = self.position;
+      self.declare_statement();
+      if self.eat_word("option") {
+        enumeration.options.push(self.parse_option_statement()?);
+      } else if self.eat_word("reserved") {
+        let reserved = self.parse_reserved()?;
+        enumeration.reserved.ranges.extend(reserved.ranges);
+        enumeration.reserved.names.extend(reserved.names);
+      } else {
+        let name = self.expect_word("an enum value name")?;
+        self.expect_symbol('=')?;
+        let number = self.expect_integer()?;
+        let options = self.parse_field_options()?;
+        self.expect_symbol(';')?;
+        enumeration.values.push(EnumValue {
+          name,
+          number,
+          options,
+          location: self.locate(statement),
+        });
+      }
+    }
+    enumeration.location = self.locate(start);
+    Ok(enumeration)
+  }
+
+  fn parse_service(&mut self, start: usize) -> Result<Service, ParseError> {
+    let name = self.expect_word("a service name")?;
+    let scope = name.clone();
+    self.in_scope(&scope, |parser| parser.parse_rpcs(name, start))
+  }
+
+  fn parse_rpcs(&mut self, name: String, start: usize) -> Result<Service, ParseError> {
+    let mut service = Service {
+      name,
+      ..Service::default()
+    };
+    self.expect_symbol('{')?;
+    while !self.eat_symbol('}') {
+      if self.eat_symbol(';') {
+        continue;
+      }
+      let statement = self.position;
+      self.declare_statement();
+      if self.eat_word("option") {
+        service.options.push(self.parse_option_statement()?);
+        continue;
+      }
+      if !self.eat_word("rpc") {
+        return Err(self.error("expected `rpc` or `option`"));
+      }
+      let name = self.expect_word("an rpc name")?;
+      let (client_streaming, input_type) = self.parse_rpc_type()?;
+      if !self.eat_word("returns") {
+        return Err(self.error("expected `returns`"));
+      }
+      let (server_streaming, output_type) = self.parse_rpc_type()?;
+      let mut options = Vec::new();
+      if self.eat_symbol('{') {
+        while !self.eat_symbol('}') {
+          if self.eat_symbol(';') {
+            continue;
+          }
+          if !self.eat_word("option") {
+            return Err(self.error("expected `option`"));
+          }
+          options.push(self.parse_option_statement()?);
+        }
+      } else {
+        self.expect_symbol(';')?;
+      }
+      service.rpcs.push(Rpc {
+        name,
+        input_type,
+        output_type,
+        client_streaming,
+        server_streaming,
+        options,
+        location: self.locate(statement),
+      });
+    }
+    service.location = self.locate(start);
+    Ok(service)
+  }
+
+  fn parse_rpc_type(&mut self) -> Result<(bool, String), ParseError> {
+    self.expect_symbol('(')?;
+    let streaming = match self.peek_at(1) {
+      Some(Token::Word(_)) => self.eat_word("stream"),
+      _ => false,
+    };
+    let type_name = self.expect_word("a message type")?;
+    self.expect_symbol(')')?;
+    Ok((streaming, type_name))
+  }
+
+  fn parse_extend(&mut self, start: usize) -> Result<Extend, ParseError> {
+    let extendee = self.expect_word("a message type")?;
+    let mut fields = Vec::new();
+    // Groups declared inside `extend` define messages in the
+    // enclosing scope; they're dropped since nothing refers to them by name.
+    let mut groups = Vec::new();
+    self.expect_symbol('{')?;
+    while !self.eat_symbol('}') {
+      if self.eat_symbol(';') {
+        continue;
+      }
+      let statement = self.position;
+      let label = self.parse_label();
+      fields.push(self.parse_field(label, statement, &mut groups)?);
+    }
+    Ok(Extend {
+      extendee,
+      fields,
+      location: self.locate(start),
+    })
+  }
+}
+
+fn token_text(token: &Token) -> String {
+  match token {
+    Token::Word(word) => word.clone(),
+    Token::Str(value) => format!("{:?}", value),
+    Token::Symbol(symbol) => symbol.to_string(),
+  }
+}
+
+/// `name` inside `scope`, e.g. `acme.v1.User` for `User` in `acme.v1`.
+pub(crate) fn qualify(scope: &str, name: &str) -> String {
+  if scope.is_empty() {
+    name.to_string()
+  } else {
+    format!("{}.{}", scope, name)
+  }
+}
+
+/// Parses a .proto file into its definitions.
+pub fn parse(source: &str) -> Result<ProtoFile, ParseError> {
+  let lexed = lex(source)?;
+  let mut parser = Parser {
+    tokens: lexed.tokens,
+    ends: lexed.ends,
+    comments: lexed
+      .comments
+      .iter()
+      .filter(|comment| !comment.is_directive())
+      .cloned()
+      .collect(),
+    position: 0,
+    scope: String::new(),
+    declarations: Vec::new(),
+    bodies: Vec::new(),
+  };
+  let mut file = parser.parse_file()?;
+  file.ignores = parser.attach(lexed.directives);
+  file.declarations = parser.describe();
+  file.comments = lexed.comments;
+  Ok(file)
+}
+
+/// Parses the .proto file at `path`, with errors pointing into it.
+pub fn parse_file(path: &Path, source: &str) -> Result<ProtoFile, ParseError> {
+  parse(source).map_err(|err| err.in_file(path))
+}
+
+/// Parses every .proto file under `root`, skipping paths matched by ignore
+/// files the same way the artifact does.
+pub fn parse_dir(root: &Path) -> Result<Vec<SourceFile>, ParseError> {
+  let mut sources = Vec::new();
+  for entry in Walk::new(root) {
+    let entry = entry.unwrap();
+    let path = entry.path();
+    if !path.is_file() || path.extension().map_or(true, |ext| ext != "proto") {
+      continue;
+    }
+    let source = fs::read_to_string(path).expect("Failed to read proto file");
+    sources.push((path.strip_prefix(root).unwrap().to_path_buf(), source));
+  }
+  parse_sources(sources)
+}
+
+/// Parses in-memory sources, e.g. ones read out of an artifact, stopping at
+/// the first file that doesn't parse.
+pub fn parse_sources(sources: Vec<(PathBuf, String)>) -> Result<Vec<SourceFile>, ParseError> {
+  let mut files = Vec::new();
+  for (path, source) in sources {
+    let file = parse_file(&path, &source)?;
+    files.push(SourceFile { path, file });
+  }
+  files.sort_by(|a, b| a.path.cmp(&b.path));
+  Ok(files)
+}
+
+/// Whether `previous` and `current` define the same elements, wherever
+/// they're declared and however they're commented.
+pub fn same_definitions(previous: &[SourceFile], current: &[SourceFile]) -> bool {
+  previous.len() == current.len()
+    && previous
+      .iter()
+      .zip(current)
+      .all(|(old, new)| old.path == new.path && definitions(&old.file) == definitions(&new.file))
+}
+
+/// Whether both lists set the same options to the same values, in the same
+/// order, wherever they're declared.
+pub fn same_options(old: &[ProtoOption], new: &[ProtoOption]) -> bool {
+  old.len() == new.len()
+    && old
+      .iter()
+      .zip(new)
+      .all(|(old, new)| old.name == new.name && old.value == new.value)
+}
+
+// Comments, including `buff:ignore` ones, declarations and locations carry
+// line numbers, so they're left out to not count reformatting as a change.
+fn definitions(file: &ProtoFile) -> ProtoFile {
+  let mut file = file.clone();
+  file.ignores.clear();
+  file.declarations.clear();
+  file.comments.clear();
+  for import in &mut file.imports {
+    import.location = Location::default();
+  }
+  clear_options(&mut file.options);
+  file.messages.iter_mut().for_each(clear_message);
+  file.enums.iter_mut().for_each(clear_enum);
+  for service in &mut file.services {
+    service.location = Location::default();
+    clear_options(&mut service.options);
+    for rpc in &mut service.rpcs {
+      rpc.location = Location::default();
+      clear_options(&mut rpc.options);
+    }
+  }
+  file.extends.iter_mut().for_each(clear_extend);
+  file
+}
+
+fn clear_message(message: &mut Message) {
+  message.location = Location::default();
+  clear_options(&mut message.options);
+  message.fields.iter_mut().for_each(clear_field);
+  for oneof in &mut message.oneofs {
+    oneof.location = Location::default();
+    clear_options(&mut oneof.options);
+  }
+  message.messages.iter_mut().for_each(clear_message);
+  message.enums.iter_mut().for_each(clear_enum);
+  message.extends.iter_mut().for_each(clear_extend);
+}
+
+fn clear_enum(definition: &mut Enum) {
+  definition.location = Location::default();
+  clear_options(&mut definition.options);
+  for value in &mut definition.values {
+    value.location = Location::default();
+    clear_options(&mut value.options);
+  }
+}
+
+fn clear_extend(extend: &mut Extend) {
+  extend.location = Location::default();
+  extend.fields.iter_mut().for_each(clear_field);
+}
+
+fn clear_field(field: &mut Field) {
+  field.location = Location::default();
+  clear_options(&mut field.options);
+}
+
+fn clear_options(options: &mut [ProtoOption]) {
+  for option in options {
+    option.location = Location::default();
+  }
+}
+
+/// `source` as the only file of a package, `orders.proto`.
+#[cfg(test)]
+pub(crate) fn sources(source: &str) -> Vec<SourceFile> {
+  vec![SourceFile {
+    path: PathBuf::from("orders.proto"),
+    file: parse(source).unwrap(),
+  }]
+}
+
+#[test]
+fn should_parse_header() {
+  let source = r#"
+    // package commented.out;
+    syntax = "proto3";
+    /* import "nope.proto"; */
+    package acme.billing.v1;
+    import "google/protobuf/timestamp.proto";
+    import public "yummy_package/food.proto";
+    message Invoice { string id = 1; message Nested { string package = 1; } }
+  "#;
+  let file = parse(source).unwrap();
+  assert_eq!(file.syntax, Some("proto3".to_string()));
+  assert_eq!(file.package, Some("acme.billing.v1".to_string()));
+  let imports: Vec<_> = file
+    .imports
+    .iter()
+    .map(|import| (import.path.as_str(), &import.kind))
+    .collect();
+  assert_eq!(
+    imports,
+    vec![
+      ("google/protobuf/timestamp.proto", &ImportKind::Default),
+      ("yummy_package/food.proto", &ImportKind::Public),
+    ]
+  );
+}
+
+#[test]
+fn should_report_error_position() {
+  let error = parse("syntax = \"proto3\";\npackage ;").unwrap_err();
+  assert_eq!((error.line, error.column), (2, 9));
+  assert_eq!(error.message, "expected a package name");
+  let error =
+    parse_sources(vec![(PathBuf::from("bad.proto"), "message {".to_string())]).unwrap_err();
+  assert_eq!(error.file, Some(PathBuf::from("bad.proto")));
+}
+
+#[test]
+fn should_parse_definitions() {
+  let source = r#"
+    syntax = "proto2";
+    package acme.orders.v1;
+    option java_package = "com.acme.orders";
+    message Order {
+      reserved 4, 9 to 11, 20 to max;
+      reserved "legacy_id";
+      required string id = 1 [(validate.rules).string.min_len = 1];
+      repeated Item items = 2;
+      map<string, int64> totals = 3 [deprecated = true];
+      oneof payment {
+        string card = 5;
+        Voucher voucher = 6;
+      }
+      optional group Shipping = 7 { optional string address = 1; }
+      message Item { optional string sku = 1; }
+      enum Status { option allow_alias = true; PENDING = 0; DONE = 1; reserved 2; }
+      extensions 100 to 199;
+    }
+    extend Order { optional float weight = 100 [default = 1.5e-3]; }
+    service Orders {
+      option deprecated = true;
+      rpc Get (.acme.orders.v1.Order) returns (stream Order);
+      rpc Watch (stream Order) returns (Order) { option idempotency_level = NO_SIDE_EFFECTS; }
+    }
+  "#;
+  let file = parse(source).unwrap();
+  assert!(same_options(
+    &file.options,
+    &[ProtoOption {
+      name: "java_package".to_string(),
+      value: "\"com.acme.orders\"".to_string(),
+      location: Location::default(),
+    }]
+  ));
+  let order = &file.messages[0];
+  assert_eq!(
+    order.reserved.ranges,
+    vec![(4, 4), (9, 11), (20, i32::max_value())]
+  );
+  assert!(order.reserved.contains_number(10));
+  assert!(order.reserved.contains_name("legacy_id"));
+  let fields: Vec<(&str, i32, Label, &str, Option<&str>)> = order
+    .fields
+    .iter()
+    .map(|f| {
+      (
+        f.name.as_str(),
+        f.number,
+        f.label,
+        f.type_name.as_str(),
+        f.oneof.as_ref().map(String::as_str),
+      )
+    })
+    .collect();
+  assert_eq!(
+    fields,
+    vec![
+      ("id", 1, Label::Required, "string", None),
+      ("items", 2, Label::Repeated, "Item", None),
+      ("totals", 3, Label::None, "map<string, int64>", None),
+      ("card", 5, Label::None, "string", Some("payment")),
+      ("voucher", 6, Label::None, "Voucher", Some("payment")),
+      ("shipping", 7, Label::Optional, "Shipping", None),
+    ]
+  );
+  assert_eq!(
+    order.fields[0].options[0].name,
+    "(validate.rules).string.min_len"
+  );
+  let nested: Vec<&str> = order.messages.iter().map(|m| m.name.as_str()).collect();
+  assert_eq!(nested, vec!["Shipping", "Item"]);
+  assert_eq!(order.enums[0].values.len(), 2);
+  assert_eq!(file.extends[0].fields[0].options[0].value, "1.5e-3");
+  let service = &file.services[0];
+  assert_eq!(service.rpcs[0].input_type, ".acme.orders.v1.Order");
+  assert!(!service.rpcs[0].client_streaming && service.rpcs[0].server_streaming);
+  assert!(service.rpcs[1].client_streaming && !service.rpcs[1].server_streaming);
+  assert_eq!(service.rpcs[1].options[0].value, "NO_SIDE_EFFECTS");
+}
+
+#[test]
+fn should_attach_ignore_comments() {
+  let source = r#"
+    syntax = "proto3";
+    package acme.v1;
+    // buff:ignore MESSAGE_REMOVED
+    message User {
+      string name = 1; // buff:ignore FIELD_TYPE_CHANGED, FIELD_NAME_CHANGED
+      // buff:ignore FIELD_REMOVED
+      reserved 2;
+      map<string, string> labels = 3; // buff:ignore FIELD_TYPE_CHANGED
+      enum Role { ADMIN = 0; } // not a directive: buff:ignore
+    }
+    service Users {
+      // buff:ignore RPC_REMOVED
+      rpc Get (User) returns (User);
+    }
+  "#;
+  let ignores: Vec<(Option<String>, Vec<String>)> = parse(source)
+    .unwrap()
+    .ignores
+    .into_iter()
+    .map(|ignore| (ignore.path, ignore.rules))
+    .collect();
+  let ignore = |path: &str, rules: &[&str]| {
+    (
+      Some(path.to_string()),
+      rules.iter().map(|rule| rule.to_string()).collect(),
+    )
+  };
+  assert_eq!(
+    ignores,
+    vec![
+      ignore("acme.v1.User", &["MESSAGE_REMOVED"]),
+      ignore(
+        "acme.v1.User.name",
+        &["FIELD_TYPE_CHANGED", "FIELD_NAME_CHANGED"]
+      ),
+      ignore("acme.v1.User", &["FIELD_REMOVED"]),
+      ignore("acme.v1.User.labels", &["FIELD_TYPE_CHANGED"]),
+      ignore("acme.v1.Users", &["RPC_REMOVED"]),
+    ]
+  );
+}
+
+#[test]
+fn should_attach_ignore_comments_where_elements_were_removed() {
+  let source = r#"
+    syntax = "proto3";
+    package acme.v1;
+    message Order {
+      string id = 1;
+      // buff:ignore FIELD_REMOVED
+      string note = 3;
+      message Item {
+        string sku = 1;
+        // buff:ignore FIELD_REMOVED
+      }
+      // buff:ignore FIELD_REMOVED
+    }
+    enum Status {
+      PENDING = 0;
+      // buff:ignore ENUM_VALUE_REMOVED
+    }
+    // buff:ignore MESSAGE_REMOVED
+  "#;
+  let paths: Vec<Option<String>> = parse(source)
+    .unwrap()
+    .ignores
+    .into_iter()
+    .map(|ignore| ignore.path)
+    .collect();
+  assert_eq!(
+    paths,
+    vec![
+      Some("acme.v1.Order".to_string()),
+      Some("acme.v1.Order.Item".to_string()),
+      Some("acme.v1.Order".to_string()),
+      Some("acme.v1.Status".to_string()),
+      None,
+    ]
+  );
+}
+
+#[test]
+fn should_record_locations_and_comments() {
+  let source = "syntax = \"proto2\";
+package acme.v1;
+
+// Detached.
+
+// An order.
+// Placed by a customer.
+message Order { // trailing
+  /* The id. */ required string id = 1;
+  optional int32 count = 2; // how many
+  extensions 100 to 199, 1000 to max;
+}
+";
+  let file = parse(source).unwrap();
+  let order = &file.messages[0];
+  assert_eq!(
+    order.location.span,
+    Span {
+      start: Position { line: 8, column: 1 },
+      end: Position {
+        line: 12,
+        column: 2
+      },
+    }
+  );
+  assert_eq!(
+    order.location.leading_comments,
+    Some(" An order.\n Placed by a customer.\n".to_string())
+  );
+  assert_eq!(
+    order.location.trailing_comments,
+    Some(" trailing\n".to_string())
+  );
+  let id = &order.fields[0].location;
+  assert_eq!(id.leading_comments, Some(" The id. ".to_string()));
+  assert_eq!(
+    id.span.start,
+    Position {
+      line: 9,
+      column: 17
+    }
+  );
+  let count = &order.fields[1].location;
+  assert_eq!(count.leading_comments, None);
+  assert_eq!(count.trailing_comments, Some(" how many\n".to_string()));
+  assert_eq!(order.extensions, vec![(100, 199), (1000, i32::max_value())]);
+  let comments: Vec<&str> = file.comments.iter().map(|c| c.text.as_str()).collect();
+  assert_eq!(
+    comments,
+    vec![
+      "// Detached.",
+      "// An order.",
+      "// Placed by a customer.",
+      "// trailing",
+      "/* The id. */",
+      "// how many",
+    ]
+  );
+  let error = parse_file(Path::new("acme/orders.proto"), "message {}").unwrap_err();
+  assert_eq!(
+    error.to_string(),
+    "acme/orders.proto:1:9: expected a message name"
+  );
+}
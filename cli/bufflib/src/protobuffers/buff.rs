@@ -1,11 +1,12 @@
-// This file is generated by rust-protobuf 2.6.2. Do not edit
+// This file is generated by rust-protobuf 2.28.0. Do not edit
 // @generated
 
-// https://github.com/Manishearth/rust-clippy/issues/702
+// https://github.com/rust-lang/rust-clippy/issues/702
 #![allow(unknown_lints)]
-#![allow(clippy)]
+#![allow(clippy::all)]
 
-#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_attributes)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
 
 #![allow(box_pointers)]
 #![allow(dead_code)]
@@ -14,12 +15,13 @@
 #![allow(non_snake_case)]
 #![allow(non_upper_case_globals)]
 #![allow(trivial_casts)]
-#![allow(unsafe_code)]
 #![allow(unused_imports)]
 #![allow(unused_results)]
+//! Generated file from `buff.proto`
 
-use protobuf::Message as Message_imported_for_functions;
-use protobuf::ProtobufEnum as ProtobufEnum_imported_for_functions;
+/// Generated files are compatible only with the same version
+/// of protobuf runtime.
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
 
 #[derive(PartialEq,Clone,Default)]
 pub struct LoginRequest {
@@ -100,7 +102,7 @@ impl ::protobuf::Message for LoginRequest {
         true
     }
 
-    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
         while !is.eof()? {
             let (field_number, wire_type) = is.read_tag_unpack()?;
             match field_number {
@@ -133,7 +135,7 @@ impl ::protobuf::Message for LoginRequest {
         my_size
     }
 
-    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
         if !self.email.is_empty() {
             os.write_string(1, &self.email)?;
         }
@@ -156,13 +158,13 @@ impl ::protobuf::Message for LoginRequest {
         &mut self.unknown_fields
     }
 
-    fn as_any(&self) -> &::std::any::Any {
-        self as &::std::any::Any
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
     }
-    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
-        self as &mut ::std::any::Any
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
     }
-    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
         self
     }
 
@@ -175,40 +177,30 @@ impl ::protobuf::Message for LoginRequest {
     }
 
     fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
-        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
-            lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
-        };
-        unsafe {
-            descriptor.get(|| {
-                let mut fields = ::std::vec::Vec::new();
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                    "email",
-                    |m: &LoginRequest| { &m.email },
-                    |m: &mut LoginRequest| { &mut m.email },
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                    "password",
-                    |m: &LoginRequest| { &m.password },
-                    |m: &mut LoginRequest| { &mut m.password },
-                ));
-                ::protobuf::reflect::MessageDescriptor::new::<LoginRequest>(
-                    "LoginRequest",
-                    fields,
-                    file_descriptor_proto()
-                )
-            })
-        }
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "email",
+                |m: &LoginRequest| { &m.email },
+                |m: &mut LoginRequest| { &mut m.email },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "password",
+                |m: &LoginRequest| { &m.password },
+                |m: &mut LoginRequest| { &mut m.password },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<LoginRequest>(
+                "LoginRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
     }
 
     fn default_instance() -> &'static LoginRequest {
-        static mut instance: ::protobuf::lazy::Lazy<LoginRequest> = ::protobuf::lazy::Lazy {
-            lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const LoginRequest,
-        };
-        unsafe {
-            instance.get(LoginRequest::new)
-        }
+        static instance: ::protobuf::rt::LazyV2<LoginRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(LoginRequest::new)
     }
 }
 
@@ -221,14 +213,14 @@ impl ::protobuf::Clear for LoginRequest {
 }
 
 impl ::std::fmt::Debug for LoginRequest {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
 impl ::protobuf::reflect::ProtobufValue for LoginRequest {
-    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
-        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
     }
 }
 
@@ -284,7 +276,7 @@ impl ::protobuf::Message for LoginResponse {
         true
     }
 
-    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
         while !is.eof()? {
             let (field_number, wire_type) = is.read_tag_unpack()?;
             match field_number {
@@ -311,7 +303,7 @@ impl ::protobuf::Message for LoginResponse {
         my_size
     }
 
-    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
         if !self.token.is_empty() {
             os.write_string(1, &self.token)?;
         }
@@ -331,13 +323,13 @@ impl ::protobuf::Message for LoginResponse {
         &mut self.unknown_fields
     }
 
-    fn as_any(&self) -> &::std::any::Any {
-        self as &::std::any::Any
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
     }
-    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
-        self as &mut ::std::any::Any
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
     }
-    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
         self
     }
 
@@ -350,35 +342,25 @@ impl ::protobuf::Message for LoginResponse {
     }
 
     fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
-        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
-            lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
-        };
-        unsafe {
-            descriptor.get(|| {
-                let mut fields = ::std::vec::Vec::new();
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                    "token",
-                    |m: &LoginResponse| { &m.token },
-                    |m: &mut LoginResponse| { &mut m.token },
-                ));
-                ::protobuf::reflect::MessageDescriptor::new::<LoginResponse>(
-                    "LoginResponse",
-                    fields,
-                    file_descriptor_proto()
-                )
-            })
-        }
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "token",
+                |m: &LoginResponse| { &m.token },
+                |m: &mut LoginResponse| { &mut m.token },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<LoginResponse>(
+                "LoginResponse",
+                fields,
+                file_descriptor_proto()
+            )
+        })
     }
 
     fn default_instance() -> &'static LoginResponse {
-        static mut instance: ::protobuf::lazy::Lazy<LoginResponse> = ::protobuf::lazy::Lazy {
-            lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const LoginResponse,
-        };
-        unsafe {
-            instance.get(LoginResponse::new)
-        }
+        static instance: ::protobuf::rt::LazyV2<LoginResponse> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(LoginResponse::new)
     }
 }
 
@@ -390,14 +372,14 @@ impl ::protobuf::Clear for LoginResponse {
 }
 
 impl ::std::fmt::Debug for LoginResponse {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
 impl ::protobuf::reflect::ProtobufValue for LoginResponse {
-    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
-        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
     }
 }
 
@@ -560,7 +542,7 @@ impl ::protobuf::Message for Package {
         true
     }
 
-    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
         while !is.eof()? {
             let (field_number, wire_type) = is.read_tag_unpack()?;
             match field_number {
@@ -611,7 +593,7 @@ impl ::protobuf::Message for Package {
         my_size
     }
 
-    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
         if !self.name.is_empty() {
             os.write_string(1, &self.name)?;
         }
@@ -643,13 +625,13 @@ impl ::protobuf::Message for Package {
         &mut self.unknown_fields
     }
 
-    fn as_any(&self) -> &::std::any::Any {
-        self as &::std::any::Any
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
     }
-    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
-        self as &mut ::std::any::Any
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
     }
-    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
         self
     }
 
@@ -662,55 +644,45 @@ impl ::protobuf::Message for Package {
     }
 
     fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
-        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
-            lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
-        };
-        unsafe {
-            descriptor.get(|| {
-                let mut fields = ::std::vec::Vec::new();
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                    "name",
-                    |m: &Package| { &m.name },
-                    |m: &mut Package| { &mut m.name },
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                    "description",
-                    |m: &Package| { &m.description },
-                    |m: &mut Package| { &mut m.description },
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                    "homepage",
-                    |m: &Package| { &m.homepage },
-                    |m: &mut Package| { &mut m.homepage },
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                    "repository_url",
-                    |m: &Package| { &m.repository_url },
-                    |m: &mut Package| { &mut m.repository_url },
-                ));
-                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
-                    "keywords",
-                    |m: &Package| { &m.keywords },
-                    |m: &mut Package| { &mut m.keywords },
-                ));
-                ::protobuf::reflect::MessageDescriptor::new::<Package>(
-                    "Package",
-                    fields,
-                    file_descriptor_proto()
-                )
-            })
-        }
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "name",
+                |m: &Package| { &m.name },
+                |m: &mut Package| { &mut m.name },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "description",
+                |m: &Package| { &m.description },
+                |m: &mut Package| { &mut m.description },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "homepage",
+                |m: &Package| { &m.homepage },
+                |m: &mut Package| { &mut m.homepage },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "repository_url",
+                |m: &Package| { &m.repository_url },
+                |m: &mut Package| { &mut m.repository_url },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "keywords",
+                |m: &Package| { &m.keywords },
+                |m: &mut Package| { &mut m.keywords },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<Package>(
+                "Package",
+                fields,
+                file_descriptor_proto()
+            )
+        })
     }
 
     fn default_instance() -> &'static Package {
-        static mut instance: ::protobuf::lazy::Lazy<Package> = ::protobuf::lazy::Lazy {
-            lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const Package,
-        };
-        unsafe {
-            instance.get(Package::new)
-        }
+        static instance: ::protobuf::rt::LazyV2<Package> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(Package::new)
     }
 }
 
@@ -726,14 +698,14 @@ impl ::protobuf::Clear for Package {
 }
 
 impl ::std::fmt::Debug for Package {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
 impl ::protobuf::reflect::ProtobufValue for Package {
-    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
-        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
     }
 }
 
@@ -741,6 +713,11 @@ impl ::protobuf::reflect::ProtobufValue for Package {
 pub struct PublishRequest {
     // message fields
     pub artifact: ::std::vec::Vec<u8>,
+    pub validate_only: bool,
+    pub signature: ::std::vec::Vec<u8>,
+    pub public_key: ::std::vec::Vec<u8>,
+    pub provenance: ::std::vec::Vec<u8>,
+    pub provenance_signature: ::std::vec::Vec<u8>,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
@@ -782,6 +759,125 @@ impl PublishRequest {
     pub fn take_artifact(&mut self) -> ::std::vec::Vec<u8> {
         ::std::mem::replace(&mut self.artifact, ::std::vec::Vec::new())
     }
+
+    // bool validate_only = 2;
+
+
+    pub fn get_validate_only(&self) -> bool {
+        self.validate_only
+    }
+    pub fn clear_validate_only(&mut self) {
+        self.validate_only = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_validate_only(&mut self, v: bool) {
+        self.validate_only = v;
+    }
+
+    // bytes signature = 3;
+
+
+    pub fn get_signature(&self) -> &[u8] {
+        &self.signature
+    }
+    pub fn clear_signature(&mut self) {
+        self.signature.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_signature(&mut self, v: ::std::vec::Vec<u8>) {
+        self.signature = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_signature(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.signature
+    }
+
+    // Take field
+    pub fn take_signature(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.signature, ::std::vec::Vec::new())
+    }
+
+    // bytes public_key = 4;
+
+
+    pub fn get_public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+    pub fn clear_public_key(&mut self) {
+        self.public_key.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_public_key(&mut self, v: ::std::vec::Vec<u8>) {
+        self.public_key = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_public_key(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.public_key
+    }
+
+    // Take field
+    pub fn take_public_key(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.public_key, ::std::vec::Vec::new())
+    }
+
+    // bytes provenance = 5;
+
+
+    pub fn get_provenance(&self) -> &[u8] {
+        &self.provenance
+    }
+    pub fn clear_provenance(&mut self) {
+        self.provenance.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_provenance(&mut self, v: ::std::vec::Vec<u8>) {
+        self.provenance = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_provenance(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.provenance
+    }
+
+    // Take field
+    pub fn take_provenance(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.provenance, ::std::vec::Vec::new())
+    }
+
+    // bytes provenance_signature = 6;
+
+
+    pub fn get_provenance_signature(&self) -> &[u8] {
+        &self.provenance_signature
+    }
+    pub fn clear_provenance_signature(&mut self) {
+        self.provenance_signature.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_provenance_signature(&mut self, v: ::std::vec::Vec<u8>) {
+        self.provenance_signature = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_provenance_signature(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.provenance_signature
+    }
+
+    // Take field
+    pub fn take_provenance_signature(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.provenance_signature, ::std::vec::Vec::new())
+    }
 }
 
 impl ::protobuf::Message for PublishRequest {
@@ -789,13 +885,32 @@ impl ::protobuf::Message for PublishRequest {
         true
     }
 
-    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
         while !is.eof()? {
             let (field_number, wire_type) = is.read_tag_unpack()?;
             match field_number {
                 1 => {
                     ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.artifact)?;
                 },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.validate_only = tmp;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.signature)?;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.public_key)?;
+                },
+                5 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.provenance)?;
+                },
+                6 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.provenance_signature)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -811,15 +926,45 @@ impl ::protobuf::Message for PublishRequest {
         if !self.artifact.is_empty() {
             my_size += ::protobuf::rt::bytes_size(1, &self.artifact);
         }
+        if self.validate_only != false {
+            my_size += 2;
+        }
+        if !self.signature.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(3, &self.signature);
+        }
+        if !self.public_key.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(4, &self.public_key);
+        }
+        if !self.provenance.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(5, &self.provenance);
+        }
+        if !self.provenance_signature.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(6, &self.provenance_signature);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
     }
 
-    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
         if !self.artifact.is_empty() {
             os.write_bytes(1, &self.artifact)?;
         }
+        if self.validate_only != false {
+            os.write_bool(2, self.validate_only)?;
+        }
+        if !self.signature.is_empty() {
+            os.write_bytes(3, &self.signature)?;
+        }
+        if !self.public_key.is_empty() {
+            os.write_bytes(4, &self.public_key)?;
+        }
+        if !self.provenance.is_empty() {
+            os.write_bytes(5, &self.provenance)?;
+        }
+        if !self.provenance_signature.is_empty() {
+            os.write_bytes(6, &self.provenance_signature)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -836,13 +981,13 @@ impl ::protobuf::Message for PublishRequest {
         &mut self.unknown_fields
     }
 
-    fn as_any(&self) -> &::std::any::Any {
-        self as &::std::any::Any
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
     }
-    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
-        self as &mut ::std::any::Any
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
     }
-    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
         self
     }
 
@@ -855,54 +1000,74 @@ impl ::protobuf::Message for PublishRequest {
     }
 
     fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
-        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
-            lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
-        };
-        unsafe {
-            descriptor.get(|| {
-                let mut fields = ::std::vec::Vec::new();
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
-                    "artifact",
-                    |m: &PublishRequest| { &m.artifact },
-                    |m: &mut PublishRequest| { &mut m.artifact },
-                ));
-                ::protobuf::reflect::MessageDescriptor::new::<PublishRequest>(
-                    "PublishRequest",
-                    fields,
-                    file_descriptor_proto()
-                )
-            })
-        }
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "artifact",
+                |m: &PublishRequest| { &m.artifact },
+                |m: &mut PublishRequest| { &mut m.artifact },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "validate_only",
+                |m: &PublishRequest| { &m.validate_only },
+                |m: &mut PublishRequest| { &mut m.validate_only },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "signature",
+                |m: &PublishRequest| { &m.signature },
+                |m: &mut PublishRequest| { &mut m.signature },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "public_key",
+                |m: &PublishRequest| { &m.public_key },
+                |m: &mut PublishRequest| { &mut m.public_key },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "provenance",
+                |m: &PublishRequest| { &m.provenance },
+                |m: &mut PublishRequest| { &mut m.provenance },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "provenance_signature",
+                |m: &PublishRequest| { &m.provenance_signature },
+                |m: &mut PublishRequest| { &mut m.provenance_signature },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<PublishRequest>(
+                "PublishRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
     }
 
     fn default_instance() -> &'static PublishRequest {
-        static mut instance: ::protobuf::lazy::Lazy<PublishRequest> = ::protobuf::lazy::Lazy {
-            lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const PublishRequest,
-        };
-        unsafe {
-            instance.get(PublishRequest::new)
-        }
+        static instance: ::protobuf::rt::LazyV2<PublishRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(PublishRequest::new)
     }
 }
 
 impl ::protobuf::Clear for PublishRequest {
     fn clear(&mut self) {
         self.artifact.clear();
+        self.validate_only = false;
+        self.signature.clear();
+        self.public_key.clear();
+        self.provenance.clear();
+        self.provenance_signature.clear();
         self.unknown_fields.clear();
     }
 }
 
 impl ::std::fmt::Debug for PublishRequest {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
 impl ::protobuf::reflect::ProtobufValue for PublishRequest {
-    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
-        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
     }
 }
 
@@ -910,6 +1075,7 @@ impl ::protobuf::reflect::ProtobufValue for PublishRequest {
 pub struct PublishResponse {
     // message fields
     pub result: bool,
+    pub rejection: ::protobuf::SingularPtrField<CompatibilityRejection>,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
@@ -940,14 +1106,52 @@ impl PublishResponse {
     pub fn set_result(&mut self, v: bool) {
         self.result = v;
     }
+
+    // .buff_server_grpc.CompatibilityRejection rejection = 2;
+
+
+    pub fn get_rejection(&self) -> &CompatibilityRejection {
+        self.rejection.as_ref().unwrap_or_else(|| <CompatibilityRejection as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_rejection(&mut self) {
+        self.rejection.clear();
+    }
+
+    pub fn has_rejection(&self) -> bool {
+        self.rejection.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_rejection(&mut self, v: CompatibilityRejection) {
+        self.rejection = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_rejection(&mut self) -> &mut CompatibilityRejection {
+        if self.rejection.is_none() {
+            self.rejection.set_default();
+        }
+        self.rejection.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_rejection(&mut self) -> CompatibilityRejection {
+        self.rejection.take().unwrap_or_else(|| CompatibilityRejection::new())
+    }
 }
 
 impl ::protobuf::Message for PublishResponse {
     fn is_initialized(&self) -> bool {
+        for v in &self.rejection {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
         true
     }
 
-    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
         while !is.eof()? {
             let (field_number, wire_type) = is.read_tag_unpack()?;
             match field_number {
@@ -958,6 +1162,9 @@ impl ::protobuf::Message for PublishResponse {
                     let tmp = is.read_bool()?;
                     self.result = tmp;
                 },
+                2 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.rejection)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -973,15 +1180,24 @@ impl ::protobuf::Message for PublishResponse {
         if self.result != false {
             my_size += 2;
         }
+        if let Some(ref v) = self.rejection.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
     }
 
-    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
         if self.result != false {
             os.write_bool(1, self.result)?;
         }
+        if let Some(ref v) = self.rejection.as_ref() {
+            os.write_tag(2, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -998,13 +1214,13 @@ impl ::protobuf::Message for PublishResponse {
         &mut self.unknown_fields
     }
 
-    fn as_any(&self) -> &::std::any::Any {
-        self as &::std::any::Any
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
     }
-    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
-        self as &mut ::std::any::Any
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
     }
-    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
         self
     }
 
@@ -1017,87 +1233,1675 @@ impl ::protobuf::Message for PublishResponse {
     }
 
     fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
-        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
-            lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
-        };
-        unsafe {
-            descriptor.get(|| {
-                let mut fields = ::std::vec::Vec::new();
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
-                    "result",
-                    |m: &PublishResponse| { &m.result },
-                    |m: &mut PublishResponse| { &mut m.result },
-                ));
-                ::protobuf::reflect::MessageDescriptor::new::<PublishResponse>(
-                    "PublishResponse",
-                    fields,
-                    file_descriptor_proto()
-                )
-            })
-        }
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                "result",
+                |m: &PublishResponse| { &m.result },
+                |m: &mut PublishResponse| { &mut m.result },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<CompatibilityRejection>>(
+                "rejection",
+                |m: &PublishResponse| { &m.rejection },
+                |m: &mut PublishResponse| { &mut m.rejection },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<PublishResponse>(
+                "PublishResponse",
+                fields,
+                file_descriptor_proto()
+            )
+        })
     }
 
     fn default_instance() -> &'static PublishResponse {
-        static mut instance: ::protobuf::lazy::Lazy<PublishResponse> = ::protobuf::lazy::Lazy {
-            lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const PublishResponse,
-        };
-        unsafe {
-            instance.get(PublishResponse::new)
-        }
+        static instance: ::protobuf::rt::LazyV2<PublishResponse> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(PublishResponse::new)
     }
 }
 
 impl ::protobuf::Clear for PublishResponse {
     fn clear(&mut self) {
         self.result = false;
+        self.rejection.clear();
         self.unknown_fields.clear();
     }
 }
 
 impl ::std::fmt::Debug for PublishResponse {
-    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
 impl ::protobuf::reflect::ProtobufValue for PublishResponse {
-    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
-        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
     }
 }
 
-static file_descriptor_proto_data: &'static [u8] = b"\
-    \n\nbuff.proto\x12\x10buff_server_grpc\"@\n\x0cLoginRequest\x12\x14\n\
-    \x05email\x18\x01\x20\x01(\tR\x05email\x12\x1a\n\x08password\x18\x02\x20\
-    \x01(\tR\x08password\"%\n\rLoginResponse\x12\x14\n\x05token\x18\x01\x20\
-    \x01(\tR\x05token\"\x9e\x01\n\x07Package\x12\x12\n\x04name\x18\x01\x20\
-    \x01(\tR\x04name\x12\x20\n\x0bdescription\x18\x02\x20\x01(\tR\x0bdescrip\
-    tion\x12\x1a\n\x08homepage\x18\x03\x20\x01(\tR\x08homepage\x12%\n\x0erep\
-    ository_url\x18\x04\x20\x01(\tR\rrepositoryUrl\x12\x1a\n\x08keywords\x18\
-    \x05\x20\x03(\tR\x08keywords\",\n\x0ePublishRequest\x12\x1a\n\x08artifac\
-    t\x18\x01\x20\x01(\x0cR\x08artifact\")\n\x0fPublishResponse\x12\x16\n\
-    \x06result\x18\x01\x20\x01(\x08R\x06result2Y\n\x0bAuthService\x12J\n\x05\
-    Login\x12\x1e.buff_server_grpc.LoginRequest\x1a\x1f.buff_server_grpc.Log\
-    inResponse\"\02c\n\x0fRegistryService\x12P\n\x07Publish\x12\x20.buff_ser\
-    ver_grpc.PublishRequest\x1a!.buff_server_grpc.PublishResponse\"\0b\x06pr\
-    oto3\
-";
+#[derive(PartialEq,Clone,Default)]
+pub struct CompatibilityRejection {
+    // message fields
+    pub baseline: ::std::string::String,
+    pub violations: ::protobuf::RepeatedField<CompatibilityViolation>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
 
-static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
-    lock: ::protobuf::lazy::ONCE_INIT,
-    ptr: 0 as *const ::protobuf::descriptor::FileDescriptorProto,
-};
+impl<'a> ::std::default::Default for &'a CompatibilityRejection {
+    fn default() -> &'a CompatibilityRejection {
+        <CompatibilityRejection as ::protobuf::Message>::default_instance()
+    }
+}
 
-fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
-    ::protobuf::parse_from_bytes(file_descriptor_proto_data).unwrap()
+impl CompatibilityRejection {
+    pub fn new() -> CompatibilityRejection {
+        ::std::default::Default::default()
+    }
+
+    // string baseline = 1;
+
+
+    pub fn get_baseline(&self) -> &str {
+        &self.baseline
+    }
+    pub fn clear_baseline(&mut self) {
+        self.baseline.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_baseline(&mut self, v: ::std::string::String) {
+        self.baseline = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_baseline(&mut self) -> &mut ::std::string::String {
+        &mut self.baseline
+    }
+
+    // Take field
+    pub fn take_baseline(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.baseline, ::std::string::String::new())
+    }
+
+    // repeated .buff_server_grpc.CompatibilityViolation violations = 2;
+
+
+    pub fn get_violations(&self) -> &[CompatibilityViolation] {
+        &self.violations
+    }
+    pub fn clear_violations(&mut self) {
+        self.violations.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_violations(&mut self, v: ::protobuf::RepeatedField<CompatibilityViolation>) {
+        self.violations = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_violations(&mut self) -> &mut ::protobuf::RepeatedField<CompatibilityViolation> {
+        &mut self.violations
+    }
+
+    // Take field
+    pub fn take_violations(&mut self) -> ::protobuf::RepeatedField<CompatibilityViolation> {
+        ::std::mem::replace(&mut self.violations, ::protobuf::RepeatedField::new())
+    }
 }
 
-pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
-    unsafe {
-        file_descriptor_proto_lazy.get(|| {
-            parse_descriptor_proto()
+impl ::protobuf::Message for CompatibilityRejection {
+    fn is_initialized(&self) -> bool {
+        for v in &self.violations {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.baseline)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.violations)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.baseline.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.baseline);
+        }
+        for value in &self.violations {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.baseline.is_empty() {
+            os.write_string(1, &self.baseline)?;
+        }
+        for v in &self.violations {
+            os.write_tag(2, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> CompatibilityRejection {
+        CompatibilityRejection::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "baseline",
+                |m: &CompatibilityRejection| { &m.baseline },
+                |m: &mut CompatibilityRejection| { &mut m.baseline },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<CompatibilityViolation>>(
+                "violations",
+                |m: &CompatibilityRejection| { &m.violations },
+                |m: &mut CompatibilityRejection| { &mut m.violations },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<CompatibilityRejection>(
+                "CompatibilityRejection",
+                fields,
+                file_descriptor_proto()
+            )
         })
     }
+
+    fn default_instance() -> &'static CompatibilityRejection {
+        static instance: ::protobuf::rt::LazyV2<CompatibilityRejection> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(CompatibilityRejection::new)
+    }
+}
+
+impl ::protobuf::Clear for CompatibilityRejection {
+    fn clear(&mut self) {
+        self.baseline.clear();
+        self.violations.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for CompatibilityRejection {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for CompatibilityRejection {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct CompatibilityViolation {
+    // message fields
+    pub rule: ::std::string::String,
+    pub path: ::std::string::String,
+    pub message: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a CompatibilityViolation {
+    fn default() -> &'a CompatibilityViolation {
+        <CompatibilityViolation as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl CompatibilityViolation {
+    pub fn new() -> CompatibilityViolation {
+        ::std::default::Default::default()
+    }
+
+    // string rule = 1;
+
+
+    pub fn get_rule(&self) -> &str {
+        &self.rule
+    }
+    pub fn clear_rule(&mut self) {
+        self.rule.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_rule(&mut self, v: ::std::string::String) {
+        self.rule = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_rule(&mut self) -> &mut ::std::string::String {
+        &mut self.rule
+    }
+
+    // Take field
+    pub fn take_rule(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.rule, ::std::string::String::new())
+    }
+
+    // string path = 2;
+
+
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+    pub fn clear_path(&mut self) {
+        self.path.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_path(&mut self, v: ::std::string::String) {
+        self.path = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_path(&mut self) -> &mut ::std::string::String {
+        &mut self.path
+    }
+
+    // Take field
+    pub fn take_path(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.path, ::std::string::String::new())
+    }
+
+    // string message = 3;
+
+
+    pub fn get_message(&self) -> &str {
+        &self.message
+    }
+    pub fn clear_message(&mut self) {
+        self.message.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_message(&mut self, v: ::std::string::String) {
+        self.message = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_message(&mut self) -> &mut ::std::string::String {
+        &mut self.message
+    }
+
+    // Take field
+    pub fn take_message(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.message, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for CompatibilityViolation {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.rule)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.path)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.message)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.rule.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.rule);
+        }
+        if !self.path.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.path);
+        }
+        if !self.message.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.message);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.rule.is_empty() {
+            os.write_string(1, &self.rule)?;
+        }
+        if !self.path.is_empty() {
+            os.write_string(2, &self.path)?;
+        }
+        if !self.message.is_empty() {
+            os.write_string(3, &self.message)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> CompatibilityViolation {
+        CompatibilityViolation::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "rule",
+                |m: &CompatibilityViolation| { &m.rule },
+                |m: &mut CompatibilityViolation| { &mut m.rule },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "path",
+                |m: &CompatibilityViolation| { &m.path },
+                |m: &mut CompatibilityViolation| { &mut m.path },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "message",
+                |m: &CompatibilityViolation| { &m.message },
+                |m: &mut CompatibilityViolation| { &mut m.message },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<CompatibilityViolation>(
+                "CompatibilityViolation",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static CompatibilityViolation {
+        static instance: ::protobuf::rt::LazyV2<CompatibilityViolation> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(CompatibilityViolation::new)
+    }
+}
+
+impl ::protobuf::Clear for CompatibilityViolation {
+    fn clear(&mut self) {
+        self.rule.clear();
+        self.path.clear();
+        self.message.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for CompatibilityViolation {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for CompatibilityViolation {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct GetPackageVersionsRequest {
+    // message fields
+    pub name: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a GetPackageVersionsRequest {
+    fn default() -> &'a GetPackageVersionsRequest {
+        <GetPackageVersionsRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl GetPackageVersionsRequest {
+    pub fn new() -> GetPackageVersionsRequest {
+        ::std::default::Default::default()
+    }
+
+    // string name = 1;
+
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn clear_name(&mut self) {
+        self.name.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_name(&mut self, v: ::std::string::String) {
+        self.name = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_name(&mut self) -> &mut ::std::string::String {
+        &mut self.name
+    }
+
+    // Take field
+    pub fn take_name(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.name, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for GetPackageVersionsRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.name)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.name.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.name);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.name.is_empty() {
+            os.write_string(1, &self.name)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> GetPackageVersionsRequest {
+        GetPackageVersionsRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "name",
+                |m: &GetPackageVersionsRequest| { &m.name },
+                |m: &mut GetPackageVersionsRequest| { &mut m.name },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<GetPackageVersionsRequest>(
+                "GetPackageVersionsRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static GetPackageVersionsRequest {
+        static instance: ::protobuf::rt::LazyV2<GetPackageVersionsRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(GetPackageVersionsRequest::new)
+    }
+}
+
+impl ::protobuf::Clear for GetPackageVersionsRequest {
+    fn clear(&mut self) {
+        self.name.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for GetPackageVersionsRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for GetPackageVersionsRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct GetPackageVersionsResponse {
+    // message fields
+    pub versions: ::protobuf::RepeatedField<::std::string::String>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a GetPackageVersionsResponse {
+    fn default() -> &'a GetPackageVersionsResponse {
+        <GetPackageVersionsResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl GetPackageVersionsResponse {
+    pub fn new() -> GetPackageVersionsResponse {
+        ::std::default::Default::default()
+    }
+
+    // repeated string versions = 1;
+
+
+    pub fn get_versions(&self) -> &[::std::string::String] {
+        &self.versions
+    }
+    pub fn clear_versions(&mut self) {
+        self.versions.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_versions(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.versions = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_versions(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.versions
+    }
+
+    // Take field
+    pub fn take_versions(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.versions, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for GetPackageVersionsResponse {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.versions)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        for value in &self.versions {
+            my_size += ::protobuf::rt::string_size(1, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        for v in &self.versions {
+            os.write_string(1, &v)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> GetPackageVersionsResponse {
+        GetPackageVersionsResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "versions",
+                |m: &GetPackageVersionsResponse| { &m.versions },
+                |m: &mut GetPackageVersionsResponse| { &mut m.versions },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<GetPackageVersionsResponse>(
+                "GetPackageVersionsResponse",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static GetPackageVersionsResponse {
+        static instance: ::protobuf::rt::LazyV2<GetPackageVersionsResponse> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(GetPackageVersionsResponse::new)
+    }
+}
+
+impl ::protobuf::Clear for GetPackageVersionsResponse {
+    fn clear(&mut self) {
+        self.versions.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for GetPackageVersionsResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for GetPackageVersionsResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct DownloadRequest {
+    // message fields
+    pub name: ::std::string::String,
+    pub version: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a DownloadRequest {
+    fn default() -> &'a DownloadRequest {
+        <DownloadRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl DownloadRequest {
+    pub fn new() -> DownloadRequest {
+        ::std::default::Default::default()
+    }
+
+    // string name = 1;
+
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn clear_name(&mut self) {
+        self.name.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_name(&mut self, v: ::std::string::String) {
+        self.name = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_name(&mut self) -> &mut ::std::string::String {
+        &mut self.name
+    }
+
+    // Take field
+    pub fn take_name(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.name, ::std::string::String::new())
+    }
+
+    // string version = 2;
+
+
+    pub fn get_version(&self) -> &str {
+        &self.version
+    }
+    pub fn clear_version(&mut self) {
+        self.version.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_version(&mut self, v: ::std::string::String) {
+        self.version = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_version(&mut self) -> &mut ::std::string::String {
+        &mut self.version
+    }
+
+    // Take field
+    pub fn take_version(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.version, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for DownloadRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.name)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.version)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.name.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.name);
+        }
+        if !self.version.is_empty() {
+            my_size += ::protobuf::rt::string_size(2, &self.version);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.name.is_empty() {
+            os.write_string(1, &self.name)?;
+        }
+        if !self.version.is_empty() {
+            os.write_string(2, &self.version)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> DownloadRequest {
+        DownloadRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "name",
+                |m: &DownloadRequest| { &m.name },
+                |m: &mut DownloadRequest| { &mut m.name },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "version",
+                |m: &DownloadRequest| { &m.version },
+                |m: &mut DownloadRequest| { &mut m.version },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<DownloadRequest>(
+                "DownloadRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static DownloadRequest {
+        static instance: ::protobuf::rt::LazyV2<DownloadRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(DownloadRequest::new)
+    }
+}
+
+impl ::protobuf::Clear for DownloadRequest {
+    fn clear(&mut self) {
+        self.name.clear();
+        self.version.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for DownloadRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for DownloadRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct DownloadResponse {
+    // message fields
+    pub artifact: ::std::vec::Vec<u8>,
+    pub signature: ::std::vec::Vec<u8>,
+    pub public_key: ::std::vec::Vec<u8>,
+    pub provenance: ::std::vec::Vec<u8>,
+    pub provenance_signature: ::std::vec::Vec<u8>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a DownloadResponse {
+    fn default() -> &'a DownloadResponse {
+        <DownloadResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl DownloadResponse {
+    pub fn new() -> DownloadResponse {
+        ::std::default::Default::default()
+    }
+
+    // bytes artifact = 1;
+
+
+    pub fn get_artifact(&self) -> &[u8] {
+        &self.artifact
+    }
+    pub fn clear_artifact(&mut self) {
+        self.artifact.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_artifact(&mut self, v: ::std::vec::Vec<u8>) {
+        self.artifact = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_artifact(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.artifact
+    }
+
+    // Take field
+    pub fn take_artifact(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.artifact, ::std::vec::Vec::new())
+    }
+
+    // bytes signature = 2;
+
+
+    pub fn get_signature(&self) -> &[u8] {
+        &self.signature
+    }
+    pub fn clear_signature(&mut self) {
+        self.signature.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_signature(&mut self, v: ::std::vec::Vec<u8>) {
+        self.signature = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_signature(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.signature
+    }
+
+    // Take field
+    pub fn take_signature(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.signature, ::std::vec::Vec::new())
+    }
+
+    // bytes public_key = 3;
+
+
+    pub fn get_public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+    pub fn clear_public_key(&mut self) {
+        self.public_key.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_public_key(&mut self, v: ::std::vec::Vec<u8>) {
+        self.public_key = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_public_key(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.public_key
+    }
+
+    // Take field
+    pub fn take_public_key(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.public_key, ::std::vec::Vec::new())
+    }
+
+    // bytes provenance = 4;
+
+
+    pub fn get_provenance(&self) -> &[u8] {
+        &self.provenance
+    }
+    pub fn clear_provenance(&mut self) {
+        self.provenance.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_provenance(&mut self, v: ::std::vec::Vec<u8>) {
+        self.provenance = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_provenance(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.provenance
+    }
+
+    // Take field
+    pub fn take_provenance(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.provenance, ::std::vec::Vec::new())
+    }
+
+    // bytes provenance_signature = 5;
+
+
+    pub fn get_provenance_signature(&self) -> &[u8] {
+        &self.provenance_signature
+    }
+    pub fn clear_provenance_signature(&mut self) {
+        self.provenance_signature.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_provenance_signature(&mut self, v: ::std::vec::Vec<u8>) {
+        self.provenance_signature = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_provenance_signature(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.provenance_signature
+    }
+
+    // Take field
+    pub fn take_provenance_signature(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.provenance_signature, ::std::vec::Vec::new())
+    }
+}
+
+impl ::protobuf::Message for DownloadResponse {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.artifact)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.signature)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.public_key)?;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.provenance)?;
+                },
+                5 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.provenance_signature)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.artifact.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.artifact);
+        }
+        if !self.signature.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(2, &self.signature);
+        }
+        if !self.public_key.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(3, &self.public_key);
+        }
+        if !self.provenance.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(4, &self.provenance);
+        }
+        if !self.provenance_signature.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(5, &self.provenance_signature);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.artifact.is_empty() {
+            os.write_bytes(1, &self.artifact)?;
+        }
+        if !self.signature.is_empty() {
+            os.write_bytes(2, &self.signature)?;
+        }
+        if !self.public_key.is_empty() {
+            os.write_bytes(3, &self.public_key)?;
+        }
+        if !self.provenance.is_empty() {
+            os.write_bytes(4, &self.provenance)?;
+        }
+        if !self.provenance_signature.is_empty() {
+            os.write_bytes(5, &self.provenance_signature)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> DownloadResponse {
+        DownloadResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "artifact",
+                |m: &DownloadResponse| { &m.artifact },
+                |m: &mut DownloadResponse| { &mut m.artifact },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "signature",
+                |m: &DownloadResponse| { &m.signature },
+                |m: &mut DownloadResponse| { &mut m.signature },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "public_key",
+                |m: &DownloadResponse| { &m.public_key },
+                |m: &mut DownloadResponse| { &mut m.public_key },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "provenance",
+                |m: &DownloadResponse| { &m.provenance },
+                |m: &mut DownloadResponse| { &mut m.provenance },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "provenance_signature",
+                |m: &DownloadResponse| { &m.provenance_signature },
+                |m: &mut DownloadResponse| { &mut m.provenance_signature },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<DownloadResponse>(
+                "DownloadResponse",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static DownloadResponse {
+        static instance: ::protobuf::rt::LazyV2<DownloadResponse> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(DownloadResponse::new)
+    }
+}
+
+impl ::protobuf::Clear for DownloadResponse {
+    fn clear(&mut self) {
+        self.artifact.clear();
+        self.signature.clear();
+        self.public_key.clear();
+        self.provenance.clear();
+        self.provenance_signature.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for DownloadResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for DownloadResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct GetCapabilitiesRequest {
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a GetCapabilitiesRequest {
+    fn default() -> &'a GetCapabilitiesRequest {
+        <GetCapabilitiesRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl GetCapabilitiesRequest {
+    pub fn new() -> GetCapabilitiesRequest {
+        ::std::default::Default::default()
+    }
+}
+
+impl ::protobuf::Message for GetCapabilitiesRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> GetCapabilitiesRequest {
+        GetCapabilitiesRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let fields = ::std::vec::Vec::new();
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<GetCapabilitiesRequest>(
+                "GetCapabilitiesRequest",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static GetCapabilitiesRequest {
+        static instance: ::protobuf::rt::LazyV2<GetCapabilitiesRequest> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(GetCapabilitiesRequest::new)
+    }
+}
+
+impl ::protobuf::Clear for GetCapabilitiesRequest {
+    fn clear(&mut self) {
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for GetCapabilitiesRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for GetCapabilitiesRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct GetCapabilitiesResponse {
+    // message fields
+    pub compression: ::protobuf::RepeatedField<::std::string::String>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a GetCapabilitiesResponse {
+    fn default() -> &'a GetCapabilitiesResponse {
+        <GetCapabilitiesResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl GetCapabilitiesResponse {
+    pub fn new() -> GetCapabilitiesResponse {
+        ::std::default::Default::default()
+    }
+
+    // repeated string compression = 1;
+
+
+    pub fn get_compression(&self) -> &[::std::string::String] {
+        &self.compression
+    }
+    pub fn clear_compression(&mut self) {
+        self.compression.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_compression(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.compression = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_compression(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.compression
+    }
+
+    // Take field
+    pub fn take_compression(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.compression, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for GetCapabilitiesResponse {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.compression)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        for value in &self.compression {
+            my_size += ::protobuf::rt::string_size(1, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        for v in &self.compression {
+            os.write_string(1, &v)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> GetCapabilitiesResponse {
+        GetCapabilitiesResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                "compression",
+                |m: &GetCapabilitiesResponse| { &m.compression },
+                |m: &mut GetCapabilitiesResponse| { &mut m.compression },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<GetCapabilitiesResponse>(
+                "GetCapabilitiesResponse",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static GetCapabilitiesResponse {
+        static instance: ::protobuf::rt::LazyV2<GetCapabilitiesResponse> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(GetCapabilitiesResponse::new)
+    }
+}
+
+impl ::protobuf::Clear for GetCapabilitiesResponse {
+    fn clear(&mut self) {
+        self.compression.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for GetCapabilitiesResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for GetCapabilitiesResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+static file_descriptor_proto_data: &'static [u8] = b"\
+    \n\nbuff.proto\x12\x10buff_server_grpc\"@\n\x0cLoginRequest\x12\x14\n\
+    \x05email\x18\x01\x20\x01(\tR\x05email\x12\x1a\n\x08password\x18\x02\x20\
+    \x01(\tR\x08password\"%\n\rLoginResponse\x12\x14\n\x05token\x18\x01\x20\
+    \x01(\tR\x05token\"\x9e\x01\n\x07Package\x12\x12\n\x04name\x18\x01\x20\
+    \x01(\tR\x04name\x12\x20\n\x0bdescription\x18\x02\x20\x01(\tR\x0bdescrip\
+    tion\x12\x1a\n\x08homepage\x18\x03\x20\x01(\tR\x08homepage\x12%\n\x0erep\
+    ository_url\x18\x04\x20\x01(\tR\rrepositoryUrl\x12\x1a\n\x08keywords\x18\
+    \x05\x20\x03(\tR\x08keywords\"\xe1\x01\n\x0ePublishRequest\x12\x1a\n\x08\
+    artifact\x18\x01\x20\x01(\x0cR\x08artifact\x12#\n\rvalidate_only\x18\x02\
+    \x20\x01(\x08R\x0cvalidateOnly\x12\x1c\n\tsignature\x18\x03\x20\x01(\x0c\
+    R\tsignature\x12\x1d\n\npublic_key\x18\x04\x20\x01(\x0cR\tpublicKey\x12\
+    \x1e\n\nprovenance\x18\x05\x20\x01(\x0cR\nprovenance\x121\n\x14provenanc\
+    e_signature\x18\x06\x20\x01(\x0cR\x13provenanceSignature\"q\n\x0fPublish\
+    Response\x12\x16\n\x06result\x18\x01\x20\x01(\x08R\x06result\x12F\n\trej\
+    ection\x18\x02\x20\x01(\x0b2(.buff_server_grpc.CompatibilityRejectionR\t\
+    rejection\"~\n\x16CompatibilityRejection\x12\x1a\n\x08baseline\x18\x01\
+    \x20\x01(\tR\x08baseline\x12H\n\nviolations\x18\x02\x20\x03(\x0b2(.buff_\
+    server_grpc.CompatibilityViolationR\nviolations\"Z\n\x16CompatibilityVio\
+    lation\x12\x12\n\x04rule\x18\x01\x20\x01(\tR\x04rule\x12\x12\n\x04path\
+    \x18\x02\x20\x01(\tR\x04path\x12\x18\n\x07message\x18\x03\x20\x01(\tR\
+    \x07message\"/\n\x19GetPackageVersionsRequest\x12\x12\n\x04name\x18\x01\
+    \x20\x01(\tR\x04name\"8\n\x1aGetPackageVersionsResponse\x12\x1a\n\x08ver\
+    sions\x18\x01\x20\x03(\tR\x08versions\"?\n\x0fDownloadRequest\x12\x12\n\
+    \x04name\x18\x01\x20\x01(\tR\x04name\x12\x18\n\x07version\x18\x02\x20\
+    \x01(\tR\x07version\"\xbe\x01\n\x10DownloadResponse\x12\x1a\n\x08artifac\
+    t\x18\x01\x20\x01(\x0cR\x08artifact\x12\x1c\n\tsignature\x18\x02\x20\x01\
+    (\x0cR\tsignature\x12\x1d\n\npublic_key\x18\x03\x20\x01(\x0cR\tpublicKey\
+    \x12\x1e\n\nprovenance\x18\x04\x20\x01(\x0cR\nprovenance\x121\n\x14prove\
+    nance_signature\x18\x05\x20\x01(\x0cR\x13provenanceSignature\"\x18\n\x16\
+    GetCapabilitiesRequest\";\n\x17GetCapabilitiesResponse\x12\x20\n\x0bcomp\
+    ression\x18\x01\x20\x03(\tR\x0bcompression2Y\n\x0bAuthService\x12J\n\x05\
+    Login\x12\x1e.buff_server_grpc.LoginRequest\x1a\x1f.buff_server_grpc.Log\
+    inResponse\"\02\xef\x03\n\x0fRegistryService\x12P\n\x07Publish\x12\x20.b\
+    uff_server_grpc.PublishRequest\x1a!.buff_server_grpc.PublishResponse\"\0\
+    \x12X\n\rPublishStream\x12\x20.buff_server_grpc.PublishRequest\x1a!.buff\
+    _server_grpc.PublishResponse\"\0(\x01\x12q\n\x12GetPackageVersions\x12+.\
+    buff_server_grpc.GetPackageVersionsRequest\x1a,.buff_server_grpc.GetPack\
+    ageVersionsResponse\"\0\x12S\n\x08Download\x12!.buff_server_grpc.Downloa\
+    dRequest\x1a\".buff_server_grpc.DownloadResponse\"\0\x12h\n\x0fGetCapabi\
+    lities\x12(.buff_server_grpc.GetCapabilitiesRequest\x1a).buff_server_grp\
+    c.GetCapabilitiesResponse\"\0b\x06proto3\
+";
+
+static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
+
+fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
+    ::protobuf::Message::parse_from_bytes(file_descriptor_proto_data).unwrap()
+}
+
+pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
+    file_descriptor_proto_lazy.get(|| {
+        parse_descriptor_proto()
+    })
 }
@@ -77,6 +77,34 @@ const METHOD_REGISTRY_SERVICE_PUBLISH: ::grpcio::Method<super::buff::PublishRequ
     resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
 };
 
+const METHOD_REGISTRY_SERVICE_PUBLISH_STREAM: ::grpcio::Method<super::buff::PublishRequest, super::buff::PublishResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::ClientStreaming,
+    name: "/buff_server_grpc.RegistryService/PublishStream",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_REGISTRY_SERVICE_GET_PACKAGE_VERSIONS: ::grpcio::Method<super::buff::GetPackageVersionsRequest, super::buff::GetPackageVersionsResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/buff_server_grpc.RegistryService/GetPackageVersions",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_REGISTRY_SERVICE_DOWNLOAD: ::grpcio::Method<super::buff::DownloadRequest, super::buff::DownloadResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/buff_server_grpc.RegistryService/Download",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
+const METHOD_REGISTRY_SERVICE_GET_CAPABILITIES: ::grpcio::Method<super::buff::GetCapabilitiesRequest, super::buff::GetCapabilitiesResponse> = ::grpcio::Method {
+    ty: ::grpcio::MethodType::Unary,
+    name: "/buff_server_grpc.RegistryService/GetCapabilities",
+    req_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+    resp_mar: ::grpcio::Marshaller { ser: ::grpcio::pb_ser, de: ::grpcio::pb_de },
+};
+
 #[derive(Clone)]
 pub struct RegistryServiceClient {
     client: ::grpcio::Client,
@@ -104,6 +132,62 @@ impl RegistryServiceClient {
     pub fn publish_async(&self, req: &super::buff::PublishRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::buff::PublishResponse>> {
         self.publish_async_opt(req, ::grpcio::CallOption::default())
     }
+
+    pub fn publish_stream_opt(&self, opt: ::grpcio::CallOption) -> ::grpcio::Result<(::grpcio::ClientCStreamSender<super::buff::PublishRequest>, ::grpcio::ClientCStreamReceiver<super::buff::PublishResponse>)> {
+        self.client.client_streaming(&METHOD_REGISTRY_SERVICE_PUBLISH_STREAM, opt)
+    }
+
+    pub fn publish_stream(&self) -> ::grpcio::Result<(::grpcio::ClientCStreamSender<super::buff::PublishRequest>, ::grpcio::ClientCStreamReceiver<super::buff::PublishResponse>)> {
+        self.publish_stream_opt(::grpcio::CallOption::default())
+    }
+
+    pub fn get_package_versions_opt(&self, req: &super::buff::GetPackageVersionsRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::buff::GetPackageVersionsResponse> {
+        self.client.unary_call(&METHOD_REGISTRY_SERVICE_GET_PACKAGE_VERSIONS, req, opt)
+    }
+
+    pub fn get_package_versions(&self, req: &super::buff::GetPackageVersionsRequest) -> ::grpcio::Result<super::buff::GetPackageVersionsResponse> {
+        self.get_package_versions_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn get_package_versions_async_opt(&self, req: &super::buff::GetPackageVersionsRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::buff::GetPackageVersionsResponse>> {
+        self.client.unary_call_async(&METHOD_REGISTRY_SERVICE_GET_PACKAGE_VERSIONS, req, opt)
+    }
+
+    pub fn get_package_versions_async(&self, req: &super::buff::GetPackageVersionsRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::buff::GetPackageVersionsResponse>> {
+        self.get_package_versions_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn download_opt(&self, req: &super::buff::DownloadRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::buff::DownloadResponse> {
+        self.client.unary_call(&METHOD_REGISTRY_SERVICE_DOWNLOAD, req, opt)
+    }
+
+    pub fn download(&self, req: &super::buff::DownloadRequest) -> ::grpcio::Result<super::buff::DownloadResponse> {
+        self.download_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn download_async_opt(&self, req: &super::buff::DownloadRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::buff::DownloadResponse>> {
+        self.client.unary_call_async(&METHOD_REGISTRY_SERVICE_DOWNLOAD, req, opt)
+    }
+
+    pub fn download_async(&self, req: &super::buff::DownloadRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::buff::DownloadResponse>> {
+        self.download_async_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn get_capabilities_opt(&self, req: &super::buff::GetCapabilitiesRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<super::buff::GetCapabilitiesResponse> {
+        self.client.unary_call(&METHOD_REGISTRY_SERVICE_GET_CAPABILITIES, req, opt)
+    }
+
+    pub fn get_capabilities(&self, req: &super::buff::GetCapabilitiesRequest) -> ::grpcio::Result<super::buff::GetCapabilitiesResponse> {
+        self.get_capabilities_opt(req, ::grpcio::CallOption::default())
+    }
+
+    pub fn get_capabilities_async_opt(&self, req: &super::buff::GetCapabilitiesRequest, opt: ::grpcio::CallOption) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::buff::GetCapabilitiesResponse>> {
+        self.client.unary_call_async(&METHOD_REGISTRY_SERVICE_GET_CAPABILITIES, req, opt)
+    }
+
+    pub fn get_capabilities_async(&self, req: &super::buff::GetCapabilitiesRequest) -> ::grpcio::Result<::grpcio::ClientUnaryReceiver<super::buff::GetCapabilitiesResponse>> {
+        self.get_capabilities_async_opt(req, ::grpcio::CallOption::default())
+    }
     pub fn spawn<F>(&self, f: F) where F: ::futures::Future<Item = (), Error = ()> + Send + 'static {
         self.client.spawn(f)
     }
@@ -111,6 +195,10 @@ impl RegistryServiceClient {
 
 pub trait RegistryService {
     fn publish(&mut self, ctx: ::grpcio::RpcContext, req: super::buff::PublishRequest, sink: ::grpcio::UnarySink<super::buff::PublishResponse>);
+    fn publish_stream(&mut self, ctx: ::grpcio::RpcContext, stream: ::grpcio::RequestStream<super::buff::PublishRequest>, sink: ::grpcio::ClientStreamingSink<super::buff::PublishResponse>);
+    fn get_package_versions(&mut self, ctx: ::grpcio::RpcContext, req: super::buff::GetPackageVersionsRequest, sink: ::grpcio::UnarySink<super::buff::GetPackageVersionsResponse>);
+    fn download(&mut self, ctx: ::grpcio::RpcContext, req: super::buff::DownloadRequest, sink: ::grpcio::UnarySink<super::buff::DownloadResponse>);
+    fn get_capabilities(&mut self, ctx: ::grpcio::RpcContext, req: super::buff::GetCapabilitiesRequest, sink: ::grpcio::UnarySink<super::buff::GetCapabilitiesResponse>);
 }
 
 pub fn create_registry_service<S: RegistryService + Send + Clone + 'static>(s: S) -> ::grpcio::Service {
@@ -119,5 +207,21 @@ pub fn create_registry_service<S: RegistryService + Send + Clone + 'static>(s: S
     builder = builder.add_unary_handler(&METHOD_REGISTRY_SERVICE_PUBLISH, move |ctx, req, resp| {
         instance.publish(ctx, req, resp)
     });
+    let mut instance = s.clone();
+    builder = builder.add_client_streaming_handler(&METHOD_REGISTRY_SERVICE_PUBLISH_STREAM, move |ctx, req, resp| {
+        instance.publish_stream(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_REGISTRY_SERVICE_GET_PACKAGE_VERSIONS, move |ctx, req, resp| {
+        instance.get_package_versions(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_REGISTRY_SERVICE_DOWNLOAD, move |ctx, req, resp| {
+        instance.download(ctx, req, resp)
+    });
+    let mut instance = s.clone();
+    builder = builder.add_unary_handler(&METHOD_REGISTRY_SERVICE_GET_CAPABILITIES, move |ctx, req, resp| {
+        instance.get_capabilities(ctx, req, resp)
+    });
     builder.build()
 }
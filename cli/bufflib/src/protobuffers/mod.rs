@@ -1,2 +1,101 @@
+// Generated from protobuffers/buff.proto and checked in, so building bufflib
+// doesn't need protoc. After changing buff.proto, regenerate the code from
+// this crate's directory with protoc, the grpc_rust_plugin of grpcio-compiler
+// 0.4 and the protoc-gen-rust of rust-protobuf 2.28, the versions of grpcio
+// and protobuf in Cargo.toml, generated code only works with the runtime of
+// its own version:
+//
+//   protoc -I ../../protobuffers --rust_out=src/protobuffers \
+//     --grpc_out=src/protobuffers \
+//     --plugin=protoc-gen-grpc=`which grpc_rust_plugin` \
+//     ../../protobuffers/buff.proto
+//
+// `should_match_buff_proto` fails while the code is out of date.
 pub mod buff;
-pub mod buff_grpc;
\ No newline at end of file
+// grpcio-compiler 0.4 allows `clippy` in its header, which clippy no longer
+// knows.
+#[allow(clippy::all)]
+pub mod buff_grpc;
+
+#[test]
+#[cfg_attr(feature = "standalone", ignore)]
+fn should_match_buff_proto() {
+  use crate::descriptor;
+  use crate::proto::{self, SourceFile};
+  use regex::Regex;
+  use std::fs;
+  use std::path::{Path, PathBuf};
+
+  let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../protobuffers/buff.proto");
+  let source = fs::read_to_string(&path).unwrap_or_else(|err| {
+    panic!(
+      "Failed to read {}: {}, build with --features standalone outside the buff repository",
+      path.display(),
+      err
+    )
+  });
+  let file = proto::parse_file(&path, &source).unwrap_or_else(|err| panic!("{}", err));
+  let files = vec![SourceFile {
+    path: PathBuf::from("buff.proto"),
+    file,
+  }];
+  let mut linked = descriptor::link(&files)
+    .unwrap_or_else(|err| panic!("{}", err))
+    .remove(0);
+  linked.clear_source_code_info();
+  // protoc gives rpcs declared with a `{}` body empty options,
+  // which makes no difference to the generated code.
+  let mut generated = buff::file_descriptor_proto().clone();
+  for service in generated.mut_service().iter_mut() {
+    for method in service.mut_method().iter_mut() {
+      if *method.get_options() == Default::default() {
+        method.clear_options();
+      }
+    }
+  }
+  let out_of_date =
+    "src/protobuffers is out of date with buff.proto, see src/protobuffers/mod.rs for how to regenerate it";
+  // The descriptor embedded in buff.rs doesn't cover the grpc code, so the
+  // methods declared in buff_grpc.rs are compared on their own.
+  let expected: Vec<String> = linked
+    .get_service()
+    .iter()
+    .flat_map(|service| {
+      let package = linked.get_package();
+      service.get_method().iter().map(move |method| {
+        let method_type = match (method.get_client_streaming(), method.get_server_streaming()) {
+          (false, false) => "Unary",
+          (true, false) => "ClientStreaming",
+          (false, true) => "ServerStreaming",
+          (true, true) => "Duplex",
+        };
+        let message = |type_name: &str| type_name.rsplit('.').next().unwrap().to_string();
+        format!(
+          "{} /{}.{}/{} {} {}",
+          method_type,
+          package,
+          service.get_name(),
+          method.get_name(),
+          message(method.get_input_type()),
+          message(method.get_output_type())
+        )
+      })
+    })
+    .collect();
+  let method = Regex::new(
+    r#"::grpcio::Method<super::buff::(\w+), super::buff::(\w+)> = ::grpcio::Method \{\s*ty: ::grpcio::MethodType::(\w+),\s*name: "([^"]+)""#,
+  )
+  .unwrap();
+  let declared: Vec<String> = method
+    .captures_iter(include_str!("buff_grpc.rs"))
+    .map(|method| {
+      format!(
+        "{} {} {} {}",
+        &method[3], &method[4], &method[1], &method[2]
+      )
+    })
+    .collect();
+  assert!(!declared.is_empty(), "{}", out_of_date);
+  assert_eq!(declared, expected, "{}", out_of_date);
+  assert!(linked == generated, "{}", out_of_date);
+}
@@ -0,0 +1,198 @@
+use crate::buff_cli_config::TrustedKeys;
+use crate::git::{self, GitState};
+use crate::signing::{self, ArtifactSignature, TrustError};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::path::Path;
+
+pub const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v0.1";
+pub const PREDICATE_TYPE: &str = "https://github.com/BlueHotDog/buff/provenance/v1";
+
+/// An in-toto statement saying where a published artifact came from. It's
+/// only recorded for signed artifacts, and stored next to the artifact signed
+/// with the same key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Statement {
+  #[serde(rename = "_type")]
+  pub statement_type: String,
+  pub subject: Vec<Subject>,
+  #[serde(rename = "predicateType")]
+  pub predicate_type: String,
+  pub predicate: Provenance,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subject {
+  /// `<name>@<version>` of the package.
+  pub name: String,
+  pub digest: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+  /// `None` when the package isn't in a git repository.
+  pub git: Option<GitState>,
+  pub build_host: String,
+  pub tool: String,
+  /// RFC 3339 time the statement was recorded.
+  pub timestamp: String,
+  pub publisher: Publisher,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Publisher {
+  /// The local user that ran `buff publish`.
+  pub user: String,
+  pub email: Option<String>,
+  /// Public key of the key the artifact was signed with.
+  pub public_key: String,
+}
+
+/// A statement as stored in the registry, with its detached signature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedStatement {
+  pub statement: Vec<u8>,
+  pub signature: Option<ArtifactSignature>,
+}
+
+/// Records provenance for the artifact of the package in `package_dir` with
+/// the hex-encoded SHA-256 `sha256`.
+pub fn record(
+  package_dir: &Path,
+  name: &str,
+  version: &str,
+  sha256: &str,
+  public_key: String,
+) -> Statement {
+  let mut digest = BTreeMap::new();
+  digest.insert("sha256".to_string(), sha256.to_string());
+  Statement {
+    statement_type: STATEMENT_TYPE.to_string(),
+    subject: vec![Subject {
+      name: format!("{}@{}", name, version),
+      digest,
+    }],
+    predicate_type: PREDICATE_TYPE.to_string(),
+    predicate: Provenance {
+      git: git::state(package_dir),
+      build_host: hostname::get_hostname().unwrap_or_else(|| "unknown".to_string()),
+      tool: format!("buff {}", env!("CARGO_PKG_VERSION")),
+      timestamp: chrono::Utc::now().to_rfc3339(),
+      publisher: Publisher {
+        user: env::var("USER")
+          .or_else(|_| env::var("USERNAME"))
+          .unwrap_or_else(|_| "unknown".to_string()),
+        email: git::user_email(package_dir),
+        public_key,
+      },
+    },
+  }
+}
+
+impl Statement {
+  pub fn to_bytes(&self) -> Vec<u8> {
+    serde_json::to_vec_pretty(self).unwrap()
+  }
+}
+
+/// Why a package's provenance couldn't be trusted.
+#[derive(Debug, PartialEq)]
+pub enum ProvenanceError {
+  Missing,
+  Unsigned,
+  Invalid(String),
+  WrongSubject,
+  Untrusted(TrustError),
+}
+
+impl fmt::Display for ProvenanceError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ProvenanceError::Missing => write!(f, "the package was published without provenance"),
+      ProvenanceError::Unsigned => write!(f, "the provenance statement isn't signed"),
+      ProvenanceError::Invalid(err) => write!(f, "invalid provenance statement: {}", err),
+      ProvenanceError::WrongSubject => {
+        write!(f, "the provenance statement is about a different artifact")
+      }
+      ProvenanceError::Untrusted(err) => write!(f, "provenance statement {}", err),
+    }
+  }
+}
+
+/// Parses the provenance stored for `<name>@<version>` and checks that it
+/// describes the artifact with the hex-encoded SHA-256 `sha256` and that its
+/// signature satisfies `trusted`. Unsigned statements are refused whatever
+/// `trusted` says. Returns the statement and its signer.
+pub fn verify(
+  signed: Option<&SignedStatement>,
+  trusted: Option<&TrustedKeys>,
+  name: &str,
+  version: &str,
+  sha256: &str,
+) -> Result<(Statement, String), ProvenanceError> {
+  let signed = signed.ok_or(ProvenanceError::Missing)?;
+  let signature = signed.signature.as_ref().ok_or(ProvenanceError::Unsigned)?;
+  let statement: Statement = serde_json::from_slice(&signed.statement)
+    .map_err(|err| ProvenanceError::Invalid(err.to_string()))?;
+  let subject = format!("{}@{}", name, version);
+  let describes_artifact = statement
+    .subject
+    .iter()
+    .any(|s| s.name == subject && s.digest.get("sha256").map(String::as_str) == Some(sha256));
+  if statement.statement_type != STATEMENT_TYPE || !describes_artifact {
+    return Err(ProvenanceError::WrongSubject);
+  }
+  let signer = signing::check_message_signature(trusted, &signed.statement, Some(signature))
+    .map_err(ProvenanceError::Untrusted)?
+    .expect("A signed statement has a signer");
+  Ok((statement, signer))
+}
+
+#[test]
+fn should_verify_statements() {
+  let keypair = ed25519_dalek::Keypair::generate(&mut rand::rngs::OsRng);
+  let sha256 = hex::encode([3u8; 32]);
+  let signer = hex::encode(keypair.public.to_bytes());
+  let statement = record(Path::new("."), "billing", "1.0.0", &sha256, signer.clone());
+  assert_eq!(statement.subject[0].name, "billing@1.0.0");
+  assert_eq!(
+    statement.predicate.tool,
+    format!("buff {}", env!("CARGO_PKG_VERSION"))
+  );
+  let bytes = statement.to_bytes();
+  let signed = SignedStatement {
+    signature: Some(signing::sign_message(&keypair, &bytes)),
+    statement: bytes,
+  };
+  assert_eq!(
+    verify(Some(&signed), None, "billing", "1.0.0", &sha256),
+    Ok((statement, signer))
+  );
+  assert_eq!(
+    verify(Some(&signed), None, "billing", "1.0.1", &sha256),
+    Err(ProvenanceError::WrongSubject)
+  );
+  assert_eq!(
+    verify(None, None, "billing", "1.0.0", &sha256),
+    Err(ProvenanceError::Missing)
+  );
+  let unsigned = SignedStatement {
+    signature: None,
+    statement: signed.statement.clone(),
+  };
+  assert_eq!(
+    verify(Some(&unsigned), None, "billing", "1.0.0", &sha256),
+    Err(ProvenanceError::Unsigned)
+  );
+  let mut tampered = signed.clone();
+  tampered.statement = String::from_utf8(tampered.statement)
+    .unwrap()
+    .replace("\"build_host\"", " \"build_host\"")
+    .into_bytes();
+  assert_eq!(
+    verify(Some(&tampered), None, "billing", "1.0.0", &sha256),
+    Err(ProvenanceError::Untrusted(TrustError::BadSignature))
+  );
+}
@@ -1,13 +1,30 @@
 use crate::artifact;
-use crate::buff_cli_config::BuffCliConfig;
+use crate::artifact::{Artifact, EntryKind, ExtractError};
+use crate::buff_cli_config::{self, BuffCliConfig};
+use crate::codec::Codec;
+use crate::git;
+use crate::manifest;
 use crate::package_metadata::PackageMetadata;
-use crate::protobuffers::buff::PublishRequest;
+use crate::protobuffers::buff::CompatibilityRejection;
+use crate::protobuffers::buff::DownloadRequest;
+use crate::protobuffers::buff::GetCapabilitiesRequest;
+use crate::protobuffers::buff::GetPackageVersionsRequest;
 use crate::protobuffers::buff::{LoginRequest, LoginResponse};
+use crate::protobuffers::buff::{PublishRequest, PublishResponse};
 use crate::protobuffers::buff_grpc::AuthServiceClient;
 use crate::protobuffers::buff_grpc::RegistryServiceClient;
-use grpcio::{Channel, ChannelBuilder, EnvBuilder};
+use crate::provenance::{self, SignedStatement, Statement};
+use crate::signing::{self, ArtifactSignature, TrustError};
+use crate::verify;
+use crate::verify::{Check, VerificationError};
+use crate::workspace::{Member, Workspace};
+use futures::{future, Future, Sink};
+use grpcio::{Channel, ChannelBuilder, EnvBuilder, WriteFlags};
+use std::fmt;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tempdir::TempDir;
 
 pub fn login(email: &str, password: &str) {
   let mut config = BuffCliConfig::new();
@@ -30,32 +47,416 @@ pub fn login(email: &str, password: &str) {
   // trace!("Successfully saved JWT token to local user config");
 }
 
-pub fn publish() {
+#[derive(Default)]
+pub struct PublishOptions {
+  /// Have the registry validate the package without storing it.
+  pub dry_run: bool,
+  /// Skip the checks configured in the `[publish]` section.
+  pub no_verify: bool,
+  /// Publish even if files in the package differ from the git commit.
+  pub allow_dirty: bool,
+  /// Overrides the `compression` set in `[package]`.
+  pub codec: Option<Codec>,
+  /// Name of the key under BUFF_HOME to sign the artifact with.
+  pub signing_key: Option<String>,
+}
+
+/// What was (or with `dry_run`, would have been) published.
+pub struct PublishSummary {
+  pub name: String,
+  pub version: String,
+  pub registry_url: String,
+  pub artifact_size: u64,
+  pub sha256: String,
+  /// The codec the artifact was compressed with, after negotiating with the
+  /// registry.
+  pub codec: Codec,
+  /// The codec that was asked for, when the registry couldn't read it.
+  pub unsupported_codec: Option<Codec>,
+  /// Public key of the key the artifact was signed with.
+  pub signed_by: Option<String>,
+  /// The provenance recorded for the artifact, only when it's signed.
+  pub provenance: Option<Statement>,
+  pub files: Vec<PathBuf>,
+  /// The pre-publish checks that passed.
+  pub verified: Vec<Check>,
+  /// The git tag created for the release when `[publish] tag` is set, or why
+  /// it couldn't be.
+  pub tag: Option<Result<String, String>>,
+}
+
+/// Builds the package's artifact, runs the pre-publish checks over it and
+/// uploads it. Packages with uncommitted changes are refused unless
+/// `allow_dirty` is set, so what's published can be traced to a commit.
+pub fn publish(
+  manifest_path: &Path,
+  options: &PublishOptions,
+) -> Result<PublishSummary, VerificationError> {
   let config = BuffCliConfig::new();
   let registry_url = config.preferred_registry.clone();
   let channel = get_channel(registry_url.as_str());
   let client = RegistryServiceClient::new(channel);
-  let metadata = PackageMetadata::new(&get_buff_toml_path().to_str().unwrap());
-  let artifact_bytes = artifact::get_artifact_bytes(get_target_path().to_str().unwrap());
-  artifact::save_artifact_to_path(get_target_path().to_str().unwrap(), "/tmp/abc");
+  let package_dir = manifest::package_dir(manifest_path);
+  let metadata = PackageMetadata::new(&manifest_path.to_str().unwrap());
+  if !options.allow_dirty {
+    let changes = git::uncommitted_changes(&package_dir);
+    if !changes.is_empty() {
+      return Err(VerificationError {
+        package: metadata.package.name,
+        check: Check::Clean,
+        problems: changes
+          .iter()
+          .map(|path| format!("{} has uncommitted changes", path.display()))
+          .collect(),
+      });
+    }
+  }
+  // The artifact is built into a temporary file and uploaded
+  // from it in chunks, so memory use doesn't depend on its size.
+  let tmp_dir = TempDir::new("buff_publish").expect("Failed to create a temporary directory");
+  let artifact_path = tmp_dir.path().join("artifact");
+  let requested = options
+    .codec
+    .or(metadata.package.compression)
+    .unwrap_or_default();
+  let codec = negotiate_codec(&registry_url, requested);
+  let digest = artifact::save_artifact_to_path(
+    package_dir.to_str().unwrap(),
+    codec,
+    artifact_path.to_str().unwrap(),
+  );
+  let artifact = Artifact::open(&artifact_path);
+  let keypair = options
+    .signing_key
+    .as_ref()
+    .map(|name| signing::load_key(&signing::keys_dir(&buff_cli_config::buff_home()), name));
+  let signature = keypair
+    .as_ref()
+    .map(|keypair| signing::sign(keypair, &digest.sha256));
+  // Provenance is only worth anything signed, so unsigned artifacts go
+  // without it.
+  let statement = keypair.as_ref().map(|keypair| {
+    let statement = provenance::record(
+      &package_dir,
+      &metadata.package.name,
+      &metadata.package.version,
+      &digest.sha256,
+      hex::encode(keypair.public.to_bytes()),
+    );
+    let signature = signing::sign_message(keypair, &statement.to_bytes());
+    (statement, signature)
+  });
+  let verified = if options.no_verify {
+    Vec::new()
+  } else {
+    verify::verify(manifest_path, &artifact, &registry_url)?
+  };
+  let create_tag = metadata.publish.tag && !options.dry_run;
+  let mut summary = PublishSummary {
+    name: metadata.package.name,
+    version: metadata.package.version,
+    registry_url,
+    artifact_size: digest.size,
+    sha256: digest.sha256,
+    codec,
+    unsupported_codec: if codec == requested {
+      None
+    } else {
+      Some(requested)
+    },
+    signed_by: signature
+      .as_ref()
+      .map(|signature| hex::encode(&signature.public_key)),
+    provenance: statement.as_ref().map(|(statement, _)| statement.clone()),
+    files: artifact
+      .entries()
+      .unwrap_or_else(|err| panic!("Failed to read the artifact: {}", err))
+      .into_iter()
+      .filter(|entry| entry.kind == EntryKind::File)
+      .map(|entry| entry.path)
+      .collect(),
+    verified,
+    tag: None,
+  };
   let mut req = PublishRequest::new();
-  req.set_artifact(artifact_bytes);
-  client.publish(&req).expect("Failed gRPC publish call");
+  req.set_validate_only(options.dry_run);
+  if let Some(signature) = signature {
+    req.set_signature(signature.signature);
+    req.set_public_key(signature.public_key);
+  }
+  if let Some((statement, signature)) = statement {
+    req.set_provenance(statement.to_bytes());
+    req.set_provenance_signature(signature.signature);
+  }
+  let reply = upload(&client, &artifact, req).map_err(|err| VerificationError {
+    package: summary.name.clone(),
+    check: Check::Connection,
+    problems: vec![format!(
+      "failed to upload to {}: {}",
+      summary.registry_url, err
+    )],
+  })?;
+  if reply.has_rejection() {
+    return Err(rejection_error(&summary.name, reply.get_rejection()));
+  }
+  if create_tag {
+    let tag = format!("{}-v{}", summary.name, summary.version);
+    let message = format!("{} {}", summary.name, summary.version);
+    summary.tag = Some(git::create_tag(&package_dir, &tag, &message).map(|_| tag));
+  }
+  Ok(summary)
 }
 
-fn get_channel(grpc_server_url: &str) -> Channel {
-  let env = Arc::new(EnvBuilder::new().build());
-  return ChannelBuilder::new(env).connect(grpc_server_url);
+/// Size of the chunks artifacts are uploaded in.
+const UPLOAD_CHUNK_SIZE: usize = 1024 * 1024;
+
+// The first request carries the fields of `req` along with the first
+// chunk of the artifact, the following ones only the next chunk.
+fn upload(
+  client: &RegistryServiceClient,
+  artifact: &Artifact,
+  mut req: PublishRequest,
+) -> grpcio::Result<PublishResponse> {
+  let (mut sink, receiver) = client.publish_stream()?;
+  let mut reader = artifact.reader().expect("Failed to read artifact");
+  let mut chunk = vec![0; UPLOAD_CHUNK_SIZE];
+  loop {
+    let read = reader.read(&mut chunk).expect("Failed to read artifact");
+    if read == 0 {
+      break;
+    }
+    req.set_artifact(chunk[..read].to_vec());
+    sink = sink.send((req, WriteFlags::default())).wait()?;
+    req = PublishRequest::new();
+  }
+  future::poll_fn(|| sink.close()).wait()?;
+  receiver.wait()
+}
+
+/// Registries that enforce compatibility refuse packages that break an
+/// earlier version in the same major line, listing what broke.
+fn rejection_error(package: &str, rejection: &CompatibilityRejection) -> VerificationError {
+  VerificationError {
+    package: package.to_string(),
+    check: Check::Registry,
+    problems: rejection
+      .get_violations()
+      .iter()
+      .map(|violation| {
+        format!(
+          "{} {}: {} (since {})",
+          violation.get_rule(),
+          violation.get_path(),
+          violation.get_message(),
+          rejection.get_baseline()
+        )
+      })
+      .collect(),
+  }
+}
+
+pub fn get_package_versions(registry_url: &str, name: &str) -> grpcio::Result<Vec<String>> {
+  let channel = get_channel(registry_url);
+  let client = RegistryServiceClient::new(channel);
+  let mut req = GetPackageVersionsRequest::new();
+  req.set_name(name.to_owned());
+  let mut reply = client.get_package_versions(&req)?;
+  Ok(reply.take_versions().into_vec())
+}
+
+/// The compression codecs the registry can read. Registries from before
+/// capabilities were advertised only read gzip.
+pub fn get_supported_codecs(registry_url: &str) -> Vec<String> {
+  let channel = get_channel(registry_url);
+  let client = RegistryServiceClient::new(channel);
+  match client.get_capabilities(&GetCapabilitiesRequest::new()) {
+    Ok(mut reply) => reply.take_compression().into_vec(),
+    Err(_) => vec!["gzip".to_string()],
+  }
+}
+
+/// `requested` if the registry can read it, otherwise the default gzip
+/// codec, which every registry reads.
+fn negotiate_codec(registry_url: &str, requested: Codec) -> Codec {
+  if requested.name() == Codec::default().name() {
+    return requested;
+  }
+  let supported = get_supported_codecs(registry_url);
+  if supported.iter().any(|codec| codec == requested.name()) {
+    requested
+  } else {
+    Codec::default()
+  }
 }
 
-fn get_buff_toml_path() -> PathBuf {
-  let target_path = get_target_path();
-  target_path.join("buff.toml")
+pub struct DownloadedArtifact {
+  pub artifact: Vec<u8>,
+  pub signature: Option<ArtifactSignature>,
+  pub provenance: Option<SignedStatement>,
 }
 
-fn get_target_path() -> PathBuf {
-  match std::env::var("BUFF_TARGET_PATH") {
-    Ok(s) => Path::new(&s).to_path_buf(),
-    _ => std::env::current_dir().unwrap(),
+pub fn download(
+  registry_url: &str,
+  name: &str,
+  version: &str,
+) -> grpcio::Result<DownloadedArtifact> {
+  let channel = get_channel(registry_url);
+  let client = RegistryServiceClient::new(channel);
+  let mut req = DownloadRequest::new();
+  req.set_name(name.to_owned());
+  req.set_version(version.to_owned());
+  let mut reply = client.download(&req)?;
+  let public_key = reply.take_public_key();
+  let signed_with = |signature: Vec<u8>| {
+    if signature.is_empty() {
+      None
+    } else {
+      Some(ArtifactSignature {
+        signature,
+        public_key: public_key.clone(),
+      })
+    }
+  };
+  let signature = signed_with(reply.take_signature());
+  let provenance = if reply.get_provenance().is_empty() {
+    None
+  } else {
+    Some(SignedStatement {
+      signature: signed_with(reply.take_provenance_signature()),
+      statement: reply.take_provenance(),
+    })
+  };
+  Ok(DownloadedArtifact {
+    artifact: reply.take_artifact(),
+    signature,
+    provenance,
+  })
+}
+
+/// Why a published artifact couldn't be fetched.
+#[derive(Debug)]
+pub enum FetchError {
+  Download(grpcio::Error),
+  Untrusted(TrustError),
+  Invalid(ExtractError),
+}
+
+impl fmt::Display for FetchError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      FetchError::Download(err) => write!(f, "the download failed: {}", err),
+      FetchError::Untrusted(err) => write!(f, "refused, {}", err),
+      FetchError::Invalid(err) => write!(f, "invalid artifact, {}", err),
+    }
+  }
+}
+
+/// Downloads a published artifact, refusing it if its signature doesn't
+/// satisfy the `[trust]` policy in the CLI config. Signed artifacts were built
+/// after manifests were added, so their files must match one.
+pub fn fetch(registry_url: &str, name: &str, version: &str) -> Result<Artifact, FetchError> {
+  let downloaded = download(registry_url, name, version).map_err(FetchError::Download)?;
+  let mut artifact = Artifact::from_bytes(downloaded.artifact);
+  let policy = BuffCliConfig::new().trust;
+  signing::check_signature(
+    policy.keys_for(registry_url, name).as_ref(),
+    &artifact.sha256(),
+    downloaded.signature.as_ref(),
+  )
+  .map_err(FetchError::Untrusted)?;
+  if downloaded.signature.is_some() {
+    artifact = artifact.require_manifest();
+    artifact.verify_digests().map_err(FetchError::Invalid)?;
   }
+  Ok(artifact)
+}
+
+/// A workspace publish that stopped at a member, with the members published
+/// before it.
+pub struct WorkspacePublishError {
+  pub published: Vec<PublishSummary>,
+  pub error: VerificationError,
+}
+
+/// Publishes the workspace members whose version isn't in the registry yet,
+/// dependencies first, stopping at the first member that fails verification
+/// or can't be compared with the registry. A member whose version is already
+/// published is skipped when its files have the digests of the published
+/// ones, and refused when they changed without a version bump.
+pub fn publish_workspace(
+  workspace: &Workspace,
+  options: &PublishOptions,
+) -> Result<Vec<PublishSummary>, WorkspacePublishError> {
+  let registry_url = BuffCliConfig::new().preferred_registry;
+  let mut published = Vec::new();
+  for member in workspace.publish_order() {
+    match publish_member(&registry_url, member, options) {
+      Ok(summary) => published.extend(summary),
+      Err(error) => return Err(WorkspacePublishError { published, error }),
+    }
+  }
+  Ok(published)
+}
+
+/// Publishes a workspace member, `None` when its version is already published
+/// with the same files.
+fn publish_member(
+  registry_url: &str,
+  member: &Member,
+  options: &PublishOptions,
+) -> Result<Option<PublishSummary>, VerificationError> {
+  let package = &member.metadata.package;
+  let failed = |check, problem| VerificationError {
+    package: package.name.clone(),
+    check,
+    problems: vec![problem],
+  };
+  let versions = get_package_versions(registry_url, &package.name).map_err(|err| {
+    failed(
+      Check::Connection,
+      format!("failed to fetch the published versions: {}", err),
+    )
+  })?;
+  if !versions.contains(&package.version) {
+    return publish(&member.manifest_path, options).map(Some);
+  }
+  let invalid = |err: ExtractError| {
+    failed(
+      Check::Version,
+      format!("invalid artifact for {}: {}", package.version, err),
+    )
+  };
+  let published_digests = match fetch(registry_url, &package.name, &package.version) {
+    Ok(artifact) => artifact.file_digests().map_err(invalid)?,
+    Err(FetchError::Invalid(err)) => return Err(invalid(err)),
+    Err(FetchError::Download(err)) => {
+      return Err(failed(
+        Check::Connection,
+        format!("failed to download {}: {}", package.version, err),
+      ))
+    }
+    Err(FetchError::Untrusted(err)) => {
+      return Err(failed(
+        Check::Trust,
+        format!("refusing the published {}: {}", package.version, err),
+      ))
+    }
+  };
+  let local_digests = artifact::file_digests(&manifest::package_dir(&member.manifest_path))
+    .expect("Failed to read package files");
+  if local_digests != published_digests {
+    return Err(failed(
+      Check::Version,
+      format!(
+        "{} is already published with different files",
+        package.version
+      ),
+    ));
+  }
+  Ok(None)
+}
+
+fn get_channel(grpc_server_url: &str) -> Channel {
+  let env = Arc::new(EnvBuilder::new().build());
+  return ChannelBuilder::new(env).connect(grpc_server_url);
 }
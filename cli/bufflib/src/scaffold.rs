@@ -0,0 +1,293 @@
+use crate::buff_cli_config::BuffCliConfig;
+use crate::dependencies;
+use crate::package_metadata;
+use crate::proto;
+use crate::proto::{ParseError, SourceFile};
+use crate::registry;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const IGNORE_TEMPLATE: &str =
+  "# Paths matching these patterns are left out of the published artifact.
+*.tar.gz
+*.tar.zst
+*.tar
+";
+
+pub struct InitSummary {
+  pub name: String,
+  pub protos: Vec<PathBuf>,
+  pub dependencies: BTreeMap<String, String>,
+  pub unresolved_imports: Vec<String>,
+  /// Why the registry couldn't be asked about the unresolved imports, which
+  /// are then left as they are.
+  pub registry_error: Option<String>,
+}
+
+pub fn new_package(parent: &Path, name: &str) -> PathBuf {
+  validate_package_name(name);
+  let path = parent.join(name);
+  if path.exists() {
+    panic!("Destination `{}` already exists", path.display());
+  }
+  fs::create_dir_all(&path).expect("Failed to create package dir");
+  write_manifest(&path, name, &BTreeMap::new());
+  write_ignore(&path);
+  write_example_service(&path, name);
+  path
+}
+
+/// Creates a package in `path` around the protos already in it, or in
+/// `from_dir`. Nothing is written when one of them doesn't parse.
+pub fn init(path: &Path, from_dir: Option<&Path>) -> Result<InitSummary, ParseError> {
+  if path.join("buff.toml").exists() {
+    panic!("`{}` already exists", path.join("buff.toml").display());
+  }
+  let discovered = proto::parse_dir(from_dir.unwrap_or(path))?;
+  if let Some(from_dir) = from_dir {
+    // Protos from another tree are copied under protos/ so they
+    // end up in the artifact, keeping their layout so imports still resolve.
+    for proto in &discovered {
+      let destination = path.join("protos").join(&proto.path);
+      fs::create_dir_all(destination.with_file_name("")).expect("Failed to create protos dir");
+      fs::copy(from_dir.join(&proto.path), destination).expect("Failed to copy proto file");
+    }
+  }
+  let name = infer_package_name(&discovered).unwrap_or_else(|| {
+    let dir_name = path
+      .canonicalize()
+      .expect("Failed to resolve package dir")
+      .file_name()
+      .map(|name| name.to_string_lossy().into_owned())
+      .unwrap_or_else(|| "package".to_string());
+    dir_name.replace(
+      |c: char| !(c.is_alphanumeric() || c == '_' || c == '-'),
+      "_",
+    )
+  });
+  let (dependencies, unresolved_imports, registry_error) = resolve_dependencies(&discovered);
+  write_manifest(path, &name, &dependencies);
+  if !path.join(".ignore").exists() {
+    write_ignore(path);
+  }
+  if discovered.is_empty() {
+    write_example_service(path, &name);
+  }
+  Ok(InitSummary {
+    name,
+    protos: discovered.into_iter().map(|proto| proto.path).collect(),
+    dependencies,
+    unresolved_imports,
+    registry_error,
+  })
+}
+
+/// Derives a package name from the longest common prefix of the `package`
+/// statements, dropping trailing version components, e.g. `acme.billing.v1`
+/// becomes `acme_billing`.
+pub fn infer_package_name(protos: &[SourceFile]) -> Option<String> {
+  let mut common: Option<Vec<&str>> = None;
+  for package in protos
+    .iter()
+    .filter_map(|proto| proto.file.package.as_ref())
+  {
+    let components: Vec<&str> = package.split('.').collect();
+    common = Some(match common {
+      None => components,
+      Some(prefix) => prefix
+        .iter()
+        .zip(components.iter())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| *a)
+        .collect(),
+    });
+  }
+  let mut components = common?;
+  while components.last().map_or(false, |c| is_version_component(c)) {
+    components.pop();
+  }
+  if components.is_empty() {
+    None
+  } else {
+    Some(components.join("_"))
+  }
+}
+
+fn is_version_component(component: &str) -> bool {
+  let mut chars = component.chars();
+  chars.next() == Some('v') && chars.next().map_or(false, |c| c.is_ascii_digit())
+}
+
+/// Matches imports that aren't satisfied by the discovered files against
+/// packages known to the registry, using the first path component of the
+/// import as the package name. Packages stop being looked up once the
+/// registry can't be reached, so `init` works offline.
+fn resolve_dependencies(
+  protos: &[SourceFile],
+) -> (BTreeMap<String, String>, Vec<String>, Option<String>) {
+  let mut unresolved = BTreeSet::new();
+  for proto in protos {
+    for import in &proto.file.imports {
+      let is_local = protos.iter().any(|p| p.path.ends_with(&import.path));
+      if !is_local && !import.path.starts_with("google/protobuf/") {
+        unresolved.insert(import.path.clone());
+      }
+    }
+  }
+  let candidates: BTreeSet<&str> = unresolved
+    .iter()
+    .filter_map(|import| import.split('/').next())
+    .collect();
+  let registry_url = BuffCliConfig::new().preferred_registry;
+  let mut dependencies = BTreeMap::new();
+  let mut registry_error = None;
+  for candidate in candidates {
+    let versions = match registry::get_package_versions(&registry_url, candidate) {
+      Ok(versions) => versions,
+      Err(err) => {
+        registry_error = Some(format!("failed to reach {}: {}", registry_url, err));
+        break;
+      }
+    };
+    if let Some(latest) = dependencies::select_version(&versions, None) {
+      dependencies.insert(
+        candidate.to_string(),
+        format!("{}.{}", latest.major, latest.minor),
+      );
+    }
+  }
+  let unresolved_imports = unresolved
+    .into_iter()
+    .filter(|import| {
+      let package = import.split('/').next().unwrap();
+      !dependencies.contains_key(package)
+    })
+    .collect();
+  (dependencies, unresolved_imports, registry_error)
+}
+
+fn validate_package_name(name: &str) {
+  if !package_metadata::is_valid_package_name(name) {
+    panic!(
+      "Invalid package name `{}`, only letters, digits, `_` and `-` are allowed",
+      name
+    );
+  }
+}
+
+fn write_manifest(path: &Path, name: &str, dependencies: &BTreeMap<String, String>) {
+  let mut content = format!(
+    "[package]
+name = \"{}\"
+version = \"0.1.0\"
+description = \"\"
+homepage = \"\"
+repository_url = \"\"
+keywords = []
+
+[dependencies]
+",
+    name
+  );
+  for (dependency, requirement) in dependencies {
+    content.push_str(&format!("{} = \"{}\"\n", dependency, requirement));
+  }
+  fs::write(path.join("buff.toml"), content).expect("Failed to write buff.toml");
+}
+
+fn write_ignore(path: &Path) {
+  fs::write(path.join(".ignore"), IGNORE_TEMPLATE).expect("Failed to write .ignore");
+}
+
+fn write_example_service(path: &Path, name: &str) {
+  let proto_package = name.replace('-', "_");
+  let service_name: String = proto_package
+    .split('_')
+    .map(|word| {
+      let mut chars = word.chars();
+      match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+      }
+    })
+    .collect();
+  let content = format!(
+    "syntax = \"proto3\";
+
+package {};
+
+service {}Service {{
+  rpc Ping (PingRequest) returns (PingResponse) {{}}
+}}
+
+message PingRequest {{
+  string message = 1;
+}}
+
+message PingResponse {{
+  string message = 1;
+}}
+",
+    proto_package, service_name
+  );
+  let protos_dir = path.join("protos");
+  fs::create_dir_all(&protos_dir).expect("Failed to create protos dir");
+  fs::write(protos_dir.join(format!("{}.proto", proto_package)), content)
+    .expect("Failed to write example service");
+}
+
+#[cfg(test)]
+fn discovered(path: &str, package: &str) -> SourceFile {
+  SourceFile {
+    path: PathBuf::from(path),
+    file: proto::ProtoFile {
+      package: Some(package.to_string()),
+      ..proto::ProtoFile::default()
+    },
+  }
+}
+
+#[test]
+fn should_new_package() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let path = new_package(tmp_dir.path(), "my-service");
+  let metadata =
+    crate::package_metadata::PackageMetadata::new(path.join("buff.toml").to_str().unwrap());
+  assert_eq!(metadata.package.name, "my-service");
+  assert_eq!(metadata.package.version, "0.1.0");
+  assert!(path.join(".ignore").exists());
+  let example = fs::read_to_string(path.join("protos/my_service.proto")).unwrap();
+  assert_eq!(
+    proto::parse(&example).unwrap().package,
+    Some("my_service".to_string())
+  );
+  assert!(example.contains("service MyServiceService"));
+}
+
+#[test]
+fn should_infer_package_name() {
+  let protos = vec![
+    discovered("billing/v1/invoice.proto", "acme.billing.v1"),
+    discovered("billing/v1/payment.proto", "acme.billing.v1"),
+  ];
+  assert_eq!(
+    infer_package_name(&protos),
+    Some("acme_billing".to_string())
+  );
+  let protos = vec![
+    discovered("billing.proto", "acme.billing.v1"),
+    discovered("users.proto", "acme.users.v1"),
+  ];
+  assert_eq!(infer_package_name(&protos), Some("acme".to_string()));
+  let protos = vec![discovered("a.proto", "v1"), discovered("b.proto", "other")];
+  assert_eq!(infer_package_name(&protos), None);
+}
+
+#[test]
+fn should_discover_protos() {
+  let protos = proto::parse_dir(Path::new("../tests/fixtures/test_artifact")).unwrap();
+  assert_eq!(protos.len(), 1);
+  assert_eq!(protos[0].path, PathBuf::from("protobuffers/buff.proto"));
+  assert_eq!(protos[0].file.package, Some("buff_server_grpc".to_string()));
+}
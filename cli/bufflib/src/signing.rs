@@ -0,0 +1,203 @@
+use crate::buff_cli_config::TrustedKeys;
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, Verifier};
+use rand::rngs::OsRng;
+use std::fmt;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_KEY_NAME: &str = "default";
+
+/// A detached ed25519 signature over an artifact's SHA-256 digest, with the
+/// public key that made it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArtifactSignature {
+  pub signature: Vec<u8>,
+  pub public_key: Vec<u8>,
+}
+
+/// Signing keys live in `keys/` under BUFF_HOME, as `<name>.key` for the
+/// keypair and `<name>.pub` for the public key, both hex-encoded.
+pub fn keys_dir(buff_home: &Path) -> PathBuf {
+  buff_home.join("keys")
+}
+
+/// Generates a keypair named `name` in `dir` and returns its public key,
+/// refusing to overwrite an existing key.
+pub fn generate_key(dir: &Path, name: &str) -> io::Result<String> {
+  let secret_path = dir.join(format!("{}.key", name));
+  let public_path = dir.join(format!("{}.pub", name));
+  if secret_path.exists() || public_path.exists() {
+    return Err(io::Error::new(
+      io::ErrorKind::AlreadyExists,
+      format!("key `{}` already exists in {}", name, dir.display()),
+    ));
+  }
+  fs::create_dir_all(dir)?;
+  let keypair = Keypair::generate(&mut OsRng);
+  let public_key = hex::encode(keypair.public.to_bytes());
+  write_secret(
+    &secret_path,
+    hex::encode(&keypair.to_bytes()[..]).as_bytes(),
+  )?;
+  fs::write(&public_path, format!("{}\n", public_key))?;
+  Ok(public_key)
+}
+
+// The keypair file is created readable by its owner only, rather
+// than tightening the permissions after it was written.
+fn write_secret(path: &Path, contents: &[u8]) -> io::Result<()> {
+  let mut options = OpenOptions::new();
+  options.write(true).create_new(true);
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::OpenOptionsExt;
+    options.mode(0o600);
+  }
+  options.open(path)?.write_all(contents)
+}
+
+pub fn load_key(dir: &Path, name: &str) -> Keypair {
+  let path = dir.join(format!("{}.key", name));
+  let content = fs::read_to_string(&path).unwrap_or_else(|_| {
+    panic!(
+      "No signing key `{}` in {}, create one with `buff key generate`",
+      name,
+      dir.display()
+    )
+  });
+  hex::decode(content.trim())
+    .ok()
+    .and_then(|bytes| Keypair::from_bytes(&bytes).ok())
+    .unwrap_or_else(|| panic!("Invalid signing key {}", path.display()))
+}
+
+/// Signs the artifact whose SHA-256 is the hex-encoded `sha256`.
+pub fn sign(keypair: &Keypair, sha256: &str) -> ArtifactSignature {
+  sign_message(
+    keypair,
+    &hex::decode(sha256).expect("Invalid artifact digest"),
+  )
+}
+
+pub fn sign_message(keypair: &Keypair, message: &[u8]) -> ArtifactSignature {
+  ArtifactSignature {
+    signature: keypair.sign(message).to_bytes().to_vec(),
+    public_key: keypair.public.to_bytes().to_vec(),
+  }
+}
+
+/// Why a fetched artifact's signature was rejected.
+#[derive(Debug, PartialEq)]
+pub enum TrustError {
+  Unsigned,
+  BadSignature,
+  UntrustedKey(String),
+}
+
+impl fmt::Display for TrustError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      TrustError::Unsigned => write!(
+        f,
+        "the package isn't signed but the trust policy requires it"
+      ),
+      TrustError::BadSignature => write!(f, "the signature doesn't match the artifact"),
+      TrustError::UntrustedKey(key) => write!(f, "signed with untrusted key {}", key),
+    }
+  }
+}
+
+/// Checks the signature of an artifact with the hex-encoded SHA-256 `sha256`
+/// against the keys trusted for it. Signatures are always verified when
+/// present, while `trusted` decides whether one is required and who may make
+/// it. Returns the signer's public key.
+pub fn check_signature(
+  trusted: Option<&TrustedKeys>,
+  sha256: &str,
+  signature: Option<&ArtifactSignature>,
+) -> Result<Option<String>, TrustError> {
+  let digest = hex::decode(sha256).map_err(|_| TrustError::BadSignature)?;
+  check_message_signature(trusted, &digest, signature)
+}
+
+/// Like `check_signature`, for a signature made over `message` itself.
+pub fn check_message_signature(
+  trusted: Option<&TrustedKeys>,
+  message: &[u8],
+  signature: Option<&ArtifactSignature>,
+) -> Result<Option<String>, TrustError> {
+  let signature = match signature {
+    Some(signature) => signature,
+    None if trusted.map_or(false, |trusted| trusted.require_signature) => {
+      return Err(TrustError::Unsigned)
+    }
+    None => return Ok(None),
+  };
+  let public_key =
+    PublicKey::from_bytes(&signature.public_key).map_err(|_| TrustError::BadSignature)?;
+  let parsed = Signature::from_bytes(&signature.signature).map_err(|_| TrustError::BadSignature)?;
+  public_key
+    .verify(message, &parsed)
+    .map_err(|_| TrustError::BadSignature)?;
+  let signer = hex::encode(&signature.public_key);
+  match trusted {
+    Some(trusted) if !trusted.keys.is_empty() && !trusted.keys.contains(&signer) => {
+      Err(TrustError::UntrustedKey(signer))
+    }
+    _ => Ok(Some(signer)),
+  }
+}
+
+#[test]
+fn should_generate_and_load_keys() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let dir = keys_dir(tmp_dir.path());
+  let public_key = generate_key(&dir, DEFAULT_KEY_NAME).unwrap();
+  assert_eq!(
+    fs::read_to_string(dir.join("default.pub")).unwrap().trim(),
+    public_key
+  );
+  assert_eq!(
+    generate_key(&dir, DEFAULT_KEY_NAME).unwrap_err().kind(),
+    io::ErrorKind::AlreadyExists
+  );
+  let keypair = load_key(&dir, DEFAULT_KEY_NAME);
+  assert_eq!(hex::encode(keypair.public.to_bytes()), public_key);
+}
+
+#[test]
+fn should_check_signatures_against_trust_policy() {
+  let keypair = Keypair::generate(&mut OsRng);
+  let signer = hex::encode(keypair.public.to_bytes());
+  let sha256 = hex::encode([7u8; 32]);
+  let signature = sign(&keypair, &sha256);
+  let required = TrustedKeys {
+    keys: Vec::new(),
+    require_signature: true,
+  };
+  assert_eq!(check_signature(None, &sha256, None), Ok(None));
+  assert_eq!(
+    check_signature(Some(&required), &sha256, None),
+    Err(TrustError::Unsigned)
+  );
+  assert_eq!(
+    check_signature(Some(&required), &sha256, Some(&signature)),
+    Ok(Some(signer.clone()))
+  );
+  let tampered = hex::encode([8u8; 32]);
+  assert_eq!(
+    check_signature(None, &tampered, Some(&signature)),
+    Err(TrustError::BadSignature)
+  );
+  let pinned = TrustedKeys {
+    keys: vec![hex::encode([1u8; 32])],
+    require_signature: false,
+  };
+  assert_eq!(
+    check_signature(Some(&pinned), &sha256, Some(&signature)),
+    Err(TrustError::UntrustedKey(signer))
+  );
+}
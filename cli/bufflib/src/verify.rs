@@ -0,0 +1,485 @@
+use crate::artifact::Artifact;
+use crate::compat;
+use crate::descriptor;
+use crate::git;
+use crate::lint;
+use crate::manifest;
+use crate::package_metadata::{self, PackageMetadata};
+use crate::proto;
+use crate::proto::{ParseError, SourceFile};
+use crate::workspace;
+use semver::{Version, VersionReq};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// What a package is checked for before, or while, it's published.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Check {
+  /// The package has no uncommitted changes.
+  Clean,
+  Manifest,
+  Protos,
+  Imports,
+  Lint,
+  Breaking,
+  Size,
+  Remote,
+  /// An already published version has the same files.
+  Version,
+  /// The registry accepts the package.
+  Registry,
+  /// The registry can be reached.
+  Connection,
+  /// The published copy of the version satisfies the `[trust]` policy.
+  Trust,
+}
+
+impl Check {
+  pub fn name(self) -> &'static str {
+    match self {
+      Check::Clean => "clean",
+      Check::Manifest => "manifest",
+      Check::Protos => "protos",
+      Check::Imports => "imports",
+      Check::Lint => "lint",
+      Check::Breaking => "breaking",
+      Check::Size => "size",
+      Check::Remote => "remote",
+      Check::Version => "version",
+      Check::Registry => "registry",
+      Check::Connection => "connection",
+      Check::Trust => "trust",
+    }
+  }
+}
+
+impl fmt::Display for Check {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+/// The first pre-publish check that failed, with everything it found wrong.
+#[derive(Debug)]
+pub struct VerificationError {
+  pub package: String,
+  pub check: Check,
+  pub problems: Vec<String>,
+}
+
+impl fmt::Display for VerificationError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} failed the `{}` check:", self.package, self.check)?;
+    for problem in &self.problems {
+      write!(f, "\n  {}", problem)?;
+    }
+    Ok(())
+  }
+}
+
+/// Runs the checks enabled in the `[publish]` section over the artifact that
+/// is about to be published, in order, stopping at the first one that fails.
+/// Returns the names of the checks that ran.
+pub fn verify(
+  manifest_path: &Path,
+  artifact: &Artifact,
+  registry_url: &str,
+) -> Result<Vec<Check>, VerificationError> {
+  let metadata = PackageMetadata::new(manifest_path.to_str().unwrap());
+  let config = &metadata.publish;
+  let parsed = parse_artifact(artifact);
+  let checks: Vec<(Check, bool, Box<dyn Fn() -> Vec<String>>)> = vec![
+    (
+      Check::Manifest,
+      config.manifest,
+      Box::new(|| verify_manifest(manifest_path, &metadata)),
+    ),
+    (
+      Check::Protos,
+      config.protos,
+      Box::new(|| with_files(&parsed, verify_protos)),
+    ),
+    (
+      Check::Imports,
+      config.imports,
+      Box::new(|| with_files(&parsed, |files| verify_imports(&metadata, files))),
+    ),
+    (
+      Check::Lint,
+      config.lint,
+      Box::new(|| {
+        with_files(&parsed, |files| {
+          lint::lint(files, &metadata.lint)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+        })
+      }),
+    ),
+    (
+      Check::Breaking,
+      config.breaking,
+      Box::new(|| {
+        with_files(&parsed, |files| {
+          verify_breaking(registry_url, &metadata, files)
+        })
+      }),
+    ),
+    (
+      Check::Size,
+      config.size,
+      Box::new(|| verify_size(config.max_size, artifact.size() as usize)),
+    ),
+    (
+      Check::Remote,
+      config.remote,
+      Box::new(|| {
+        git::verify_pushed(
+          &manifest::package_dir(manifest_path),
+          &metadata.package.repository_url,
+        )
+        .err()
+        .into_iter()
+        .collect()
+      }),
+    ),
+  ];
+  let mut ran = Vec::new();
+  for (check, enabled, run) in checks {
+    if !enabled {
+      continue;
+    }
+    let problems = run();
+    if !problems.is_empty() {
+      return Err(VerificationError {
+        package: metadata.package.name.clone(),
+        check,
+        problems,
+      });
+    }
+    ran.push(check);
+  }
+  Ok(ran)
+}
+
+fn parse_artifact(artifact: &Artifact) -> Result<Vec<SourceFile>, Vec<String>> {
+  let sources = artifact
+    .proto_sources()
+    .map_err(|err| vec![format!("failed to read the artifact: {}", err)])?;
+  let mut files = Vec::new();
+  let mut errors = Vec::new();
+  for (path, source) in sources {
+    match proto::parse_file(&path, &source) {
+      Ok(file) => files.push(SourceFile { path, file }),
+      Err(err) => errors.push(err.to_string()),
+    }
+  }
+  if errors.is_empty() {
+    Ok(files)
+  } else {
+    Err(errors)
+  }
+}
+
+// Checks that need the parsed files fail with the parse errors
+// when the `protos` check itself is turned off.
+fn with_files<F>(parsed: &Result<Vec<SourceFile>, Vec<String>>, check: F) -> Vec<String>
+where
+  F: Fn(&[SourceFile]) -> Vec<String>,
+{
+  match parsed {
+    Ok(files) => check(files),
+    Err(errors) => errors.clone(),
+  }
+}
+
+// Files are linked with the paths the package's imports refer to them by.
+// The ones importing something from outside the package, i.e. dependencies
+// or well-known types, aren't in the artifact to link against, so those
+// files are only parsed and the `imports` check covers their imports.
+fn verify_protos(files: &[SourceFile]) -> Vec<String> {
+  let mut linked: Vec<(PathBuf, SourceFile)> = files
+    .iter()
+    .map(|source| (source.path.clone(), source.clone()))
+    .collect();
+  for import in files.iter().flat_map(|source| &source.file.imports) {
+    if let Some((_, source)) = linked
+      .iter_mut()
+      .find(|(_, source)| source.path.ends_with(&import.path))
+    {
+      source.path = PathBuf::from(&import.path);
+    }
+  }
+  loop {
+    let paths: Vec<PathBuf> = linked
+      .iter()
+      .map(|(_, source)| source.path.clone())
+      .collect();
+    let count = linked.len();
+    linked.retain(|(_, source)| {
+      source
+        .file
+        .imports
+        .iter()
+        .all(|import| paths.contains(&PathBuf::from(&import.path)))
+    });
+    if linked.len() == count {
+      break;
+    }
+  }
+  let (paths, sources): (Vec<PathBuf>, Vec<SourceFile>) = linked.into_iter().unzip();
+  match descriptor::link(&sources) {
+    Ok(_) => Vec::new(),
+    Err(err) => {
+      let file = err.file.as_ref().and_then(|file| {
+        sources
+          .iter()
+          .position(|source| source.path == *file)
+          .map(|i| paths[i].clone())
+      });
+      vec![ParseError { file, ..err }.to_string()]
+    }
+  }
+}
+
+fn verify_manifest(manifest_path: &Path, metadata: &PackageMetadata) -> Vec<String> {
+  let package = &metadata.package;
+  let mut problems = Vec::new();
+  if !package_metadata::is_valid_package_name(&package.name) {
+    problems.push(format!(
+      "name `{}` may only contain letters, digits, `_` and `-`",
+      package.name
+    ));
+  }
+  if Version::parse(&package.version).is_err() {
+    problems.push(format!("version `{}` is not valid semver", package.version));
+  }
+  for (field, value) in &[
+    ("description", &package.description),
+    ("homepage", &package.homepage),
+    ("repository_url", &package.repository_url),
+  ] {
+    if value.trim().is_empty() {
+      problems.push(format!("{} is empty", field));
+    }
+  }
+  if !package.repository_url.is_empty() && !is_url(&package.repository_url) {
+    problems.push(format!(
+      "repository_url `{}` is not a valid URL",
+      package.repository_url
+    ));
+  }
+  let workspace = workspace::find_workspace(manifest_path);
+  let mut names: Vec<&String> = metadata.dependencies.keys().collect();
+  names.sort();
+  for name in names {
+    let dependency = &metadata.dependencies[name];
+    let requirement = if dependency.is_workspace() {
+      workspace
+        .as_ref()
+        .and_then(|workspace| workspace.dependencies.get(name))
+        .and_then(|dependency| dependency.version())
+    } else {
+      dependency.version()
+    };
+    match requirement {
+      None => problems.push(format!("dependency `{}` has no version", name)),
+      Some(requirement) if VersionReq::parse(requirement).is_err() => problems.push(format!(
+        "dependency `{}` has an invalid version requirement `{}`",
+        name, requirement
+      )),
+      Some(_) => (),
+    }
+  }
+  problems
+}
+
+fn is_url(url: &str) -> bool {
+  match url.find("://") {
+    Some(scheme_end) if scheme_end > 0 => {
+      let host = url[scheme_end + 3..].split('/').next().unwrap_or("");
+      host.contains('.')
+    }
+    _ => false,
+  }
+}
+
+fn verify_imports(metadata: &PackageMetadata, files: &[SourceFile]) -> Vec<String> {
+  let mut problems = Vec::new();
+  for source in files {
+    for import in &source.file.imports {
+      let is_local = files.iter().any(|file| file.path.ends_with(&import.path));
+      let is_well_known = import.path.starts_with("google/protobuf/");
+      let is_dependency = import
+        .path
+        .split('/')
+        .next()
+        .map_or(false, |package| metadata.dependencies.contains_key(package));
+      if !(is_local || is_well_known || is_dependency) {
+        problems.push(format!(
+          "{}: import \"{}\" is not in the package or a declared dependency",
+          source.path.display(),
+          import.path
+        ));
+      }
+    }
+  }
+  problems
+}
+
+fn verify_breaking(
+  registry_url: &str,
+  metadata: &PackageMetadata,
+  files: &[SourceFile],
+) -> Vec<String> {
+  let version = match Version::parse(&metadata.package.version) {
+    Ok(version) => version,
+    Err(_) => {
+      return vec![format!(
+        "version `{}` is not valid semver",
+        metadata.package.version
+      )]
+    }
+  };
+  let compatibility = &metadata.compatibility;
+  let baselines = match compat::published_baselines(
+    registry_url,
+    &metadata.package.name,
+    compatibility.transitive,
+  ) {
+    Ok(baselines) => baselines,
+    Err(err) => return vec![err],
+  };
+  if compat::starts_major_line(&baselines, &version) {
+    return Vec::new();
+  }
+  let baselines: Vec<(String, Vec<SourceFile>)> = baselines
+    .into_iter()
+    .map(|(version, files)| (version.to_string(), files))
+    .collect();
+  let mut suppressions = compat::Suppressions::new(
+    &compatibility.ignore,
+    files,
+    chrono::Local::now().naive_local().date(),
+  );
+  compat::check_baselines(&baselines, files, compatibility)
+    .iter()
+    .filter(|(_, violation)| !suppressions.suppresses(violation))
+    .map(|(baseline, violation)| format!("{} (since {})", violation, baseline))
+    .collect()
+}
+
+fn verify_size(max_size: usize, size: usize) -> Vec<String> {
+  if size > max_size {
+    vec![format!(
+      "artifact is {} bytes, over the limit of {} bytes",
+      size, max_size
+    )]
+  } else {
+    Vec::new()
+  }
+}
+
+#[test]
+fn should_verify_manifest() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let path = tmp_dir.path().join("buff.toml");
+  std::fs::write(
+    &path,
+    "[package]\nname = \"bad name\"\nversion = \"1.0\"\ndescription = \"\"\nhomepage = \"https://example.com\"\nrepository_url = \"repo\"\nkeywords = []\n\n[dependencies]\nyummy_package = \"not a version\"\nshared = { workspace = true }\n",
+  )
+  .unwrap();
+  let metadata = PackageMetadata::new(path.to_str().unwrap());
+  assert_eq!(
+    verify_manifest(&path, &metadata),
+    vec![
+      "name `bad name` may only contain letters, digits, `_` and `-`",
+      "version `1.0` is not valid semver",
+      "description is empty",
+      "repository_url `repo` is not a valid URL",
+      "dependency `shared` has no version",
+      "dependency `yummy_package` has an invalid version requirement `not a version`",
+    ]
+  );
+}
+
+#[test]
+fn should_verify_imports() {
+  let metadata = PackageMetadata::new("../tests/fixtures/test_artifact/buff.toml");
+  let file = |path: &str, source: &str| SourceFile {
+    path: std::path::PathBuf::from(path),
+    file: proto::parse(source).unwrap(),
+  };
+  let files = vec![
+    file(
+      "protos/acme/orders.proto",
+      "import \"acme/common.proto\"; import \"google/protobuf/any.proto\";
+       import \"yummy_package/food.proto\"; import \"other/thing.proto\";",
+    ),
+    file("protos/acme/common.proto", ""),
+  ];
+  assert_eq!(
+    verify_imports(&metadata, &files),
+    vec![
+      "protos/acme/orders.proto: import \"other/thing.proto\" is not in the package or a declared dependency"
+    ]
+  );
+}
+
+#[test]
+fn should_verify_protos() {
+  let file = |path: &str, source: &str| SourceFile {
+    path: PathBuf::from(path),
+    file: proto::parse(source).unwrap(),
+  };
+  assert_eq!(
+    verify_protos(&[
+      file("protos/acme/common.proto", "package acme; message Money {}"),
+      file(
+        "protos/acme/orders.proto",
+        "import \"acme/common.proto\"; package acme; message Order { Money total = 1; }",
+      ),
+      file(
+        "protos/acme/events.proto",
+        "import \"google/protobuf/any.proto\"; message Event { google.protobuf.Any body = 1; }",
+      ),
+    ]),
+    Vec::<String>::new()
+  );
+  assert_eq!(
+    verify_protos(&[file(
+      "protos/acme/orders.proto",
+      "package acme;\nmessage Order { Money total = 1; }",
+    )]),
+    vec!["protos/acme/orders.proto:2:17: unknown type `Money`"]
+  );
+}
+
+#[test]
+fn should_stop_at_the_first_failing_check() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let package_dir = tmp_dir.path();
+  std::fs::write(
+    package_dir.join("buff.toml"),
+    "[package]\nname = \"a\"\nversion = \"0.1.0\"\ndescription = \"a\"\nhomepage = \"https://example.com\"\nrepository_url = \"https://example.com/a\"\nkeywords = []\n\n[publish]\nbreaking = false\nmax_size = 10\n",
+  )
+  .unwrap();
+  std::fs::write(
+    package_dir.join("a.proto"),
+    "syntax = \"proto3\"; package a; message bad_name {}",
+  )
+  .unwrap();
+  let manifest_path = package_dir.join("buff.toml");
+  let artifact = Artifact::from_bytes(crate::artifact::get_artifact_bytes(
+    package_dir.to_str().unwrap(),
+  ));
+  let error = verify(&manifest_path, &artifact, "localhost:0").unwrap_err();
+  assert_eq!(error.check, Check::Lint);
+  std::fs::write(
+    package_dir.join("buff.toml"),
+    "[package]\nname = \"a\"\nversion = \"0.1.0\"\ndescription = \"a\"\nhomepage = \"https://example.com\"\nrepository_url = \"https://example.com/a\"\nkeywords = []\n\n[publish]\nlint = false\nbreaking = false\nmax_size = 10\n",
+  )
+  .unwrap();
+  let error = verify(&manifest_path, &artifact, "localhost:0").unwrap_err();
+  assert_eq!(error.check, Check::Size);
+}
@@ -0,0 +1,292 @@
+use crate::buff_cli_config::BuffCliConfig;
+use crate::compat::{self, Compatibility, Suppressions};
+use crate::diff;
+use crate::manifest;
+use crate::package_metadata::{ManifestDocument, PackageMetadata};
+use crate::proto;
+use crate::proto::SourceFile;
+use chrono::Local;
+use semver::Version;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+#[cfg(test)]
+use crate::proto::sources;
+
+/// A semver bump, from smallest to largest.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Bump {
+  Patch,
+  Minor,
+  Major,
+}
+
+impl Bump {
+  /// `version` with this part incremented and the ones after it reset.
+  pub fn apply(self, version: &Version) -> Version {
+    let mut version = version.clone();
+    match self {
+      Bump::Patch => version.increment_patch(),
+      Bump::Minor => version.increment_minor(),
+      Bump::Major => version.increment_major(),
+    }
+    version
+  }
+}
+
+impl fmt::Display for Bump {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Bump::Patch => write!(f, "patch"),
+      Bump::Minor => write!(f, "minor"),
+      Bump::Major => write!(f, "major"),
+    }
+  }
+}
+
+impl FromStr for Bump {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "patch" => Ok(Bump::Patch),
+      "minor" => Ok(Bump::Minor),
+      "major" => Ok(Bump::Major),
+      _ => Err(format!(
+        "unknown bump `{}`, expected major, minor or patch",
+        s
+      )),
+    }
+  }
+}
+
+/// The bump a change calls for, with what called for it.
+#[derive(Debug, PartialEq)]
+pub struct Suggestion {
+  pub bump: Bump,
+  pub reasons: Vec<String>,
+}
+
+/// The smallest bump that describes going from `previous` to `current`:
+/// major for breaking changes under `compatibility`, minor for additions or
+/// any other change to the definitions, patch when only comments and
+/// formatting changed. Suppressed breaking changes don't call for a major
+/// bump.
+pub fn suggest(
+  previous: &[SourceFile],
+  current: &[SourceFile],
+  compatibility: &Compatibility,
+) -> Suggestion {
+  let mut suppressions = Suppressions::new(
+    &compatibility.ignore,
+    current,
+    Local::now().naive_local().date(),
+  );
+  let breaking: Vec<String> = compat::check_compatibility(previous, current, compatibility)
+    .into_iter()
+    .filter(|violation| !suppressions.suppresses(violation))
+    .map(|violation| violation.to_string())
+    .collect();
+  if !breaking.is_empty() {
+    return Suggestion {
+      bump: Bump::Major,
+      reasons: breaking,
+    };
+  }
+  let changes = diff::diff(previous, current);
+  if !changes.is_empty() {
+    return Suggestion {
+      bump: Bump::Minor,
+      reasons: changes.iter().map(|change| change.to_string()).collect(),
+    };
+  }
+  if !proto::same_definitions(previous, current) {
+    Suggestion {
+      bump: Bump::Minor,
+      reasons: vec!["definitions changed without adding or removing anything".to_string()],
+    }
+  } else {
+    Suggestion {
+      bump: Bump::Patch,
+      reasons: vec!["only comments and formatting changed".to_string()],
+    }
+  }
+}
+
+/// The version after `published` that `bump` calls for. Below 1.0 the minor
+/// version is the breaking one, so breaking changes bump it and additions
+/// bump the patch version.
+pub fn next_version(published: &Version, bump: Bump) -> Version {
+  let bump = match (published.major, bump) {
+    (0, Bump::Major) => Bump::Minor,
+    (0, _) => Bump::Patch,
+    (_, bump) => bump,
+  };
+  bump.apply(published)
+}
+
+/// The package's latest published version with the bump its working tree
+/// calls for, `None` if nothing was published yet.
+pub fn suggest_for_package(manifest_path: &Path) -> Option<(Version, Suggestion)> {
+  let metadata = PackageMetadata::new(manifest_path.to_str().unwrap());
+  let registry_url = BuffCliConfig::new().preferred_registry;
+  let (published, previous) =
+    compat::published_baselines(&registry_url, &metadata.package.name, false)
+      .unwrap_or_else(|err| panic!("Failed to check `{}`: {}", metadata.package.name, err))
+      .pop()?;
+  let current = proto::parse_dir(&manifest::package_dir(manifest_path))
+    .unwrap_or_else(|err| panic!("Failed to parse {}", err));
+  let suggestion = suggest(&previous, &current, &metadata.compatibility);
+  Some((published, suggestion))
+}
+
+/// What `bump_package` did to the version in buff.toml.
+#[derive(Debug, PartialEq)]
+pub enum BumpOutcome {
+  Bumped {
+    from: String,
+    to: String,
+  },
+  /// The version already covers the bump the changes call for.
+  UpToDate {
+    version: String,
+    bump: Bump,
+    published: String,
+  },
+  /// There's no published version to work out an `auto` bump from.
+  NothingPublished,
+}
+
+/// Bumps the version in the buff.toml at `manifest_path` by `bump`, or when
+/// it's `None` by whatever the changes since the latest published version
+/// call for. Everything else in the file is left as it was.
+pub fn bump_package(manifest_path: &Path, bump: Option<Bump>) -> BumpOutcome {
+  let metadata = PackageMetadata::new(manifest_path.to_str().unwrap());
+  let current = Version::parse(&metadata.package.version).unwrap_or_else(|_| {
+    panic!(
+      "version `{}` in {} is not valid semver",
+      metadata.package.version,
+      manifest_path.display()
+    )
+  });
+  let next = match bump {
+    Some(bump) => bump.apply(&current),
+    None => {
+      let (published, suggestion) = match suggest_for_package(manifest_path) {
+        Some(suggested) => suggested,
+        None => return BumpOutcome::NothingPublished,
+      };
+      let next = next_version(&published, suggestion.bump);
+      if current >= next {
+        return BumpOutcome::UpToDate {
+          version: current.to_string(),
+          bump: suggestion.bump,
+          published: published.to_string(),
+        };
+      }
+      next
+    }
+  };
+  let mut document = ManifestDocument::open(manifest_path);
+  document.set_version(&next.to_string());
+  document.save();
+  BumpOutcome::Bumped {
+    from: current.to_string(),
+    to: next.to_string(),
+  }
+}
+
+#[test]
+fn should_suggest_bumps() {
+  let previous = sources(
+    "syntax = \"proto3\"; package acme.v1;
+     message Order { string id = 1; }
+     service Orders { rpc Get (Order) returns (Order); }",
+  );
+  let suggest_for = |current: &str| suggest(&previous, &sources(current), &Default::default());
+  assert_eq!(
+    suggest_for(
+      "syntax = \"proto3\";
+       package acme.v1;
+       // The order.
+       message Order {
+         string id = 1; // buff:ignore FIELD_TYPE_CHANGED
+       }
+       service Orders { rpc Get (Order) returns (Order); }"
+    )
+    .bump,
+    Bump::Patch
+  );
+  assert_eq!(
+    suggest_for(
+      "syntax = \"proto3\"; package acme.v1;
+       message Order { string id = 1; string note = 2; }
+       service Orders { rpc Get (Order) returns (Order); rpc List (Order) returns (Order); }"
+    ),
+    Suggestion {
+      bump: Bump::Minor,
+      reasons: vec![
+        "added rpc acme.v1.Orders.List".to_string(),
+        "added field acme.v1.Order.note: number 2".to_string(),
+      ],
+    }
+  );
+  assert_eq!(
+    suggest_for(
+      "syntax = \"proto3\"; package acme.v1;
+       message Order { string id = 1 [deprecated = true]; }
+       service Orders { rpc Get (Order) returns (Order); }"
+    )
+    .bump,
+    Bump::Minor
+  );
+  assert_eq!(
+    suggest_for(
+      "syntax = \"proto3\"; package acme.v1;
+       message Order { string id = 1; }"
+    ),
+    Suggestion {
+      bump: Bump::Major,
+      reasons: vec!["SERVICE_REMOVED acme.v1.Orders: service was removed".to_string()],
+    }
+  );
+}
+
+#[test]
+fn should_bump_versions() {
+  let version = |v: &str| Version::parse(v).unwrap();
+  assert_eq!(Bump::Major.apply(&version("0.3.1")), version("1.0.0"));
+  assert_eq!(Bump::Minor.apply(&version("1.3.1-rc.1")), version("1.4.0"));
+  assert_eq!(
+    next_version(&version("1.3.1"), Bump::Minor),
+    version("1.4.0")
+  );
+  assert_eq!(
+    next_version(&version("0.3.1"), Bump::Major),
+    version("0.4.0")
+  );
+  assert_eq!(
+    next_version(&version("0.3.1"), Bump::Minor),
+    version("0.3.2")
+  );
+  assert!("huge".parse::<Bump>().is_err());
+}
+
+#[test]
+fn should_bump_package_versions() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let path = tmp_dir.path().join("buff.toml");
+  std::fs::copy("../tests/fixtures/test_artifact/buff.toml", &path).unwrap();
+  assert_eq!(
+    bump_package(&path, Some(Bump::Minor)),
+    BumpOutcome::Bumped {
+      from: "0.1.0".to_string(),
+      to: "0.2.0".to_string(),
+    }
+  );
+  assert_eq!(
+    PackageMetadata::new(path.to_str().unwrap()).package.version,
+    "0.2.0"
+  );
+}
@@ -0,0 +1,272 @@
+use crate::manifest::{self, MANIFEST_FILE_NAME};
+use crate::package_metadata::{Dependency, PackageMetadata};
+use serde_derive::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Deserialize)]
+struct WorkspaceManifest {
+  workspace: Option<WorkspaceSection>,
+}
+
+#[derive(Deserialize)]
+struct WorkspaceSection {
+  members: Vec<String>,
+  #[serde(default)]
+  dependencies: HashMap<String, Dependency>,
+}
+
+pub struct Member {
+  pub manifest_path: PathBuf,
+  pub metadata: PackageMetadata,
+}
+
+/// A set of packages developed together. The root buff.toml lists the member
+/// directories under `[workspace]`, relative to itself, where a trailing `/*`
+/// stands for every subdirectory with a buff.toml. Versions declared in
+/// `[workspace.dependencies]` are shared by members that depend on them with
+/// `{ workspace = true }`, and all members share the root's buff.lock.
+pub struct Workspace {
+  pub root: PathBuf,
+  pub dependencies: HashMap<String, Dependency>,
+  pub members: Vec<Member>,
+}
+
+impl Workspace {
+  /// Loads the workspace declared by the manifest at `manifest_path`, or
+  /// `None` if it has no `[workspace]` section.
+  pub fn load(manifest_path: &Path) -> Option<Self> {
+    let toml_content = fs::read_to_string(manifest_path).expect("Failed to read buff.toml");
+    let manifest: WorkspaceManifest =
+      toml::from_str(&toml_content).expect("Failed to parse buff.toml");
+    let section = manifest.workspace?;
+    let root = manifest::package_dir(manifest_path);
+    let mut members: Vec<Member> = Vec::new();
+    for pattern in &section.members {
+      for dir in expand_member(&root, pattern) {
+        let member_manifest = dir.join(MANIFEST_FILE_NAME);
+        if !member_manifest.is_file() {
+          panic!(
+            "Workspace member `{}` has no {}",
+            dir.display(),
+            MANIFEST_FILE_NAME
+          );
+        }
+        let metadata = PackageMetadata::new(member_manifest.to_str().unwrap());
+        if members
+          .iter()
+          .any(|member| member.metadata.package.name == metadata.package.name)
+        {
+          panic!(
+            "Package `{}` is listed more than once in the workspace",
+            metadata.package.name
+          );
+        }
+        for (name, dependency) in &metadata.dependencies {
+          if dependency.is_workspace() && !section.dependencies.contains_key(name) {
+            panic!(
+              "`{}` inherits dependency `{}` which isn't declared in [workspace.dependencies]",
+              metadata.package.name, name
+            );
+          }
+        }
+        members.push(Member {
+          manifest_path: member_manifest,
+          metadata,
+        });
+      }
+    }
+    Some(Workspace {
+      root,
+      dependencies: section.dependencies,
+      members,
+    })
+  }
+
+  pub fn lockfile_path(&self) -> PathBuf {
+    self.root.join("buff.lock")
+  }
+
+  pub fn member(&self, name: &str) -> Option<&Member> {
+    self
+      .members
+      .iter()
+      .find(|member| member.metadata.package.name == name)
+  }
+
+  /// The version requirement `member` has on `dependency`, looking it up in
+  /// `[workspace.dependencies]` when the member inherits it.
+  pub fn dependency_requirement<'a>(
+    &'a self,
+    member: &'a Member,
+    dependency: &str,
+  ) -> Option<&'a str> {
+    let declared = member.metadata.dependencies.get(dependency)?;
+    if declared.is_workspace() {
+      self.dependencies.get(dependency)?.version()
+    } else {
+      declared.version()
+    }
+  }
+
+  /// Members ordered so that each one comes after the members it depends on,
+  /// alphabetically where the order doesn't matter.
+  pub fn publish_order(&self) -> Vec<&Member> {
+    let mut pending: BTreeMap<&str, BTreeSet<&str>> = self
+      .members
+      .iter()
+      .map(|member| {
+        let name = member.metadata.package.name.as_str();
+        let dependencies = member
+          .metadata
+          .dependencies
+          .keys()
+          .map(String::as_str)
+          .filter(|dependency| *dependency != name && self.member(dependency).is_some())
+          .collect();
+        (name, dependencies)
+      })
+      .collect();
+    let mut order = Vec::new();
+    while !pending.is_empty() {
+      let ready: Vec<&str> = pending
+        .iter()
+        .filter(|(_, dependencies)| dependencies.is_empty())
+        .map(|(name, _)| *name)
+        .collect();
+      if ready.is_empty() {
+        let cycle: Vec<&str> = pending.keys().cloned().collect();
+        panic!(
+          "Workspace members depend on each other in a cycle: {}",
+          cycle.join(", ")
+        );
+      }
+      for name in ready {
+        pending.remove(name);
+        for dependencies in pending.values_mut() {
+          dependencies.remove(name);
+        }
+        order.push(self.member(name).unwrap());
+      }
+    }
+    order
+  }
+}
+
+/// Finds the workspace the package at `manifest_path` belongs to by looking
+/// for a buff.toml with a `[workspace]` section in the package directory and
+/// its parents. The search stops at the first workspace root, so a package
+/// that root doesn't list isn't part of any workspace.
+pub fn find_workspace(manifest_path: &Path) -> Option<Workspace> {
+  let package_dir = manifest::package_dir(manifest_path)
+    .canonicalize()
+    .expect("Failed to resolve package dir");
+  for dir in package_dir.ancestors() {
+    let candidate = dir.join(MANIFEST_FILE_NAME);
+    if !candidate.is_file() {
+      continue;
+    }
+    if let Some(workspace) = Workspace::load(&candidate) {
+      let is_member = dir == package_dir
+        || workspace.members.iter().any(|member| {
+          manifest::package_dir(&member.manifest_path)
+            .canonicalize()
+            .map_or(false, |member_dir| member_dir == package_dir)
+        });
+      return if is_member { Some(workspace) } else { None };
+    }
+  }
+  None
+}
+
+fn expand_member(root: &Path, pattern: &str) -> Vec<PathBuf> {
+  if pattern.ends_with("/*") {
+    let parent = root.join(&pattern[..pattern.len() - 2]);
+    let mut dirs: Vec<PathBuf> = fs::read_dir(&parent)
+      .unwrap_or_else(|_| panic!("Failed to read workspace members in `{}`", parent.display()))
+      .map(|entry| entry.unwrap().path())
+      .filter(|path| path.join(MANIFEST_FILE_NAME).is_file())
+      .collect();
+    dirs.sort();
+    dirs
+  } else {
+    vec![root.join(pattern)]
+  }
+}
+
+#[cfg(test)]
+fn write_package(dir: &Path, name: &str, dependencies: &str) {
+  fs::create_dir_all(dir).unwrap();
+  fs::write(
+    dir.join(MANIFEST_FILE_NAME),
+    format!(
+      "[package]\nname = \"{}\"\nversion = \"0.1.0\"\ndescription = \"\"\nhomepage = \"\"\nrepository_url = \"\"\nkeywords = []\n\n[dependencies]\n{}",
+      name, dependencies
+    ),
+  )
+  .unwrap();
+}
+
+#[test]
+fn should_load_workspace() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let root = tmp_dir.path();
+  fs::write(
+    root.join(MANIFEST_FILE_NAME),
+    "[workspace]\nmembers = [\"common\", \"services/*\"]\n\n[workspace.dependencies]\nyummy_package = \"1.2\"\n",
+  )
+  .unwrap();
+  write_package(&root.join("common"), "common", "");
+  write_package(
+    &root.join("services/billing"),
+    "billing",
+    "common = \"0.1\"\nyummy_package = { workspace = true }\n",
+  );
+  fs::create_dir_all(root.join("services/docs")).unwrap();
+  let member_manifest = root.join("services/billing").join(MANIFEST_FILE_NAME);
+  let workspace = find_workspace(&member_manifest).unwrap();
+  let names: Vec<&str> = workspace
+    .members
+    .iter()
+    .map(|member| member.metadata.package.name.as_str())
+    .collect();
+  assert_eq!(names, vec!["common", "billing"]);
+  let billing = workspace.member("billing").unwrap();
+  assert_eq!(
+    workspace.dependency_requirement(billing, "yummy_package"),
+    Some("1.2")
+  );
+  assert_eq!(
+    workspace.dependency_requirement(billing, "common"),
+    Some("0.1")
+  );
+  assert_eq!(
+    crate::lockfile::lockfile_path(&member_manifest),
+    root.canonicalize().unwrap().join("buff.lock")
+  );
+  write_package(&root.join("unlisted"), "unlisted", "");
+  assert!(find_workspace(&root.join("unlisted").join(MANIFEST_FILE_NAME)).is_none());
+}
+
+#[test]
+fn should_order_members_by_dependencies() {
+  let tmp_dir = tempdir::TempDir::new("buff_test").unwrap();
+  let root = tmp_dir.path();
+  fs::write(
+    root.join(MANIFEST_FILE_NAME),
+    "[workspace]\nmembers = [\"a\", \"b\", \"c\", \"d\"]\n",
+  )
+  .unwrap();
+  write_package(&root.join("a"), "a", "c = \"0.1\"\n");
+  write_package(&root.join("b"), "b", "yummy_package = \"1.0\"\n");
+  write_package(&root.join("c"), "c", "d = \"0.1\"\n");
+  write_package(&root.join("d"), "d", "");
+  let workspace = Workspace::load(&root.join(MANIFEST_FILE_NAME)).unwrap();
+  let order: Vec<&str> = workspace
+    .publish_order()
+    .iter()
+    .map(|member| member.metadata.package.name.as_str())
+    .collect();
+  assert_eq!(order, vec!["b", "d", "c", "a"]);
+}
@@ -20,6 +20,12 @@ config :buff_server,
   generators: [binary_id: true],
   password_hasher: Argon2
 
+# Set enforce_compatibility to refuse versions that break consumers of an earlier
+# version in the same major line. The check runs the `buff` CLI at buff_executable.
+config :buff_server,
+  enforce_compatibility: false,
+  buff_executable: "buff"
+
 # Configures the endpoint
 config :buff_server, BuffServerWeb.Endpoint,
   url: [host: "localhost"],
@@ -0,0 +1,93 @@
+defmodule BuffServer.Compatibility do
+  @moduledoc """
+  Refuses versions that break consumers of an earlier version in the same major line,
+  when `enforce_compatibility` is set. The analysis is the one `buff check` runs, through
+  `buff check-publish`, so the registry and the CLI agree on what's breaking.
+  """
+
+  alias BuffServer.Packages
+  alias BuffServerGrpc.CompatibilityRejection
+  alias BuffServerGrpc.CompatibilityViolation
+
+  # Exit codes of `buff check-publish`.
+  @breaking 1
+  @invalid 2
+
+  @doc """
+  Checks the artifact in `attrs` against the latest version published before it.
+  Returns `:ok`, `{:rejected, rejection}` with the breaking changes, `{:error, reason}`
+  when the artifact can't be checked, or `{:unavailable, reason}` when the earlier version
+  can't be read from storage.
+  """
+  def check(%{name: name, version: version, artifact_binary: artifact}) do
+    with true <- Application.get_env(:buff_server, :enforce_compatibility, false),
+         %Version{} = baseline <- previous_version(name, version) do
+      package = Packages.get_package_version(name, to_string(baseline))
+
+      case Packages.get_artifact(package) do
+        {:ok, baseline_artifact} ->
+          check_publish(artifact, baseline_artifact)
+
+        {:error, reason} ->
+          {:unavailable, "failed to read #{name} #{baseline}: #{inspect(reason)}"}
+      end
+    else
+      _ -> :ok
+    end
+  end
+
+  # Packages without a name or version are refused by the changeset.
+  def check(_attrs), do: :ok
+
+  # Whether breaking changes are allowed against it is up to `buff check-publish`.
+  defp previous_version(name, version) do
+    with {:ok, version} <- Version.parse(version) do
+      name
+      |> Packages.list_versions()
+      |> Enum.flat_map(fn published ->
+        case Version.parse(published) do
+          {:ok, %Version{pre: []} = published} -> [published]
+          _ -> []
+        end
+      end)
+      |> Enum.filter(&(Version.compare(&1, version) == :lt))
+      |> Enum.max_by(&{&1.major, &1.minor, &1.patch}, fn -> nil end)
+    end
+  end
+
+  defp check_publish(artifact, baseline) do
+    dir = Path.join(System.tmp_dir!(), "buff_check_publish_#{System.unique_integer([:positive])}")
+    File.mkdir_p!(dir)
+
+    try do
+      artifact_path = Path.join(dir, "artifact")
+      baseline_path = Path.join(dir, "baseline")
+      File.write!(artifact_path, artifact)
+      File.write!(baseline_path, baseline)
+      executable = Application.get_env(:buff_server, :buff_executable, "buff")
+
+      case System.cmd(executable, ["check-publish", artifact_path, "--baseline", baseline_path]) do
+        {_output, 0} -> :ok
+        {output, @breaking} -> {:rejected, rejection(Jason.decode!(output))}
+        {output, @invalid} -> {:error, String.trim(output)}
+        {output, status} -> raise "buff check-publish exited with #{status}: #{output}"
+      end
+    after
+      File.rm_rf!(dir)
+    end
+  end
+
+  defp rejection(%{"baseline" => baseline, "violations" => violations}) do
+    CompatibilityRejection.new(
+      baseline: baseline,
+      violations:
+        Enum.map(violations, fn violation ->
+          CompatibilityViolation.new(
+            rule: violation["rule"],
+            path: violation["path"],
+            message: violation["message"]
+          )
+        end)
+    )
+  end
+end
@@ -27,6 +27,21 @@ defmodule BuffServer.Packages do
   """
   def get_package!(id), do: Repo.get!(Package, id)
 
+  @doc """
+  Gets the package published under `name` at `version`, or nil if there isn't one.
+  """
+  def get_package_version(name, version), do: Repo.get_by(Package, name: name, version: version)
+
+  @doc """
+  Returns every version of the package published under `name`.
+  """
+  def list_versions(name) do
+    Package
+    |> where([p], p.name == ^name)
+    |> select([p], p.version)
+    |> Repo.all()
+  end
+
   @doc """
   Creates the package and uploads to S3, this is run inside a transaction so if anything fails it'll rollback.
   """
@@ -61,10 +76,44 @@ defmodule BuffServer.Packages do
   end
 
   @doc """
-  Generates the s3 bucket path to be used for this package.
+  Reads the artifact of the package back from S3.
+  """
+  def get_artifact(%Package{} = package) do
+    get_res =
+      package.s3_bucket_name
+      |> ExAws.S3.get_object(package.s3_bucket_path)
+      |> ExAws.request()
+
+    with {:ok, %{status_code: 200, body: body}} <- get_res do
+      {:ok, body}
+    else
+      err -> {:error, err}
+    end
+  end
+
+  @doc """
+  Runs the same validations as `create_package/1` without storing anything.
+  """
+  def validate_package(attrs) do
+    attrs =
+      Map.merge(attrs, %{s3_bucket_name: @s3_bucket_name, s3_bucket_path: s3_bucket_path(attrs)})
+
+    changeset = Package.changeset(%Package{}, attrs)
+
+    with true <- changeset.valid?,
+         nil <- get_package_version(attrs.name, attrs.version) do
+      :ok
+    else
+      false -> {:error, changeset}
+      %Package{} -> {:error, :already_exists}
+    end
+  end
+
+  @doc """
+  Generates the s3 bucket path to be used for this version of the package.
   """
   def s3_bucket_path(attrs) do
-    "/#{attrs.name}/artifact"
+    "/#{attrs.name}/#{attrs.version}/artifact"
   end
 
   @doc """
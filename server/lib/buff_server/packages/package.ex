@@ -9,6 +9,7 @@ defmodule BuffServer.Packages.Package do
   @foreign_key_type :binary_id
   @required_fields [
     :name,
+    :version,
     :description,
     :keywords,
     :homepage,
@@ -16,15 +17,21 @@ defmodule BuffServer.Packages.Package do
     :s3_bucket_name,
     :s3_bucket_path
   ]
+  @optional_fields [:signature, :public_key, :provenance, :provenance_signature]
   schema "packages" do
     field(:description, :string)
     field(:homepage, :string)
     field(:keywords, {:array, :string})
     field(:name, :string)
+    field(:version, :string)
     field(:repository_url, :string)
     field(:owner_user_id, :binary_id)
     field(:s3_bucket_name, :string)
     field(:s3_bucket_path, :string)
+    field(:signature, :binary)
+    field(:public_key, :binary)
+    field(:provenance, :binary)
+    field(:provenance_signature, :binary)
 
     timestamps()
   end
@@ -32,9 +39,9 @@ defmodule BuffServer.Packages.Package do
   @doc false
   def changeset(package, attrs) do
     package
-    |> cast(attrs, @required_fields)
+    |> cast(attrs, @required_fields ++ @optional_fields)
     |> validate_required(@required_fields)
-    |> unique_constraint(:name)
+    |> unique_constraint(:version, name: :packages_name_version_index)
     |> validate_url(:repository_url)
   end
 
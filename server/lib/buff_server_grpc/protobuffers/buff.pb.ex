@@ -49,11 +49,21 @@ defmodule BuffServerGrpc.PublishRequest do
   use Protobuf, syntax: :proto3
 
   @type t :: %__MODULE__{
-          artifact: binary
+          artifact: binary,
+          validate_only: boolean,
+          signature: binary,
+          public_key: binary,
+          provenance: binary,
+          provenance_signature: binary
         }
-  defstruct [:artifact]
+  defstruct [:artifact, :validate_only, :signature, :public_key, :provenance, :provenance_signature]
 
   field :artifact, 1, type: :bytes
+  field :validate_only, 2, type: :bool
+  field :signature, 3, type: :bytes
+  field :public_key, 4, type: :bytes
+  field :provenance, 5, type: :bytes
+  field :provenance_signature, 6, type: :bytes
 end
 
 defmodule BuffServerGrpc.PublishResponse do
@@ -61,11 +71,122 @@ defmodule BuffServerGrpc.PublishResponse do
   use Protobuf, syntax: :proto3
 
   @type t :: %__MODULE__{
-          result: boolean
+          result: boolean,
+          rejection: BuffServerGrpc.CompatibilityRejection.t() | nil
         }
-  defstruct [:result]
+  defstruct [:result, :rejection]
 
   field :result, 1, type: :bool
+  field :rejection, 2, type: BuffServerGrpc.CompatibilityRejection
+end
+
+defmodule BuffServerGrpc.CompatibilityRejection do
+  @moduledoc false
+  use Protobuf, syntax: :proto3
+
+  @type t :: %__MODULE__{
+          baseline: String.t(),
+          violations: [BuffServerGrpc.CompatibilityViolation.t()]
+        }
+  defstruct [:baseline, :violations]
+
+  field :baseline, 1, type: :string
+  field :violations, 2, repeated: true, type: BuffServerGrpc.CompatibilityViolation
+end
+
+defmodule BuffServerGrpc.CompatibilityViolation do
+  @moduledoc false
+  use Protobuf, syntax: :proto3
+
+  @type t :: %__MODULE__{
+          rule: String.t(),
+          path: String.t(),
+          message: String.t()
+        }
+  defstruct [:rule, :path, :message]
+
+  field :rule, 1, type: :string
+  field :path, 2, type: :string
+  field :message, 3, type: :string
+end
+
+defmodule BuffServerGrpc.GetPackageVersionsRequest do
+  @moduledoc false
+  use Protobuf, syntax: :proto3
+
+  @type t :: %__MODULE__{
+          name: String.t()
+        }
+  defstruct [:name]
+
+  field :name, 1, type: :string
+end
+
+defmodule BuffServerGrpc.GetPackageVersionsResponse do
+  @moduledoc false
+  use Protobuf, syntax: :proto3
+
+  @type t :: %__MODULE__{
+          versions: [String.t()]
+        }
+  defstruct [:versions]
+
+  field :versions, 1, repeated: true, type: :string
+end
+
+defmodule BuffServerGrpc.DownloadRequest do
+  @moduledoc false
+  use Protobuf, syntax: :proto3
+
+  @type t :: %__MODULE__{
+          name: String.t(),
+          version: String.t()
+        }
+  defstruct [:name, :version]
+
+  field :name, 1, type: :string
+  field :version, 2, type: :string
+end
+
+defmodule BuffServerGrpc.DownloadResponse do
+  @moduledoc false
+  use Protobuf, syntax: :proto3
+
+  @type t :: %__MODULE__{
+          artifact: binary,
+          signature: binary,
+          public_key: binary,
+          provenance: binary,
+          provenance_signature: binary
+        }
+  defstruct [:artifact, :signature, :public_key, :provenance, :provenance_signature]
+
+  field :artifact, 1, type: :bytes
+  field :signature, 2, type: :bytes
+  field :public_key, 3, type: :bytes
+  field :provenance, 4, type: :bytes
+  field :provenance_signature, 5, type: :bytes
+end
+
+defmodule BuffServerGrpc.GetCapabilitiesRequest do
+  @moduledoc false
+  use Protobuf, syntax: :proto3
+
+  @type t :: %__MODULE__{}
+  defstruct []
+
+end
+
+defmodule BuffServerGrpc.GetCapabilitiesResponse do
+  @moduledoc false
+  use Protobuf, syntax: :proto3
+
+  @type t :: %__MODULE__{
+          compression: [String.t()]
+        }
+  defstruct [:compression]
+
+  field :compression, 1, repeated: true, type: :string
 end
 
 defmodule BuffServerGrpc.AuthService.Service do
@@ -85,6 +206,10 @@ defmodule BuffServerGrpc.RegistryService.Service do
   use GRPC.Service, name: "buff_server_grpc.RegistryService"
 
   rpc(:Publish, BuffServerGrpc.PublishRequest, BuffServerGrpc.PublishResponse)
+  rpc(:PublishStream, stream(BuffServerGrpc.PublishRequest), BuffServerGrpc.PublishResponse)
+  rpc(:GetPackageVersions, BuffServerGrpc.GetPackageVersionsRequest, BuffServerGrpc.GetPackageVersionsResponse)
+  rpc(:Download, BuffServerGrpc.DownloadRequest, BuffServerGrpc.DownloadResponse)
+  rpc(:GetCapabilities, BuffServerGrpc.GetCapabilitiesRequest, BuffServerGrpc.GetCapabilitiesResponse)
 end
 
 defmodule BuffServerGrpc.RegistryService.Stub do
@@ -4,18 +4,142 @@ defmodule BuffServerGrpc.RegistryService.Server do
   """
   use GRPC.Server, service: BuffServerGrpc.RegistryService.Service
 
-  def publish(%{artifact: artifact}, _stream) do
+  def publish(%{artifact: artifact, validate_only: true}, _stream) do
     package_attributes = get_package_attributes_from_artifact(artifact)
     attrs = package_attributes |> Map.merge(%{artifact_binary: artifact})
-    {:ok, _package} = BuffServer.Packages.create_package(attrs)
-    BuffServerGrpc.PublishResponse.new(result: true)
+
+    case BuffServer.Packages.validate_package(attrs) do
+      :ok -> check_compatibility(attrs, fn -> BuffServerGrpc.PublishResponse.new(result: true) end)
+      {:error, reason} -> raise publish_error(attrs, reason)
+    end
+  end
+
+  def publish(%{artifact: artifact} = request, _stream) do
+    package_attributes = get_package_attributes_from_artifact(artifact)
+
+    attrs =
+      package_attributes
+      |> Map.merge(%{artifact_binary: artifact})
+      |> Map.merge(signature_attributes(request))
+      |> Map.merge(provenance_attributes(request))
+
+    check_compatibility(attrs, fn -> create_package(attrs) end)
+  end
+
+  # The artifact comes in chunks, the rest of the fields with the first one.
+  def publish_stream(requests, stream) do
+    case Enum.to_list(requests) do
+      [] ->
+        raise GRPC.RPCError.exception(GRPC.Status.invalid_argument(), "No artifact was sent")
+
+      [first | _] = requests ->
+        artifact = requests |> Enum.map(& &1.artifact) |> IO.iodata_to_binary()
+        publish(%{first | artifact: artifact}, stream)
+    end
+  end
+
+  def get_package_versions(%{name: name}, _stream) do
+    BuffServerGrpc.GetPackageVersionsResponse.new(versions: BuffServer.Packages.list_versions(name))
+  end
+
+  def download(%{name: name, version: version}, _stream) do
+    package =
+      BuffServer.Packages.get_package_version(name, version) ||
+        raise GRPC.RPCError.exception(GRPC.Status.not_found(), "#{name} #{version} isn't published")
+
+    {:ok, artifact} = BuffServer.Packages.get_artifact(package)
+
+    BuffServerGrpc.DownloadResponse.new(
+      artifact: artifact,
+      signature: package.signature,
+      public_key: package.public_key,
+      provenance: package.provenance,
+      provenance_signature: package.provenance_signature
+    )
+  end
+
+  @supported_compression ["gzip", "none"]
+
+  def get_capabilities(_request, _stream) do
+    BuffServerGrpc.GetCapabilitiesResponse.new(compression: @supported_compression)
+  end
+
+  defp create_package(attrs) do
+    case BuffServer.Packages.create_package(attrs) do
+      {:ok, _package} ->
+        BuffServerGrpc.PublishResponse.new(result: true)
+
+      {:error, :package, changeset, _changes} ->
+        raise publish_error(attrs, changeset)
+
+      {:error, :s3_artifact, _reason, _changes} ->
+        raise GRPC.RPCError.exception(GRPC.Status.internal(), "Failed to store the artifact")
+    end
+  end
+
+  # Runs `publish` unless the package breaks consumers of an earlier version.
+  defp check_compatibility(attrs, publish) do
+    case BuffServer.Compatibility.check(attrs) do
+      :ok ->
+        publish.()
+
+      {:rejected, rejection} ->
+        BuffServerGrpc.PublishResponse.new(result: false, rejection: rejection)
+
+      {:error, reason} ->
+        raise GRPC.RPCError.exception(GRPC.Status.invalid_argument(), "Invalid package: #{reason}")
+
+      {:unavailable, reason} ->
+        raise GRPC.RPCError.exception(
+          GRPC.Status.unavailable(),
+          "Couldn't check compatibility, #{reason}"
+        )
+    end
+  end
+
+  defp publish_error(attrs, :already_exists) do
+    GRPC.RPCError.exception(
+      GRPC.Status.already_exists(),
+      "#{attrs[:name]} #{attrs[:version]} is already published"
+    )
+  end
+
+  defp publish_error(attrs, %Ecto.Changeset{errors: errors}) do
+    if Enum.any?(errors, fn {_field, {_message, opts}} -> opts[:constraint] == :unique end) do
+      publish_error(attrs, :already_exists)
+    else
+      message = Enum.map_join(errors, ", ", fn {field, {message, _opts}} -> "#{field} #{message}" end)
+      GRPC.RPCError.exception(GRPC.Status.invalid_argument(), "Invalid package: #{message}")
+    end
   end
 
   defp get_package_attributes_from_artifact(artifact) do
-    {:ok, package_file} = :erl_tar.extract({:binary, artifact}, [:memory, :compressed, {:files, ['buff.toml']}])
+    {:ok, package_file} =
+      :erl_tar.extract({:binary, artifact}, [:memory, {:files, ['buff.toml']} | compression_options(artifact)])
 
     {_, buff_toml} = List.first(package_file)
     {:ok, package} = Toml.decode(buff_toml, keys: :atoms)
     package[:package]
   end
+
+  defp signature_attributes(%{signature: signature, public_key: public_key})
+       when is_binary(signature) and byte_size(signature) > 0 do
+    %{signature: signature, public_key: public_key}
+  end
+
+  defp signature_attributes(_request), do: %{}
+
+  defp provenance_attributes(%{provenance: provenance, provenance_signature: signature})
+       when is_binary(provenance) and byte_size(provenance) > 0 do
+    if signature in [nil, ""] do
+      raise GRPC.RPCError.exception(GRPC.Status.invalid_argument(), "Provenance must be signed")
+    end
+
+    %{provenance: provenance, provenance_signature: signature}
+  end
+
+  defp provenance_attributes(_request), do: %{}
+
+  defp compression_options(<<0x1F, 0x8B, _::binary>>), do: [:compressed]
+  defp compression_options(_artifact), do: []
 end
@@ -0,0 +1,10 @@
+defmodule BuffServer.Repo.Migrations.AddSignatureToPackages do
+  use Ecto.Migration
+
+  def change do
+    alter table(:packages) do
+      add(:signature, :binary)
+      add(:public_key, :binary)
+    end
+  end
+end
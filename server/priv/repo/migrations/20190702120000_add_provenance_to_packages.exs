@@ -0,0 +1,10 @@
+defmodule BuffServer.Repo.Migrations.AddProvenanceToPackages do
+  use Ecto.Migration
+
+  def change do
+    alter table(:packages) do
+      add(:provenance, :binary)
+      add(:provenance_signature, :binary)
+    end
+  end
+end
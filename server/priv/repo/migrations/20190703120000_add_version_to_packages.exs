@@ -0,0 +1,12 @@
+defmodule BuffServer.Repo.Migrations.AddVersionToPackages do
+  use Ecto.Migration
+
+  def change do
+    alter table(:packages) do
+      add(:version, :string, null: false, default: "0.0.0")
+    end
+
+    drop(unique_index(:packages, [:name]))
+    create(unique_index(:packages, [:name, :version]))
+  end
+end
@@ -14,6 +14,7 @@ defmodule BuffServer.PackagesTest do
       homepage: Faker.Internet.url(),
       keywords: Enum.reduce(1..10, [], fn _x, acc -> acc ++ [Faker.Company.En.buzzword()] end),
       name: Faker.Company.En.buzzword(),
+      version: "0.1.0",
       repository_url: Faker.Internet.url(),
       artifact_binary: @dummy_artifact
     }
@@ -23,6 +24,7 @@ defmodule BuffServer.PackagesTest do
       homepage: nil,
       keywords: nil,
       name: nil,
+      version: nil,
       repository_url: nil,
       artifact_binary: @dummy_artifact
     }
@@ -45,6 +47,7 @@ defmodule BuffServer.PackagesTest do
     test "create_package/2 with valid data creates a package" do
       {:ok, package} = package_fixture(@default_attrs)
       assert package.name == @default_attrs.name
+      assert package.version == @default_attrs.version
       assert package.description == @default_attrs.description
       assert package.homepage == @default_attrs.homepage
       assert package.keywords == @default_attrs.keywords
@@ -63,6 +66,19 @@ defmodule BuffServer.PackagesTest do
       assert Packages.get_package!(package.id) == package
     end
 
+    test "list_versions/1 returns every published version of a package" do
+      {:ok, _} = package_fixture(@default_attrs)
+      {:ok, _} = package_fixture(%{@default_attrs | version: "0.2.0"})
+      assert Enum.sort(Packages.list_versions(@default_attrs.name)) == ["0.1.0", "0.2.0"]
+      assert Packages.list_versions("missing") == []
+    end
+
+    test "create_package/1 refuses to publish a version twice" do
+      {:ok, _} = package_fixture(@default_attrs)
+      {:error, :package, changeset, _} = package_fixture(@default_attrs)
+      assert changeset.valid? == false
+    end
+
     test "create_package/1 with invalid data returns error changeset" do
       {:error, :package, changeset, _} = package_fixture(@invalid_attrs)
       assert changeset.valid? == false
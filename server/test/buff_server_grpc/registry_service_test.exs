@@ -24,6 +24,250 @@ defmodule BuffServerGrpc.BuffRegistryTest do
       end)
     end
 
+    test "should publish an artifact sent in chunks" do
+      <<first_chunk::binary-size(100), rest::binary>> = @dummy_artifact
+
+      ExAws.Request.HttpMock
+      |> stub(:request, fn _method, _url, _body, _headers, _opts ->
+        {:ok, %{status_code: 200}}
+      end)
+
+      get_client(RegistryServer, fn channel ->
+        stream = RegistryService.Stub.publish_stream(channel)
+        GRPC.Stub.send_request(stream, BuffServerGrpc.PublishRequest.new(artifact: first_chunk))
+        GRPC.Stub.send_request(stream, BuffServerGrpc.PublishRequest.new(artifact: rest), end_stream: true)
+        assert {:ok, %PublishResponse{result: true}} == GRPC.Stub.recv(stream)
+        assert ["0.1.0"] == BuffServer.Packages.list_versions("osher")
+      end)
+    end
+
+    test "should store the signature of a signed PublishRequest" do
+      publish_req =
+        BuffServerGrpc.PublishRequest.new(
+          artifact: @dummy_artifact,
+          signature: <<1, 2, 3>>,
+          public_key: <<4, 5, 6>>
+        )
+
+      ExAws.Request.HttpMock
+      |> stub(:request, fn _method, _url, _body, _headers, _opts ->
+        {:ok, %{status_code: 200}}
+      end)
+
+      get_client(RegistryServer, fn channel ->
+        assert {:ok, %PublishResponse{result: true}} == RegistryService.Stub.publish(channel, publish_req)
+        assert [%{signature: <<1, 2, 3>>, public_key: <<4, 5, 6>>}] = BuffServer.Packages.list_packages()
+      end)
+    end
+
+    test "should store the provenance of a PublishRequest" do
+      publish_req =
+        BuffServerGrpc.PublishRequest.new(
+          artifact: @dummy_artifact,
+          provenance: ~s({"_type": "https://in-toto.io/Statement/v0.1"}),
+          provenance_signature: <<7, 8, 9>>
+        )
+
+      ExAws.Request.HttpMock
+      |> stub(:request, fn _method, _url, _body, _headers, _opts ->
+        {:ok, %{status_code: 200}}
+      end)
+
+      get_client(RegistryServer, fn channel ->
+        assert {:ok, %PublishResponse{result: true}} == RegistryService.Stub.publish(channel, publish_req)
+        assert [%{provenance_signature: <<7, 8, 9>>}] = BuffServer.Packages.list_packages()
+      end)
+    end
+
+    test "should refuse unsigned provenance" do
+      publish_req =
+        BuffServerGrpc.PublishRequest.new(
+          artifact: @dummy_artifact,
+          provenance: ~s({"_type": "https://in-toto.io/Statement/v0.1"})
+        )
+
+      get_client(RegistryServer, fn channel ->
+        assert {:error, %GRPC.RPCError{message: "Provenance must be signed", status: 3}} ==
+                 RegistryService.Stub.publish(channel, publish_req)
+
+        assert [] == BuffServer.Packages.list_packages()
+      end)
+    end
+
+    test "should validate without storing for a validate_only PublishRequest" do
+      publish_req = BuffServerGrpc.PublishRequest.new(artifact: @dummy_artifact, validate_only: true)
+
+      get_client(RegistryServer, fn channel ->
+        assert {:ok, %PublishResponse{result: true}} == RegistryService.Stub.publish(channel, publish_req)
+        assert BuffServer.Packages.list_packages() == []
+      end)
+    end
+
+    test "should list the published versions of a package" do
+      ExAws.Request.HttpMock
+      |> stub(:request, fn _method, _url, _body, _headers, _opts ->
+        {:ok, %{status_code: 200}}
+      end)
+
+      get_client(RegistryServer, fn channel ->
+        versions_req = BuffServerGrpc.GetPackageVersionsRequest.new(name: "osher")
+
+        assert {:ok, %BuffServerGrpc.GetPackageVersionsResponse{versions: []}} ==
+                 RegistryService.Stub.get_package_versions(channel, versions_req)
+
+        RegistryService.Stub.publish(channel, BuffServerGrpc.PublishRequest.new(artifact: @dummy_artifact))
+
+        assert {:ok, %BuffServerGrpc.GetPackageVersionsResponse{versions: ["0.1.0"]}} ==
+                 RegistryService.Stub.get_package_versions(channel, versions_req)
+      end)
+    end
+
+    test "should download a published version with its signature and provenance" do
+      publish_req =
+        BuffServerGrpc.PublishRequest.new(
+          artifact: @dummy_artifact,
+          signature: <<1, 2, 3>>,
+          public_key: <<4, 5, 6>>,
+          provenance: "statement",
+          provenance_signature: <<7, 8, 9>>
+        )
+
+      ExAws.Request.HttpMock
+      |> stub(:request, fn _method, _url, _body, _headers, _opts ->
+        {:ok, %{status_code: 200, body: @dummy_artifact}}
+      end)
+
+      get_client(RegistryServer, fn channel ->
+        RegistryService.Stub.publish(channel, publish_req)
+        download_req = BuffServerGrpc.DownloadRequest.new(name: "osher", version: "0.1.0")
+
+        assert {:ok,
+                %BuffServerGrpc.DownloadResponse{
+                  artifact: @dummy_artifact,
+                  signature: <<1, 2, 3>>,
+                  public_key: <<4, 5, 6>>,
+                  provenance: "statement",
+                  provenance_signature: <<7, 8, 9>>
+                }} == RegistryService.Stub.download(channel, download_req)
+      end)
+    end
+
+    test "should return not found when downloading an unpublished version" do
+      download_req = BuffServerGrpc.DownloadRequest.new(name: "osher", version: "9.9.9")
+
+      get_client(RegistryServer, fn channel ->
+        assert {:error, %GRPC.RPCError{status: 5}} = RegistryService.Stub.download(channel, download_req)
+      end)
+    end
+
+    test "should return already exists for a validate_only PublishRequest of a published version" do
+      ExAws.Request.HttpMock
+      |> stub(:request, fn _method, _url, _body, _headers, _opts ->
+        {:ok, %{status_code: 200}}
+      end)
+
+      get_client(RegistryServer, fn channel ->
+        RegistryService.Stub.publish(channel, BuffServerGrpc.PublishRequest.new(artifact: @dummy_artifact))
+        validate_req = BuffServerGrpc.PublishRequest.new(artifact: @dummy_artifact, validate_only: true)
+
+        assert {:error, %GRPC.RPCError{message: "osher 0.1.0 is already published", status: 6}} ==
+                 RegistryService.Stub.publish(channel, validate_req)
+      end)
+    end
+
+    test "should return invalid argument for a validate_only PublishRequest of an invalid package" do
+      path = Path.join(System.tmp_dir!(), "invalid_artifact.tar")
+      :ok = :erl_tar.create(String.to_charlist(path), [{'buff.toml', "[package]\nname = \"osher\"\nversion = \"0.1.0\"\n"}])
+      publish_req = BuffServerGrpc.PublishRequest.new(artifact: File.read!(path), validate_only: true)
+
+      get_client(RegistryServer, fn channel ->
+        assert {:error, %GRPC.RPCError{status: 3}} = RegistryService.Stub.publish(channel, publish_req)
+      end)
+    end
+
+    test "should reject breaking changes when compatibility is enforced" do
+      Application.put_env(:buff_server, :enforce_compatibility, true)
+      Application.put_env(:buff_server, :buff_executable, Path.expand("test/support/fake_buff_breaking.sh"))
+
+      on_exit(fn ->
+        Application.put_env(:buff_server, :enforce_compatibility, false)
+        Application.put_env(:buff_server, :buff_executable, "buff")
+      end)
+
+      {:ok, [{_, buff_toml}]} =
+        :erl_tar.extract({:binary, @dummy_artifact}, [:memory, :compressed, {:files, ['buff.toml']}])
+
+      path = Path.join(System.tmp_dir!(), "next_artifact.tar")
+      :ok = :erl_tar.create(String.to_charlist(path), [{'buff.toml', String.replace(buff_toml, "0.1.0", "0.1.1")}])
+
+      ExAws.Request.HttpMock
+      |> stub(:request, fn _method, _url, _body, _headers, _opts ->
+        {:ok, %{status_code: 200, body: @dummy_artifact}}
+      end)
+
+      get_client(RegistryServer, fn channel ->
+        RegistryService.Stub.publish(channel, BuffServerGrpc.PublishRequest.new(artifact: @dummy_artifact))
+        publish_req = BuffServerGrpc.PublishRequest.new(artifact: File.read!(path))
+
+        assert {:ok,
+                %PublishResponse{
+                  result: false,
+                  rejection: %BuffServerGrpc.CompatibilityRejection{
+                    baseline: "0.1.0",
+                    violations: [%BuffServerGrpc.CompatibilityViolation{rule: "FIELD_TYPE_CHANGED"}]
+                  }
+                }} = RegistryService.Stub.publish(channel, publish_req)
+
+        assert ["0.1.0"] == BuffServer.Packages.list_versions("osher")
+      end)
+    end
+
+    test "should return unavailable when the earlier version can't be read to enforce compatibility" do
+      Application.put_env(:buff_server, :enforce_compatibility, true)
+      on_exit(fn -> Application.put_env(:buff_server, :enforce_compatibility, false) end)
+
+      {:ok, [{_, buff_toml}]} =
+        :erl_tar.extract({:binary, @dummy_artifact}, [:memory, :compressed, {:files, ['buff.toml']}])
+
+      path = Path.join(System.tmp_dir!(), "next_artifact.tar")
+      :ok = :erl_tar.create(String.to_charlist(path), [{'buff.toml', String.replace(buff_toml, "0.1.0", "0.1.1")}])
+
+      ExAws.Request.HttpMock
+      |> stub(:request, fn
+        :get, _url, _body, _headers, _opts -> {:ok, %{status_code: 503}}
+        _method, _url, _body, _headers, _opts -> {:ok, %{status_code: 200}}
+      end)
+
+      get_client(RegistryServer, fn channel ->
+        RegistryService.Stub.publish(channel, BuffServerGrpc.PublishRequest.new(artifact: @dummy_artifact))
+        publish_req = BuffServerGrpc.PublishRequest.new(artifact: File.read!(path))
+
+        assert {:error, %GRPC.RPCError{status: 14}} = RegistryService.Stub.publish(channel, publish_req)
+        assert ["0.1.0"] == BuffServer.Packages.list_versions("osher")
+      end)
+    end
+
+    test "should advertise the compression codecs it reads" do
+      get_client(RegistryServer, fn channel ->
+        assert {:ok, %BuffServerGrpc.GetCapabilitiesResponse{compression: ["gzip", "none"]}} ==
+                 RegistryService.Stub.get_capabilities(channel, BuffServerGrpc.GetCapabilitiesRequest.new())
+      end)
+    end
+
+    test "should validate an uncompressed artifact" do
+      {:ok, [{_, buff_toml}]} =
+        :erl_tar.extract({:binary, @dummy_artifact}, [:memory, :compressed, {:files, ['buff.toml']}])
+
+      path = Path.join(System.tmp_dir!(), "uncompressed_artifact.tar")
+      :ok = :erl_tar.create(String.to_charlist(path), [{'buff.toml', buff_toml}])
+      artifact = File.read!(path)
+      publish_req = BuffServerGrpc.PublishRequest.new(artifact: artifact, validate_only: true)
+
+      get_client(RegistryServer, fn channel ->
+        assert {:ok, %PublishResponse{result: true}} == RegistryService.Stub.publish(channel, publish_req)
+      end)
+    end
+
     test "should return error for an existing artifact" do
       publish_req = BuffServerGrpc.PublishRequest.new(artifact: @dummy_artifact)
 
@@ -35,7 +279,7 @@ defmodule BuffServerGrpc.BuffRegistryTest do
       get_client(RegistryServer, fn channel ->
         RegistryService.Stub.publish(channel, publish_req)
 
-        assert {:error, %GRPC.RPCError{message: "Internal Server Error", status: 2}} ==
+        assert {:error, %GRPC.RPCError{message: "osher 0.1.0 is already published", status: 6}} ==
                  RegistryService.Stub.publish(channel, publish_req)
       end)
     end
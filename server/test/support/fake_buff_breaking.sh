@@ -0,0 +1,6 @@
+#!/bin/sh
+# Stands in for `buff check-publish`, reporting one breaking change.
+cat <<'JSON'
+{"baseline": "0.1.0", "violations": [{"rule": "FIELD_TYPE_CHANGED", "path": "acme.v1.Order.id", "message": "type changed from `string` to `int64`"}]}
+JSON
+exit 1